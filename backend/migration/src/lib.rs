@@ -13,6 +13,23 @@ mod m20250609_200736_add_recipe_share_table;
 mod m20250629_113335_recipe_add_visibility_column;
 mod m20250629_115347_recipe_visibilit_default_value;
 mod m20250629_120028_set_recipe_visibility_non_null;
+mod m20261019_090000_ingredient_catalog;
+mod m20261019_091500_ingredient_search_index;
+mod m20261019_093000_ingredient_categories;
+mod m20261019_094500_ingredient_dietary_flags;
+mod m20261019_100000_recipe_ingredient_lines;
+mod m20261019_103000_recipe_durations;
+mod m20261019_110000_recipe_metadata;
+mod m20261019_113000_favorites_and_collections;
+mod m20261019_120000_recipe_views;
+mod m20261019_123000_voting_sessions;
+mod m20261019_130000_swipe_sessions;
+mod m20261019_133000_meal_plan;
+mod m20261019_140000_account_deletion;
+mod m20261019_143000_notifications;
+mod m20261019_150000_recipe_cooks;
+mod m20261019_153000_invites;
+mod m20261019_160000_user_profiles;
 
 pub struct Migrator;
 
@@ -33,6 +50,23 @@ impl MigratorTrait for Migrator {
             Box::new(m20250629_113335_recipe_add_visibility_column::Migration),
             Box::new(m20250629_115347_recipe_visibilit_default_value::Migration),
             Box::new(m20250629_120028_set_recipe_visibility_non_null::Migration),
+            Box::new(m20261019_090000_ingredient_catalog::Migration),
+            Box::new(m20261019_091500_ingredient_search_index::Migration),
+            Box::new(m20261019_093000_ingredient_categories::Migration),
+            Box::new(m20261019_094500_ingredient_dietary_flags::Migration),
+            Box::new(m20261019_100000_recipe_ingredient_lines::Migration),
+            Box::new(m20261019_103000_recipe_durations::Migration),
+            Box::new(m20261019_110000_recipe_metadata::Migration),
+            Box::new(m20261019_113000_favorites_and_collections::Migration),
+            Box::new(m20261019_120000_recipe_views::Migration),
+            Box::new(m20261019_123000_voting_sessions::Migration),
+            Box::new(m20261019_130000_swipe_sessions::Migration),
+            Box::new(m20261019_133000_meal_plan::Migration),
+            Box::new(m20261019_140000_account_deletion::Migration),
+            Box::new(m20261019_143000_notifications::Migration),
+            Box::new(m20261019_150000_recipe_cooks::Migration),
+            Box::new(m20261019_153000_invites::Migration),
+            Box::new(m20261019_160000_user_profiles::Migration),
         ]
     }
 }
@@ -45,4 +45,9 @@ pub enum Users {
     Email,
     Password,
     Name,
+    DisplayName,
+    Bio,
+    Avatar,
+    Picture,
+    PreferredUnits,
 }
@@ -71,6 +71,15 @@ pub enum Recipes {
     Servings,
     PrepTime,
     BakingTime,
+    PrepMinutes,
+    CookingMinutes,
+    BakingMinutes,
+    RestingMinutes,
+    SourceUrl,
+    SourceName,
+    Cuisine,
+    Difficulty,
+    YieldUnit,
     CreatedAt,
     UpdatedAt,
     Visibility,
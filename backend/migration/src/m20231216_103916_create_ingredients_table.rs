@@ -51,4 +51,9 @@ pub enum Ingredients {
     Id,
     Name,
     UserId,
+    NormalizedName,
+    Category,
+    Allergens,
+    Vegetarian,
+    Vegan,
 }
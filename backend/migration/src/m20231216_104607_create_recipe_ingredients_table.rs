@@ -65,4 +65,9 @@ pub enum RecipeIngredients {
     IngredientId,
     Unit,
     Amount,
+    Id,
+    Position,
+    Note,
+    Optional,
+    GroupName,
 }
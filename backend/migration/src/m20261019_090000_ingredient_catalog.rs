@@ -0,0 +1,161 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20231216_103916_create_ingredients_table::Ingredients;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ingredients::Table)
+                    .add_column(ColumnDef::new(Ingredients::NormalizedName).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        // Everything that exists today was globally unique and shared between users, so it becomes
+        // the global catalog. Ingredients created from now on are private until an admin merges
+        // or promotes them.
+        db.execute_unprepared(
+            "UPDATE ingredients SET normalized_name = lower(regexp_replace(btrim(name), '\\s+', ' ', 'g'))",
+        )
+        .await?;
+
+        // The names were unique with case mattering, so "Tomato" and "tomato" may both exist. They
+        // are merged into the one with the lowest id before the catalog can be unique on the
+        // normalized name. A recipe that used more than one of them keeps only the first.
+        db.execute_unprepared(
+            "WITH canonical AS (
+                SELECT id, min(id) OVER (PARTITION BY normalized_name) AS keep_id FROM ingredients
+            )
+            DELETE FROM recipe_ingredients ri
+            USING canonical c, recipe_ingredients other, canonical oc
+            WHERE ri.ingredient_id = c.id
+                AND other.recipe_id = ri.recipe_id
+                AND other.ingredient_id = oc.id
+                AND oc.keep_id = c.keep_id
+                AND other.ingredient_id < ri.ingredient_id",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "WITH canonical AS (
+                SELECT id, min(id) OVER (PARTITION BY normalized_name) AS keep_id FROM ingredients
+            )
+            UPDATE recipe_ingredients ri SET ingredient_id = c.keep_id
+            FROM canonical c
+            WHERE ri.ingredient_id = c.id AND c.id <> c.keep_id",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "DELETE FROM ingredients i
+            USING ingredients keep
+            WHERE keep.normalized_name = i.normalized_name AND keep.id < i.id",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "ALTER TABLE ingredients DROP CONSTRAINT IF EXISTS ingredients_name_key",
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ingredients::Table)
+                    .modify_column(ColumnDef::new(Ingredients::NormalizedName).not_null())
+                    .modify_column(ColumnDef::new(Ingredients::UserId).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        db.execute_unprepared("UPDATE ingredients SET user_id = NULL")
+            .await?;
+
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX unique_global_ingredient
+                ON ingredients (normalized_name)
+                WHERE user_id IS NULL",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX unique_private_ingredient
+                ON ingredients (user_id, normalized_name)
+                WHERE user_id IS NOT NULL",
+        )
+        .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(IngredientAliases::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IngredientAliases::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(IngredientAliases::IngredientId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-ingredient_aliases-ingredient_id")
+                            .from(IngredientAliases::Table, IngredientAliases::IngredientId)
+                            .to(Ingredients::Table, Ingredients::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(IngredientAliases::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(IngredientAliases::NormalizedName)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IngredientAliases::Table).to_owned())
+            .await?;
+
+        let db = manager.get_connection();
+        db.execute_unprepared("DROP INDEX IF EXISTS unique_private_ingredient")
+            .await?;
+        db.execute_unprepared("DROP INDEX IF EXISTS unique_global_ingredient")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ingredients::Table)
+                    .drop_column(Ingredients::NormalizedName)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum IngredientAliases {
+    Table,
+    Id,
+    IngredientId,
+    Name,
+    NormalizedName,
+}
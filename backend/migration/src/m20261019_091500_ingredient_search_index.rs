@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared("CREATE EXTENSION IF NOT EXISTS pg_trgm")
+            .await?;
+
+        db.execute_unprepared(
+            "CREATE INDEX ingredients_normalized_name_trgm
+                ON ingredients
+                USING GIN (normalized_name gin_trgm_ops)",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "CREATE INDEX ingredient_aliases_normalized_name_trgm
+                ON ingredient_aliases
+                USING GIN (normalized_name gin_trgm_ops)",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared("DROP INDEX IF EXISTS ingredient_aliases_normalized_name_trgm")
+            .await?;
+
+        db.execute_unprepared("DROP INDEX IF EXISTS ingredients_normalized_name_trgm")
+            .await?;
+
+        Ok(())
+    }
+}
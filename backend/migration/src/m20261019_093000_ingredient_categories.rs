@@ -0,0 +1,187 @@
+use sea_orm_migration::{prelude::*, sea_query::extension::postgres::Type};
+
+use crate::{
+    m20220101_000001_create_users_table::Users,
+    m20231216_103916_create_ingredients_table::Ingredients,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(IngredientCategory::Table)
+                    .values([
+                        IngredientCategory::Produce,
+                        IngredientCategory::Dairy,
+                        IngredientCategory::Meat,
+                        IngredientCategory::Seafood,
+                        IngredientCategory::Bakery,
+                        IngredientCategory::DryGoods,
+                        IngredientCategory::Spices,
+                        IngredientCategory::Frozen,
+                        IngredientCategory::Beverages,
+                        IngredientCategory::Other,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ingredients::Table)
+                    .add_column(
+                        ColumnDef::new(Ingredients::Category)
+                            .custom(IngredientCategory::Table)
+                            .not_null()
+                            .default(Expr::cust("'other'")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserIngredientCategories::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserIngredientCategories::UserId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserIngredientCategories::IngredientId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserIngredientCategories::Category)
+                            .custom(IngredientCategory::Table)
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-user_ingredient_categories-user_id")
+                            .from(
+                                UserIngredientCategories::Table,
+                                UserIngredientCategories::UserId,
+                            )
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-user_ingredient_categories-ingredient_id")
+                            .from(
+                                UserIngredientCategories::Table,
+                                UserIngredientCategories::IngredientId,
+                            )
+                            .to(Ingredients::Table, Ingredients::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        sea_query::Index::create()
+                            .col(UserIngredientCategories::UserId)
+                            .col(UserIngredientCategories::IngredientId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserAisleOrders::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(UserAisleOrders::UserId).integer().not_null())
+                    .col(
+                        ColumnDef::new(UserAisleOrders::Category)
+                            .custom(IngredientCategory::Table)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserAisleOrders::Position)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-user_aisle_orders-user_id")
+                            .from(UserAisleOrders::Table, UserAisleOrders::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        sea_query::Index::create()
+                            .col(UserAisleOrders::UserId)
+                            .col(UserAisleOrders::Category),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserAisleOrders::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(UserIngredientCategories::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ingredients::Table)
+                    .drop_column(Ingredients::Category)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(IngredientCategory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum IngredientCategory {
+    Table,
+    Produce,
+    Dairy,
+    Meat,
+    Seafood,
+    Bakery,
+    DryGoods,
+    Spices,
+    Frozen,
+    Beverages,
+    Other,
+}
+
+#[derive(DeriveIden)]
+pub enum UserIngredientCategories {
+    Table,
+    UserId,
+    IngredientId,
+    Category,
+}
+
+#[derive(DeriveIden)]
+pub enum UserAisleOrders {
+    Table,
+    UserId,
+    Category,
+    Position,
+}
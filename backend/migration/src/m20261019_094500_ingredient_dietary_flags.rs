@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20231216_103916_create_ingredients_table::Ingredients;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Nothing is known about the existing ingredients, so they are not considered vegetarian or
+        // vegan until someone has flagged them
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ingredients::Table)
+                    .add_column(
+                        ColumnDef::new(Ingredients::Allergens)
+                            .array(ColumnType::Text)
+                            .not_null()
+                            .default(Expr::cust("'{}'")),
+                    )
+                    .add_column(
+                        ColumnDef::new(Ingredients::Vegetarian)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .add_column(
+                        ColumnDef::new(Ingredients::Vegan)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ingredients::Table)
+                    .drop_column(Ingredients::Allergens)
+                    .drop_column(Ingredients::Vegetarian)
+                    .drop_column(Ingredients::Vegan)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,105 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20231216_104607_create_recipe_ingredients_table::RecipeIngredients;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // The same ingredient can be used several times in a recipe now, e.g. in different groups,
+        // so the lines get their own id instead of using the recipe and ingredient as the key
+        db.execute_unprepared(
+            "ALTER TABLE recipe_ingredients DROP CONSTRAINT IF EXISTS recipe_ingredients_pkey",
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RecipeIngredients::Table)
+                    .add_column(
+                        ColumnDef::new(RecipeIngredients::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .add_column(
+                        ColumnDef::new(RecipeIngredients::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .add_column(ColumnDef::new(RecipeIngredients::Note).string())
+                    .add_column(
+                        ColumnDef::new(RecipeIngredients::Optional)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .add_column(ColumnDef::new(RecipeIngredients::GroupName).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Keep the order the lines were inserted in for the existing recipes
+        db.execute_unprepared(
+            "UPDATE recipe_ingredients ri
+                SET position = ordered.position
+                FROM (
+                    SELECT id, row_number() OVER (PARTITION BY recipe_id ORDER BY id) - 1 AS position
+                    FROM recipe_ingredients
+                ) ordered
+                WHERE ordered.id = ri.id",
+        )
+        .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-recipe_ingredients-recipe_id-position")
+                    .table(RecipeIngredients::Table)
+                    .col(RecipeIngredients::RecipeId)
+                    .col(RecipeIngredients::Position)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            "DELETE FROM recipe_ingredients a
+                USING recipe_ingredients b
+                WHERE a.recipe_id = b.recipe_id
+                AND a.ingredient_id = b.ingredient_id
+                AND a.id > b.id",
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RecipeIngredients::Table)
+                    .drop_column(RecipeIngredients::Id)
+                    .drop_column(RecipeIngredients::Position)
+                    .drop_column(RecipeIngredients::Note)
+                    .drop_column(RecipeIngredients::Optional)
+                    .drop_column(RecipeIngredients::GroupName)
+                    .to_owned(),
+            )
+            .await?;
+
+        db.execute_unprepared(
+            "ALTER TABLE recipe_ingredients ADD PRIMARY KEY (recipe_id, ingredient_id)",
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20231216_004843_create_recipes_table::Recipes;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // The times were stored as a time of day, which can't be longer than 24 hours. Store the
+        // number of minutes instead
+        db.execute_unprepared(
+            "ALTER TABLE recipes
+                ALTER COLUMN prep_time TYPE integer
+                    USING (extract(epoch FROM prep_time) / 60)::integer,
+                ALTER COLUMN baking_time TYPE integer
+                    USING (extract(epoch FROM baking_time) / 60)::integer",
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Recipes::Table)
+                    .rename_column(Recipes::PrepTime, Recipes::PrepMinutes)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Recipes::Table)
+                    .rename_column(Recipes::BakingTime, Recipes::BakingMinutes)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Recipes::Table)
+                    .add_column(ColumnDef::new(Recipes::CookingMinutes).integer())
+                    .add_column(ColumnDef::new(Recipes::RestingMinutes).integer())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Recipes::Table)
+                    .drop_column(Recipes::CookingMinutes)
+                    .drop_column(Recipes::RestingMinutes)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Recipes::Table)
+                    .rename_column(Recipes::PrepMinutes, Recipes::PrepTime)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Recipes::Table)
+                    .rename_column(Recipes::BakingMinutes, Recipes::BakingTime)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Anything longer than a day wraps around, since that is all a time can hold
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE recipes
+                    ALTER COLUMN prep_time TYPE time
+                        USING make_interval(mins => prep_time % 1440)::time,
+                    ALTER COLUMN baking_time TYPE time
+                        USING make_interval(mins => baking_time % 1440)::time",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
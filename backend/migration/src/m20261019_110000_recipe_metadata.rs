@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+use crate::m20231216_004843_create_recipes_table::Recipes;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(RecipeDifficulty::Table)
+                    .values([
+                        RecipeDifficulty::Easy,
+                        RecipeDifficulty::Medium,
+                        RecipeDifficulty::Hard,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Recipes::Table)
+                    .add_column(ColumnDef::new(Recipes::SourceUrl).string())
+                    .add_column(ColumnDef::new(Recipes::SourceName).string())
+                    .add_column(ColumnDef::new(Recipes::Cuisine).string())
+                    .add_column(ColumnDef::new(Recipes::Difficulty).custom(RecipeDifficulty::Table))
+                    .add_column(ColumnDef::new(Recipes::YieldUnit).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-recipes-cuisine")
+                    .table(Recipes::Table)
+                    .col(Recipes::Cuisine)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-recipes-cuisine")
+                    .table(Recipes::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Recipes::Table)
+                    .drop_column(Recipes::SourceUrl)
+                    .drop_column(Recipes::SourceName)
+                    .drop_column(Recipes::Cuisine)
+                    .drop_column(Recipes::Difficulty)
+                    .drop_column(Recipes::YieldUnit)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(RecipeDifficulty::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum RecipeDifficulty {
+    Table,
+    Easy,
+    Medium,
+    Hard,
+}
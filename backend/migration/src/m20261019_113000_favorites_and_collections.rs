@@ -0,0 +1,187 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20220101_000001_create_users_table::Users, m20231216_004843_create_recipes_table::Recipes,
+    m20250629_113335_recipe_add_visibility_column::RecipeVisibility,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Favorites::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Favorites::UserId).integer().not_null())
+                    .col(ColumnDef::new(Favorites::RecipeId).integer().not_null())
+                    .col(
+                        ColumnDef::new(Favorites::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-favorites-user_id")
+                            .from(Favorites::Table, Favorites::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-favorites-recipe_id")
+                            .from(Favorites::Table, Favorites::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        sea_query::Index::create()
+                            .col(Favorites::UserId)
+                            .col(Favorites::RecipeId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Collections::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Collections::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Collections::UserId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-collections-user_id")
+                            .from(Collections::Table, Collections::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Collections::Name).string().not_null())
+                    .col(ColumnDef::new(Collections::Description).string())
+                    .col(
+                        ColumnDef::new(Collections::Visibility)
+                            .custom(RecipeVisibility::Table)
+                            .not_null()
+                            .default(Expr::cust("'friends'")),
+                    )
+                    .col(
+                        ColumnDef::new(Collections::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Collections::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .index(
+                        Index::create()
+                            .name("unique_collection_name")
+                            .col(Collections::UserId)
+                            .col(Collections::Name)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CollectionRecipes::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CollectionRecipes::CollectionId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionRecipes::RecipeId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CollectionRecipes::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-collection_recipes-collection_id")
+                            .from(CollectionRecipes::Table, CollectionRecipes::CollectionId)
+                            .to(Collections::Table, Collections::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-collection_recipes-recipe_id")
+                            .from(CollectionRecipes::Table, CollectionRecipes::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        sea_query::Index::create()
+                            .col(CollectionRecipes::CollectionId)
+                            .col(CollectionRecipes::RecipeId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CollectionRecipes::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Collections::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Favorites::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Favorites {
+    Table,
+    UserId,
+    RecipeId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum Collections {
+    Table,
+    Id,
+    UserId,
+    Name,
+    Description,
+    Visibility,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum CollectionRecipes {
+    Table,
+    CollectionId,
+    RecipeId,
+    CreatedAt,
+}
@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20220101_000001_create_users_table::Users, m20231216_004843_create_recipes_table::Recipes,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecipeViews::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RecipeViews::UserId).integer().not_null())
+                    .col(ColumnDef::new(RecipeViews::RecipeId).integer().not_null())
+                    .col(
+                        ColumnDef::new(RecipeViews::ViewCount)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(RecipeViews::LastViewedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-recipe_views-user_id")
+                            .from(RecipeViews::Table, RecipeViews::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-recipe_views-recipe_id")
+                            .from(RecipeViews::Table, RecipeViews::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        sea_query::Index::create()
+                            .col(RecipeViews::UserId)
+                            .col(RecipeViews::RecipeId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RecipeViews::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum RecipeViews {
+    Table,
+    UserId,
+    RecipeId,
+    ViewCount,
+    LastViewedAt,
+}
@@ -0,0 +1,202 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20220101_000001_create_users_table::Users, m20231216_004843_create_recipes_table::Recipes,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(VotingSessions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VotingSessions::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(VotingSessions::OwnerId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-voting_sessions-owner_id")
+                            .from(VotingSessions::Table, VotingSessions::OwnerId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(VotingSessions::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(VotingSessions::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(VotingSessions::ClosedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(VotingSessions::WinnerRecipeId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-voting_sessions-winner_recipe_id")
+                            .from(VotingSessions::Table, VotingSessions::WinnerRecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(VotingSessionRecipes::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VotingSessionRecipes::SessionId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VotingSessionRecipes::RecipeId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VotingSessionRecipes::Position)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-voting_session_recipes-session_id")
+                            .from(VotingSessionRecipes::Table, VotingSessionRecipes::SessionId)
+                            .to(VotingSessions::Table, VotingSessions::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-voting_session_recipes-recipe_id")
+                            .from(VotingSessionRecipes::Table, VotingSessionRecipes::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        sea_query::Index::create()
+                            .col(VotingSessionRecipes::SessionId)
+                            .col(VotingSessionRecipes::RecipeId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The vote is stored on the participant, so everyone has at most one vote per session
+        manager
+            .create_table(
+                Table::create()
+                    .table(VotingSessionParticipants::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VotingSessionParticipants::SessionId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VotingSessionParticipants::UserId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(VotingSessionParticipants::RecipeId).integer())
+                    .col(
+                        ColumnDef::new(VotingSessionParticipants::VotedAt)
+                            .timestamp_with_time_zone(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-voting_session_participants-session_id")
+                            .from(
+                                VotingSessionParticipants::Table,
+                                VotingSessionParticipants::SessionId,
+                            )
+                            .to(VotingSessions::Table, VotingSessions::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-voting_session_participants-user_id")
+                            .from(
+                                VotingSessionParticipants::Table,
+                                VotingSessionParticipants::UserId,
+                            )
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-voting_session_participants-recipe_id")
+                            .from(
+                                VotingSessionParticipants::Table,
+                                VotingSessionParticipants::RecipeId,
+                            )
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .primary_key(
+                        sea_query::Index::create()
+                            .col(VotingSessionParticipants::SessionId)
+                            .col(VotingSessionParticipants::UserId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(VotingSessionParticipants::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(VotingSessionRecipes::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(VotingSessions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum VotingSessions {
+    Table,
+    Id,
+    OwnerId,
+    Name,
+    CreatedAt,
+    ClosedAt,
+    WinnerRecipeId,
+}
+
+#[derive(DeriveIden)]
+pub enum VotingSessionRecipes {
+    Table,
+    SessionId,
+    RecipeId,
+    Position,
+}
+
+#[derive(DeriveIden)]
+pub enum VotingSessionParticipants {
+    Table,
+    SessionId,
+    UserId,
+    RecipeId,
+    VotedAt,
+}
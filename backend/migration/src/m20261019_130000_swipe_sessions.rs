@@ -0,0 +1,226 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20220101_000001_create_users_table::Users, m20231216_004843_create_recipes_table::Recipes,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SwipeSessions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SwipeSessions::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SwipeSessions::OwnerId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-swipe_sessions-owner_id")
+                            .from(SwipeSessions::Table, SwipeSessions::OwnerId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(SwipeSessions::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(SwipeSessions::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SwipeSessionParticipants::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SwipeSessionParticipants::SessionId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SwipeSessionParticipants::UserId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-swipe_session_participants-session_id")
+                            .from(
+                                SwipeSessionParticipants::Table,
+                                SwipeSessionParticipants::SessionId,
+                            )
+                            .to(SwipeSessions::Table, SwipeSessions::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-swipe_session_participants-user_id")
+                            .from(
+                                SwipeSessionParticipants::Table,
+                                SwipeSessionParticipants::UserId,
+                            )
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        sea_query::Index::create()
+                            .col(SwipeSessionParticipants::SessionId)
+                            .col(SwipeSessionParticipants::UserId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Swipes::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Swipes::SessionId).integer().not_null())
+                    .col(ColumnDef::new(Swipes::UserId).integer().not_null())
+                    .col(ColumnDef::new(Swipes::RecipeId).integer().not_null())
+                    .col(ColumnDef::new(Swipes::Liked).boolean().not_null())
+                    .col(
+                        ColumnDef::new(Swipes::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-swipes-session_id")
+                            .from(Swipes::Table, Swipes::SessionId)
+                            .to(SwipeSessions::Table, SwipeSessions::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-swipes-user_id")
+                            .from(Swipes::Table, Swipes::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-swipes-recipe_id")
+                            .from(Swipes::Table, Swipes::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        sea_query::Index::create()
+                            .col(Swipes::SessionId)
+                            .col(Swipes::UserId)
+                            .col(Swipes::RecipeId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SwipeMatches::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(SwipeMatches::SessionId).integer().not_null())
+                    .col(ColumnDef::new(SwipeMatches::RecipeId).integer().not_null())
+                    .col(
+                        ColumnDef::new(SwipeMatches::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-swipe_matches-session_id")
+                            .from(SwipeMatches::Table, SwipeMatches::SessionId)
+                            .to(SwipeSessions::Table, SwipeSessions::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-swipe_matches-recipe_id")
+                            .from(SwipeMatches::Table, SwipeMatches::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        sea_query::Index::create()
+                            .col(SwipeMatches::SessionId)
+                            .col(SwipeMatches::RecipeId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SwipeMatches::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Swipes::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(SwipeSessionParticipants::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(SwipeSessions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum SwipeSessions {
+    Table,
+    Id,
+    OwnerId,
+    Name,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum SwipeSessionParticipants {
+    Table,
+    SessionId,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+pub enum Swipes {
+    Table,
+    SessionId,
+    UserId,
+    RecipeId,
+    Liked,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum SwipeMatches {
+    Table,
+    SessionId,
+    RecipeId,
+    CreatedAt,
+}
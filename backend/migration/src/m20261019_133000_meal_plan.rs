@@ -0,0 +1,130 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20220101_000001_create_users_table::Users, m20231216_004843_create_recipes_table::Recipes,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PlannedMeals::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PlannedMeals::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PlannedMeals::UserId).integer().not_null())
+                    .col(ColumnDef::new(PlannedMeals::RecipeId).integer().not_null())
+                    .col(
+                        ColumnDef::new(PlannedMeals::EatAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PlannedMeals::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-planned_meals-user_id")
+                            .from(PlannedMeals::Table, PlannedMeals::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-planned_meals-recipe_id")
+                            .from(PlannedMeals::Table, PlannedMeals::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-planned_meals-user_id-eat_at")
+                    .table(PlannedMeals::Table)
+                    .col(PlannedMeals::UserId)
+                    .col(PlannedMeals::EatAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // The token is the only thing protecting the feed, since calendar apps can't log in
+        manager
+            .create_table(
+                Table::create()
+                    .table(CalendarFeeds::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CalendarFeeds::UserId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CalendarFeeds::Token)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CalendarFeeds::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-calendar_feeds-user_id")
+                            .from(CalendarFeeds::Table, CalendarFeeds::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CalendarFeeds::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PlannedMeals::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum PlannedMeals {
+    Table,
+    Id,
+    UserId,
+    RecipeId,
+    EatAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum CalendarFeeds {
+    Table,
+    UserId,
+    Token,
+    CreatedAt,
+}
@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20220101_000001_create_users_table::Users, m20231216_004843_create_recipes_table::Recipes,
+    m20231216_103916_create_ingredients_table::Ingredients,
+    m20250609_144836_add_friends_table::Friendships,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// The foreign keys to users were created without an action, which stops users from being deleted.
+// Ingredients that are left when a user is deleted are used by other users, so they are kept as a
+// part of the catalog
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (name, table, column, action) in foreign_keys() {
+            replace_foreign_key(manager, name, table, column, action).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (name, table, column, _) in foreign_keys() {
+            replace_foreign_key(manager, name, table, column, ForeignKeyAction::NoAction).await?;
+        }
+        Ok(())
+    }
+}
+
+fn foreign_keys() -> [(&'static str, DynIden, DynIden, ForeignKeyAction); 4] {
+    [
+        (
+            "fk-recipe-user_id",
+            Recipes::Table.into_iden(),
+            Recipes::UserId.into_iden(),
+            ForeignKeyAction::Cascade,
+        ),
+        (
+            "fk-ingredients-user_id",
+            Ingredients::Table.into_iden(),
+            Ingredients::UserId.into_iden(),
+            ForeignKeyAction::SetNull,
+        ),
+        (
+            "fk-request-user_id",
+            Friendships::Table.into_iden(),
+            Friendships::RequesterId.into_iden(),
+            ForeignKeyAction::Cascade,
+        ),
+        (
+            "fk-recipient-user_id",
+            Friendships::Table.into_iden(),
+            Friendships::RecipientId.into_iden(),
+            ForeignKeyAction::Cascade,
+        ),
+    ]
+}
+
+async fn replace_foreign_key(
+    manager: &SchemaManager<'_>,
+    name: &str,
+    table: DynIden,
+    column: DynIden,
+    action: ForeignKeyAction,
+) -> Result<(), DbErr> {
+    manager
+        .drop_foreign_key(
+            ForeignKey::drop()
+                .name(name)
+                .table(table.clone())
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_foreign_key(
+            ForeignKey::create()
+                .name(name)
+                .from(table, column)
+                .to(Users::Table, Users::Id)
+                .on_delete(action)
+                .to_owned(),
+        )
+        .await
+}
@@ -0,0 +1,133 @@
+use sea_orm_migration::{prelude::*, sea_query::extension::postgres::Type};
+
+use crate::{
+    m20220101_000001_create_users_table::Users, m20231216_004843_create_recipes_table::Recipes,
+    m20261019_123000_voting_sessions::VotingSessions,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(NotificationKind::Table)
+                    .values([
+                        NotificationKind::FriendRequest,
+                        NotificationKind::FriendRequestAccepted,
+                        NotificationKind::FriendRecipe,
+                        NotificationKind::VotingSessionInvite,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // The user is the one who gets the notification, and the actor the one who caused it. What
+        // it is about is in the column for the kind, if any
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notifications::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Notifications::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Notifications::UserId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notifications-user_id")
+                            .from(Notifications::Table, Notifications::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(Notifications::Kind)
+                            .custom(NotificationKind::Table)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Notifications::ActorId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notifications-actor_id")
+                            .from(Notifications::Table, Notifications::ActorId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Notifications::RecipeId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notifications-recipe_id")
+                            .from(Notifications::Table, Notifications::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Notifications::VotingSessionId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-notifications-voting_session_id")
+                            .from(Notifications::Table, Notifications::VotingSessionId)
+                            .to(VotingSessions::Table, VotingSessions::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(Notifications::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Notifications::ReadAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-notifications-user_id-created_at")
+                    .table(Notifications::Table)
+                    .col(Notifications::UserId)
+                    .col(Notifications::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Notifications::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(NotificationKind::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Notifications {
+    Table,
+    Id,
+    UserId,
+    Kind,
+    ActorId,
+    RecipeId,
+    VotingSessionId,
+    CreatedAt,
+    ReadAt,
+}
+
+#[derive(DeriveIden)]
+pub enum NotificationKind {
+    Table,
+    FriendRequest,
+    FriendRequestAccepted,
+    FriendRecipe,
+    VotingSessionInvite,
+}
@@ -0,0 +1,80 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20220101_000001_create_users_table::Users, m20231216_004843_create_recipes_table::Recipes,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// A user can cook a recipe many times, and rate it each time
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecipeCooks::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RecipeCooks::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(RecipeCooks::UserId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-recipe_cooks-user_id")
+                            .from(RecipeCooks::Table, RecipeCooks::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(RecipeCooks::RecipeId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-recipe_cooks-recipe_id")
+                            .from(RecipeCooks::Table, RecipeCooks::RecipeId)
+                            .to(Recipes::Table, Recipes::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(RecipeCooks::Rating).integer())
+                    .col(
+                        ColumnDef::new(RecipeCooks::CookedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-recipe_cooks-user_id-cooked_at")
+                    .table(RecipeCooks::Table)
+                    .col(RecipeCooks::UserId)
+                    .col(RecipeCooks::CookedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RecipeCooks::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum RecipeCooks {
+    Table,
+    Id,
+    UserId,
+    RecipeId,
+    Rating,
+    CookedAt,
+}
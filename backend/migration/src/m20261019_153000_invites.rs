@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20220101_000001_create_users_table::Users;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// An invite lets someone register without being in the list of allowed emails. The token is
+// in the link sent to them, and can only be used once before it expires
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Invites::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Invites::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Invites::InviterId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-invites-inviter_id")
+                            .from(Invites::Table, Invites::InviterId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Invites::Email).string().not_null())
+                    .col(
+                        ColumnDef::new(Invites::Token)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Invites::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Invites::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Invites::AcceptedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Invites::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Invites {
+    Table,
+    Id,
+    InviterId,
+    Email,
+    Token,
+    CreatedAt,
+    ExpiresAt,
+    AcceptedAt,
+}
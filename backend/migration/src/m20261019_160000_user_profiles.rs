@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+use crate::m20220101_000001_create_users_table::Users;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// The avatar is an uploaded image in the storage, and the picture is the one imported from Google.
+// The avatar is shown if the user has uploaded one
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(UnitSystem::Table)
+                    .values([UnitSystem::Metric, UnitSystem::Imperial])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(ColumnDef::new(Users::DisplayName).string())
+                    .add_column(ColumnDef::new(Users::Bio).text())
+                    .add_column(ColumnDef::new(Users::Avatar).uuid())
+                    .add_column(ColumnDef::new(Users::Picture).string())
+                    .add_column(
+                        ColumnDef::new(Users::PreferredUnits)
+                            .custom(UnitSystem::Table)
+                            .not_null()
+                            .default(Expr::val("metric").as_enum(UnitSystem::Table)),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::DisplayName)
+                    .drop_column(Users::Bio)
+                    .drop_column(Users::Avatar)
+                    .drop_column(Users::Picture)
+                    .drop_column(Users::PreferredUnits)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(UnitSystem::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum UnitSystem {
+    Table,
+    Metric,
+    Imperial,
+}
@@ -1,16 +1,21 @@
 use crate::api::allowed_mails;
+use crate::api::invite::{accept_invite, find_invite, has_user_with_email};
 use crate::entities::users::Entity as UserEntity;
 use crate::{auth_backend::AuthSession, entities::users, ApiError};
 use argon2::password_hash::SaltString;
 use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
-use axum::{extract::State, Json};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
 use common::user::{CreateUser, User, UserLogin};
 use hyper::StatusCode;
 use rand::rngs::OsRng;
 use sea_orm::entity::prelude::*;
 use sea_orm::sea_query::OnConflict;
 use sea_orm::ActiveValue::NotSet;
-use sea_orm::Set;
+use sea_orm::{Set, TransactionTrait};
+use serde::Deserialize;
 
 pub fn compute_hash(password: &[u8]) -> String {
     let salt = SaltString::generate(&mut OsRng);
@@ -24,31 +29,61 @@ fn is_test_env() -> bool {
     std::env::var("FOODIE_TEST").is_ok_and(|v| v == "1")
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RegisterQuery {
+    // The token from the link in an invite
+    invite: Option<String>,
+}
+
+// Registers a user. Someone who is invited can register without being in the allowed emails, and
+// becomes friends with whoever invited them
 pub async fn register(
     State(db): State<DatabaseConnection>,
+    Query(query): Query<RegisterQuery>,
     Json(create_user): Json<CreateUser>,
 ) -> Result<Json<User>, ApiError> {
+    let invite = match &query.invite {
+        Some(token) => Some(find_invite(&db, token, &create_user.email).await?),
+        None => None,
+    };
+
     // TODO: Do not hardcode access to login/create user
     // Only hardcode login if not in test env
     let allowed = allowed_mails();
-    if !is_test_env() && !allowed.contains(&create_user.email) {
+    if invite.is_none() && !is_test_env() && !allowed.contains(&create_user.email) {
         return Err(ApiError::UnknownError("Not valid".to_string()));
     }
 
+    // An invite is not a way to set a new password for someone who already has an account
+    if invite.is_some() && has_user_with_email(&db, &create_user.email).await? {
+        return Err(ApiError::ConflictError(
+            "There is already a user with this email".to_string(),
+        ));
+    }
+
+    let tx = db.begin().await?;
+
     let user = users::Entity::insert(users::ActiveModel {
         id: NotSet,
         email: Set(create_user.email),
         password: Set(Some(compute_hash(create_user.password.as_bytes()))),
         name: Set(create_user.name),
+        ..Default::default()
     })
     .on_conflict(
         OnConflict::column(users::Column::Email)
             .update_column(users::Column::Password)
             .to_owned(),
     )
-    .exec_with_returning(&db)
+    .exec_with_returning(&tx)
     .await?;
 
+    if let Some(invite) = invite {
+        accept_invite(&tx, &invite, user.id).await?;
+    }
+
+    tx.commit().await?;
+
     Ok(Json(User {
         id: user.id,
         name: user.name,
@@ -119,7 +154,7 @@ pub async fn get_me(
 
     let user = User {
         id: user_model.id,
-        name: user_model.name,
+        name: user_model.display_name.unwrap_or(user_model.name),
         email: user_model.email,
     };
 
@@ -0,0 +1,463 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use common::{
+    collection::{Collection, CreateCollection, RecipeBookmarks},
+    recipe::Recipe,
+};
+use hyper::StatusCode;
+use itertools::Itertools;
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue::NotSet, ColumnTrait, Condition, ConnectionTrait,
+    DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set, SqlErr,
+};
+
+use crate::{
+    api::{
+        recipe::{_get_recipes, has_access_to_recipe},
+        users::fetch_user_relationships,
+    },
+    app::AppState,
+    auth_backend::AuthSession,
+    entities::{
+        collection_recipes, collections, favorites, recipes,
+        sea_orm_active_enums::{FriendshipStatus, RecipeVisibility},
+    },
+    storage::FoodieStorage,
+    ApiError,
+};
+
+// Gets the recipes the user has marked as favorites, newest first
+pub async fn get_favorites<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+) -> Result<Json<Vec<Recipe>>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+    let has_access = has_access_to_recipe(&state.db, user.id).await?;
+
+    let recipe_ids = favorites::Entity::find()
+        .filter(favorites::Column::UserId.eq(user.id))
+        .order_by_desc(favorites::Column::CreatedAt)
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|it| it.recipe_id)
+        .collect::<Vec<_>>();
+
+    let recipes = find_recipes_in_order(&state.db, has_access, &recipe_ids).await?;
+
+    _get_recipes(recipes, state).await
+}
+
+pub async fn put_favorite(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Path(recipe_id): Path<i32>,
+) -> Result<Json<RecipeBookmarks>, ApiError> {
+    let user = auth.user.unwrap();
+
+    find_accessible_recipe(&db, user.id, recipe_id).await?;
+
+    favorites::Entity::insert(favorites::ActiveModel {
+        user_id: Set(user.id),
+        recipe_id: Set(recipe_id),
+        created_at: NotSet,
+    })
+    .on_conflict(
+        OnConflict::columns([favorites::Column::UserId, favorites::Column::RecipeId])
+            .do_nothing()
+            .to_owned(),
+    )
+    .exec_without_returning(&db)
+    .await?;
+
+    Ok(Json(recipe_bookmarks(&db, user.id, recipe_id).await?))
+}
+
+pub async fn delete_favorite(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Path(recipe_id): Path<i32>,
+) -> Result<Json<RecipeBookmarks>, ApiError> {
+    let user = auth.user.unwrap();
+
+    favorites::Entity::delete_many()
+        .filter(favorites::Column::UserId.eq(user.id))
+        .filter(favorites::Column::RecipeId.eq(recipe_id))
+        .exec(&db)
+        .await?;
+
+    Ok(Json(recipe_bookmarks(&db, user.id, recipe_id).await?))
+}
+
+// Gets if the recipe is a favorite, and which of the user's collections it is in
+pub async fn get_recipe_bookmarks(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Path(recipe_id): Path<i32>,
+) -> Result<Json<RecipeBookmarks>, ApiError> {
+    let user = auth.user.unwrap();
+
+    find_accessible_recipe(&db, user.id, recipe_id).await?;
+
+    Ok(Json(recipe_bookmarks(&db, user.id, recipe_id).await?))
+}
+
+// Gets the user's own collections, and the ones friends have shared
+pub async fn get_collections(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+) -> Result<Json<Vec<Collection>>, ApiError> {
+    let user = auth.user.unwrap();
+    let has_access = has_access_to_collection(&db, user.id).await?;
+
+    let collections = collections::Entity::find()
+        .filter(has_access)
+        .order_by_asc(collections::Column::Name)
+        .all(&db)
+        .await?;
+
+    Ok(Json(to_collections(&db, user.id, collections).await?))
+}
+
+pub async fn get_collection(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Path(collection_id): Path<i32>,
+) -> Result<Json<Collection>, ApiError> {
+    let user = auth.user.unwrap();
+
+    let collection = find_accessible_collection(&db, user.id, collection_id).await?;
+
+    let collection = to_collections(&db, user.id, vec![collection])
+        .await?
+        .remove(0);
+
+    Ok(Json(collection))
+}
+
+// Gets the recipes in a collection, in the order they were added. Recipes in a friend's
+// collection that the user doesn't have access to are left out
+pub async fn get_collection_recipes<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Path(collection_id): Path<i32>,
+) -> Result<Json<Vec<Recipe>>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    find_accessible_collection(&state.db, user.id, collection_id).await?;
+
+    let recipe_ids = collection_recipes::Entity::find()
+        .filter(collection_recipes::Column::CollectionId.eq(collection_id))
+        .order_by_asc(collection_recipes::Column::CreatedAt)
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|it| it.recipe_id)
+        .collect::<Vec<_>>();
+
+    let has_access = has_access_to_recipe(&state.db, user.id).await?;
+    let recipes = find_recipes_in_order(&state.db, has_access, &recipe_ids).await?;
+
+    _get_recipes(recipes, state).await
+}
+
+pub async fn post_collection(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Json(collection): Json<CreateCollection>,
+) -> Result<Json<Collection>, ApiError> {
+    let user = auth.user.unwrap();
+
+    let created_collection = collections::Entity::insert(collections::ActiveModel {
+        id: NotSet,
+        user_id: Set(user.id),
+        name: Set(valid_name(&collection)?),
+        description: Set(collection.description.filter(|it| !it.trim().is_empty())),
+        visibility: Set(collection.visibility.into()),
+        created_at: NotSet,
+        updated_at: NotSet,
+    })
+    .exec_with_returning(&db)
+    .await
+    .map_err(name_conflict)?;
+
+    let collection = to_collections(&db, user.id, vec![created_collection])
+        .await?
+        .remove(0);
+
+    Ok(Json(collection))
+}
+
+pub async fn update_collection(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Path(collection_id): Path<i32>,
+    Json(collection): Json<CreateCollection>,
+) -> Result<Json<Collection>, ApiError> {
+    let user = auth.user.unwrap();
+
+    find_own_collection(&db, user.id, collection_id).await?;
+
+    let updated_collection = collections::Entity::update(collections::ActiveModel {
+        id: Set(collection_id),
+        user_id: NotSet,
+        name: Set(valid_name(&collection)?),
+        description: Set(collection.description.filter(|it| !it.trim().is_empty())),
+        visibility: Set(collection.visibility.into()),
+        created_at: NotSet,
+        updated_at: Set(chrono::Utc::now().into()),
+    })
+    .exec(&db)
+    .await
+    .map_err(name_conflict)?;
+
+    let collection = to_collections(&db, user.id, vec![updated_collection])
+        .await?
+        .remove(0);
+
+    Ok(Json(collection))
+}
+
+pub async fn delete_collection(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Path(collection_id): Path<i32>,
+) -> Result<Json<i32>, ApiError> {
+    let user = auth.user.unwrap();
+
+    find_own_collection(&db, user.id, collection_id).await?;
+
+    collections::Entity::delete_by_id(collection_id)
+        .exec(&db)
+        .await?;
+
+    Ok(Json(collection_id))
+}
+
+// Adds a recipe to one of the user's collections. The user needs access to the recipe
+pub async fn put_collection_recipe(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Path((collection_id, recipe_id)): Path<(i32, i32)>,
+) -> Result<Json<RecipeBookmarks>, ApiError> {
+    let user = auth.user.unwrap();
+
+    find_own_collection(&db, user.id, collection_id).await?;
+    find_accessible_recipe(&db, user.id, recipe_id).await?;
+
+    collection_recipes::Entity::insert(collection_recipes::ActiveModel {
+        collection_id: Set(collection_id),
+        recipe_id: Set(recipe_id),
+        created_at: NotSet,
+    })
+    .on_conflict(
+        OnConflict::columns([
+            collection_recipes::Column::CollectionId,
+            collection_recipes::Column::RecipeId,
+        ])
+        .do_nothing()
+        .to_owned(),
+    )
+    .exec_without_returning(&db)
+    .await?;
+
+    Ok(Json(recipe_bookmarks(&db, user.id, recipe_id).await?))
+}
+
+pub async fn delete_collection_recipe(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Path((collection_id, recipe_id)): Path<(i32, i32)>,
+) -> Result<Json<RecipeBookmarks>, ApiError> {
+    let user = auth.user.unwrap();
+
+    find_own_collection(&db, user.id, collection_id).await?;
+
+    collection_recipes::Entity::delete_many()
+        .filter(collection_recipes::Column::CollectionId.eq(collection_id))
+        .filter(collection_recipes::Column::RecipeId.eq(recipe_id))
+        .exec(&db)
+        .await?;
+
+    Ok(Json(recipe_bookmarks(&db, user.id, recipe_id).await?))
+}
+
+pub async fn has_access_to_collection<C>(db: &C, user_id: i32) -> Result<Condition, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let friends_ids = fetch_user_relationships(db, user_id, "")
+        .await?
+        .into_iter()
+        .filter(|it| it.status == Some(FriendshipStatus::Accepted.into()))
+        .map(|it| it.id)
+        .collect::<Vec<_>>();
+
+    Ok(Condition::any()
+        .add(collections::Column::UserId.eq(user_id))
+        .add(
+            Condition::all()
+                .add(collections::Column::UserId.is_in(friends_ids))
+                .add(collections::Column::Visibility.eq(RecipeVisibility::Friends)),
+        ))
+}
+
+async fn find_accessible_collection(
+    db: &DatabaseConnection,
+    user_id: i32,
+    collection_id: i32,
+) -> Result<collections::Model, ApiError> {
+    let has_access = has_access_to_collection(db, user_id).await?;
+    collections::Entity::find_by_id(collection_id)
+        .filter(has_access)
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)
+}
+
+// Friends can see a shared collection, but only the owner can change it
+async fn find_own_collection(
+    db: &DatabaseConnection,
+    user_id: i32,
+    collection_id: i32,
+) -> Result<collections::Model, ApiError> {
+    let collection = find_accessible_collection(db, user_id, collection_id).await?;
+    if collection.user_id != user_id {
+        return Err(ApiError::StatusCode(
+            StatusCode::FORBIDDEN,
+            "Only the owner can change a collection".to_string(),
+        ));
+    }
+    Ok(collection)
+}
+
+async fn find_accessible_recipe(
+    db: &DatabaseConnection,
+    user_id: i32,
+    recipe_id: i32,
+) -> Result<recipes::Model, ApiError> {
+    let has_access = has_access_to_recipe(db, user_id).await?;
+    recipes::Entity::find_by_id(recipe_id)
+        .filter(has_access)
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)
+}
+
+// Finds the recipes the user has access to, in the same order as `recipe_ids`
+async fn find_recipes_in_order(
+    db: &DatabaseConnection,
+    has_access: Condition,
+    recipe_ids: &[i32],
+) -> Result<Vec<recipes::Model>, DbErr> {
+    let mut recipes = recipes::Entity::find()
+        .filter(has_access)
+        .filter(recipes::Column::Id.is_in(recipe_ids.iter().copied()))
+        .all(db)
+        .await?;
+
+    recipes.sort_by_key(|it| recipe_ids.iter().position(|id| *id == it.id));
+    Ok(recipes)
+}
+
+async fn recipe_bookmarks(
+    db: &DatabaseConnection,
+    user_id: i32,
+    recipe_id: i32,
+) -> Result<RecipeBookmarks, DbErr> {
+    let favorite = favorites::Entity::find_by_id((user_id, recipe_id))
+        .one(db)
+        .await?
+        .is_some();
+
+    let own_collections = collections::Entity::find()
+        .filter(collections::Column::UserId.eq(user_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|it| it.id)
+        .collect::<Vec<_>>();
+
+    let collection_ids = collection_recipes::Entity::find()
+        .filter(collection_recipes::Column::RecipeId.eq(recipe_id))
+        .filter(collection_recipes::Column::CollectionId.is_in(own_collections))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|it| it.collection_id)
+        .sorted()
+        .collect();
+
+    Ok(RecipeBookmarks {
+        favorite,
+        collection_ids,
+    })
+}
+
+async fn to_collections(
+    db: &DatabaseConnection,
+    user_id: i32,
+    collections: Vec<collections::Model>,
+) -> Result<Vec<Collection>, ApiError> {
+    let has_access = has_access_to_recipe(db, user_id).await?;
+    let accessible = recipes::Entity::find()
+        .select_only()
+        .column(recipes::Column::Id)
+        .filter(has_access)
+        .into_tuple::<i32>()
+        .all(db)
+        .await?;
+
+    let lines = collection_recipes::Entity::find()
+        .filter(collection_recipes::Column::CollectionId.is_in(collections.iter().map(|it| it.id)))
+        .filter(collection_recipes::Column::RecipeId.is_in(accessible))
+        .order_by_asc(collection_recipes::Column::CreatedAt)
+        .all(db)
+        .await?
+        .into_iter()
+        .into_group_map_by(|it| it.collection_id);
+
+    Ok(collections
+        .into_iter()
+        .map(|it| Collection {
+            recipe_ids: lines
+                .get(&it.id)
+                .map(|lines| lines.iter().map(|line| line.recipe_id).collect())
+                .unwrap_or_default(),
+            id: it.id,
+            user_id: it.user_id,
+            name: it.name,
+            description: it.description,
+            visibility: it.visibility.into(),
+            updated_at: it.updated_at,
+        })
+        .collect())
+}
+
+fn valid_name(collection: &CreateCollection) -> Result<String, ApiError> {
+    let name = collection.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::StatusCode(
+            StatusCode::BAD_REQUEST,
+            "The collection needs a name".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn name_conflict(err: DbErr) -> ApiError {
+    match err.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => {
+            ApiError::ConflictError("You already have a collection with that name".to_string())
+        }
+        _ => err.into(),
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use common::{
+    ingredient::normalize_ingredient_name,
+    recipe::{DuplicateRecipe, MergeRecipe, Recipe},
+    websocket::FoodieMessageType,
+};
+use hyper::StatusCode;
+use itertools::Itertools;
+use sea_orm::{
+    sea_query::{Expr, Query},
+    ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QuerySelect, TransactionTrait, Value,
+};
+
+use crate::{
+    api::{
+        recipe::{find_recipe, has_access_to_recipe},
+        users::fetch_user_relationships,
+        websocket::send_message,
+    },
+    app::AppState,
+    auth_backend::AuthSession,
+    entities::{
+        collection_recipes, collections, favorites, ingredients, planned_meals, recipe_ingredients,
+        recipes, sea_orm_active_enums::FriendshipStatus,
+    },
+    storage::FoodieStorage,
+    ApiError,
+};
+
+// Recipes with names less alike than this are not compared at all. It is trigram similarity, so
+// "Taco fredag" and "Tacofredag" are about 0.5 alike
+const MIN_NAME_SIMILARITY: f32 = 0.3;
+// The name weighs more than the ingredients, since two versions of the same dish often differ in
+// a couple of the ingredients
+const NAME_WEIGHT: f32 = 0.6;
+// With the weights above, recipes with the same name are always duplicates, and recipes with
+// similar names are duplicates when they have most of the ingredients in common
+const DUPLICATE_SCORE: f32 = 0.6;
+
+// Finds the recipes the user has access to that are likely the same as a recipe with the name and
+// the ingredients. The most likely duplicates are first
+pub async fn find_duplicates<C>(
+    db: &C,
+    user_id: i32,
+    name: &str,
+    ingredient_names: &[&str],
+) -> Result<Vec<DuplicateRecipe>, ApiError>
+where
+    C: ConnectionTrait,
+{
+    let name = name.trim().to_lowercase();
+    let similarity = Expr::cust_with_values("similarity(lower(recipes.name), $1)", [name]);
+
+    let similar = recipes::Entity::find()
+        .select_only()
+        .column(recipes::Column::Id)
+        .column(recipes::Column::UserId)
+        .column(recipes::Column::Name)
+        .column_as(similarity.clone(), "similarity")
+        .filter(has_access_to_recipe(db, user_id).await?)
+        .filter(Expr::expr(similarity).gte(Value::from(MIN_NAME_SIMILARITY)))
+        .into_tuple::<(i32, i32, String, f32)>()
+        .all(db)
+        .await?;
+
+    let lines = recipe_ingredients::Entity::find()
+        .select_only()
+        .column(recipe_ingredients::Column::RecipeId)
+        .column(ingredients::Column::NormalizedName)
+        .inner_join(ingredients::Entity)
+        .filter(recipe_ingredients::Column::RecipeId.is_in(similar.iter().map(|it| it.0)))
+        .into_tuple::<(i32, String)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .into_group_map();
+
+    let ingredients = ingredient_names
+        .iter()
+        .map(|it| normalize_ingredient_name(it))
+        .collect::<HashSet<_>>();
+
+    Ok(similar
+        .into_iter()
+        .filter_map(|(id, user_id, name, name_similarity)| {
+            let other = lines
+                .get(&id)
+                .map(|it| it.iter().cloned().collect::<HashSet<_>>())
+                .unwrap_or_default();
+            let ingredient_overlap = overlap(&ingredients, &other);
+            let score = NAME_WEIGHT * name_similarity + (1.0 - NAME_WEIGHT) * ingredient_overlap;
+
+            (score >= DUPLICATE_SCORE).then_some((
+                score,
+                DuplicateRecipe {
+                    id,
+                    user_id,
+                    name,
+                    name_similarity,
+                    ingredient_overlap,
+                },
+            ))
+        })
+        .sorted_by(|a, b| b.0.total_cmp(&a.0))
+        .map(|(_, duplicate)| duplicate)
+        .collect())
+}
+
+// The share of the ingredients that are in both, out of all the ingredients
+fn overlap(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    match a.union(b).count() {
+        0 => 0.0,
+        all => a.intersection(b).count() as f32 / all as f32,
+    }
+}
+
+// Merges a recipe of the user into another recipe, e.g. when they turn out to be duplicates. The
+// merged recipe is deleted, and its favorites, collections and planned meals are moved to the kept
+// recipe for the users that can see it. The rest are deleted along with the merged recipe
+pub async fn merge_recipe<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Path(recipe_id): Path<i32>,
+    Json(merge): Json<MergeRecipe>,
+) -> Result<Json<Recipe>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    if recipe_id == merge.into_id {
+        return Err(ApiError::StatusCode(
+            StatusCode::BAD_REQUEST,
+            "Cannot merge a recipe into itself".to_string(),
+        ));
+    }
+
+    let source = recipes::Entity::find_by_id(recipe_id)
+        .filter(recipes::Column::UserId.eq(user.id))
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    // Checks that the user has access to the kept recipe
+    find_recipe(&state, user.id, merge.into_id).await?;
+
+    let tx = state.db.begin().await?;
+
+    let allowed = users_with_access(&tx, source.id, merge.into_id).await?;
+
+    // Users that already has the kept recipe as a favorite keeps that one
+    favorites::Entity::update_many()
+        .col_expr(favorites::Column::RecipeId, Expr::value(merge.into_id))
+        .filter(favorites::Column::RecipeId.eq(source.id))
+        .filter(favorites::Column::UserId.is_in(allowed.clone()))
+        .filter(
+            favorites::Column::UserId.not_in_subquery(
+                Query::select()
+                    .column(favorites::Column::UserId)
+                    .from(favorites::Entity)
+                    .and_where(favorites::Column::RecipeId.eq(merge.into_id))
+                    .to_owned(),
+            ),
+        )
+        .exec(&tx)
+        .await?;
+
+    collection_recipes::Entity::update_many()
+        .col_expr(
+            collection_recipes::Column::RecipeId,
+            Expr::value(merge.into_id),
+        )
+        .filter(collection_recipes::Column::RecipeId.eq(source.id))
+        .filter(
+            collection_recipes::Column::CollectionId.in_subquery(
+                Query::select()
+                    .column(collections::Column::Id)
+                    .from(collections::Entity)
+                    .and_where(collections::Column::UserId.is_in(allowed.clone()))
+                    .to_owned(),
+            ),
+        )
+        .filter(
+            collection_recipes::Column::CollectionId.not_in_subquery(
+                Query::select()
+                    .column(collection_recipes::Column::CollectionId)
+                    .from(collection_recipes::Entity)
+                    .and_where(collection_recipes::Column::RecipeId.eq(merge.into_id))
+                    .to_owned(),
+            ),
+        )
+        .exec(&tx)
+        .await?;
+
+    planned_meals::Entity::update_many()
+        .col_expr(planned_meals::Column::RecipeId, Expr::value(merge.into_id))
+        .filter(planned_meals::Column::RecipeId.eq(source.id))
+        .filter(planned_meals::Column::UserId.is_in(allowed))
+        .exec(&tx)
+        .await?;
+
+    recipes::Entity::delete_by_id(source.id).exec(&tx).await?;
+
+    tx.commit().await?;
+
+    if let Some(img) = source.img {
+        let _ = state.storage.delete(img).await;
+    }
+
+    let friends = fetch_user_relationships(&state.db, user.id, "")
+        .await?
+        .into_iter()
+        .filter(|it| it.status == Some(FriendshipStatus::Accepted.into()));
+
+    for friend in friends {
+        send_message(
+            &state.connections,
+            friend.id,
+            FoodieMessageType::RecipeDelete,
+        );
+    }
+
+    Ok(Json(find_recipe(&state, user.id, merge.into_id).await?))
+}
+
+// The users with favorites, collections or planned meals of `recipe_id` that can see `into_id`
+async fn users_with_access<C>(db: &C, recipe_id: i32, into_id: i32) -> Result<Vec<i32>, ApiError>
+where
+    C: ConnectionTrait,
+{
+    let favorites = favorites::Entity::find()
+        .filter(favorites::Column::RecipeId.eq(recipe_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|it| it.user_id);
+
+    let collections = collections::Entity::find()
+        .filter(
+            collections::Column::Id.in_subquery(
+                Query::select()
+                    .column(collection_recipes::Column::CollectionId)
+                    .from(collection_recipes::Entity)
+                    .and_where(collection_recipes::Column::RecipeId.eq(recipe_id))
+                    .to_owned(),
+            ),
+        )
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|it| it.user_id);
+
+    let planned_meals = planned_meals::Entity::find()
+        .filter(planned_meals::Column::RecipeId.eq(recipe_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|it| it.user_id);
+
+    let mut allowed = vec![];
+    for user_id in favorites.chain(collections).chain(planned_meals).unique() {
+        let has_access = recipes::Entity::find_by_id(into_id)
+            .filter(has_access_to_recipe(db, user_id).await?)
+            .one(db)
+            .await?
+            .is_some();
+        if has_access {
+            allowed.push(user_id);
+        }
+    }
+
+    Ok(allowed)
+}
@@ -0,0 +1,240 @@
+use std::cmp::{Ordering, Reverse};
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, FixedOffset};
+use common::feed::{CookRecipe, FeedItem, FeedKind};
+use hyper::StatusCode;
+use sea_orm::{
+    sea_query::Expr, ActiveValue::NotSet, ColumnTrait, Condition, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Select, Set,
+};
+use serde::Deserialize;
+
+use crate::{
+    api::{recipe::has_access_to_recipe, users::fetch_user_relationships},
+    app::AppState,
+    auth_backend::AuthSession,
+    entities::{recipe_cooks, recipes, sea_orm_active_enums::FriendshipStatus, users},
+    storage::FoodieStorage,
+    ApiError,
+};
+
+const DEFAULT_PAGE_SIZE: u64 = 20;
+const MAX_PAGE_SIZE: u64 = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct FeedQuery {
+    // Only activity before this is included. The next page is everything before the last item,
+    // given by its `at`, `kind` and `id`, since several items can happen at the same time
+    before: Option<DateTime<FixedOffset>>,
+    before_kind: Option<FeedKind>,
+    before_id: Option<i32>,
+    limit: Option<u64>,
+}
+
+impl FeedQuery {
+    // Whether the activity comes after the cursor in the feed, where the newest is first
+    fn after_cursor<C: ColumnTrait>(&self, kind: FeedKind, at: C, id: C) -> Condition {
+        let Some(before) = self.before else {
+            return Condition::all();
+        };
+
+        let (before_kind, before_id) = match (self.before_kind, self.before_id) {
+            (Some(before_kind), Some(before_id)) => (before_kind, before_id),
+            _ => return Condition::all().add(at.lt(before)),
+        };
+
+        match kind.cmp(&before_kind) {
+            Ordering::Less => Condition::all().add(at.lte(before)),
+            Ordering::Greater => Condition::all().add(at.lt(before)),
+            Ordering::Equal => Condition::any()
+                .add(at.lt(before))
+                .add(Condition::all().add(at.eq(before)).add(id.lt(before_id))),
+        }
+    }
+}
+
+// Marks the recipe as cooked by the user, with an optional rating
+pub async fn post_cooked<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Path(recipe_id): Path<i32>,
+    Json(cook): Json<CookRecipe>,
+) -> Result<StatusCode, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    if cook.rating.is_some_and(|it| !(1..=5).contains(&it)) {
+        return Err(ApiError::StatusCode(
+            StatusCode::BAD_REQUEST,
+            "The rating must be from 1 to 5".to_string(),
+        ));
+    }
+
+    let has_access = has_access_to_recipe(&state.db, user.id).await?;
+    let accessible = recipes::Entity::find_by_id(recipe_id)
+        .filter(has_access)
+        .count(&state.db)
+        .await?;
+    if accessible == 0 {
+        return Err(ApiError::RecordNotFound);
+    }
+
+    recipe_cooks::Entity::insert(recipe_cooks::ActiveModel {
+        id: NotSet,
+        user_id: Set(user.id),
+        recipe_id: Set(recipe_id),
+        rating: Set(cook.rating),
+        cooked_at: NotSet,
+    })
+    .exec_without_returning(&state.db)
+    .await?;
+
+    Ok(StatusCode::CREATED)
+}
+
+// What friends have done with the recipes the user has access to, the newest first
+pub async fn get_feed<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Query(query): Query<FeedQuery>,
+) -> Result<Json<Vec<FeedItem>>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let friend_ids = fetch_user_relationships(&state.db, user.id, "")
+        .await?
+        .into_iter()
+        .filter(|it| it.status == Some(FriendshipStatus::Accepted.into()))
+        .map(|it| it.id)
+        .collect::<Vec<_>>();
+
+    let has_access = has_access_to_recipe(&state.db, user.id).await?;
+    let friend_recipes = || {
+        recipes::Entity::find()
+            .filter(has_access.clone())
+            .filter(recipes::Column::UserId.is_in(friend_ids.clone()))
+    };
+
+    let created = recipe_activity(friend_recipes(), recipes::Column::CreatedAt)
+        .filter(query.after_cursor(
+            FeedKind::RecipeCreated,
+            recipes::Column::CreatedAt,
+            recipes::Column::Id,
+        ))
+        .limit(limit)
+        .into_tuple::<(i32, String, DateTime<FixedOffset>, i32, String)>()
+        .all(&state.db)
+        .await?;
+
+    // The timestamps are the same when a recipe is created
+    let updated = recipe_activity(friend_recipes(), recipes::Column::UpdatedAt)
+        .filter(query.after_cursor(
+            FeedKind::RecipeUpdated,
+            recipes::Column::UpdatedAt,
+            recipes::Column::Id,
+        ))
+        .filter(Expr::col(recipes::Column::UpdatedAt).gt(Expr::col(recipes::Column::CreatedAt)))
+        .limit(limit)
+        .into_tuple::<(i32, String, DateTime<FixedOffset>, i32, String)>()
+        .all(&state.db)
+        .await?;
+
+    let cooked = recipe_cooks::Entity::find()
+        .select_only()
+        .column(recipe_cooks::Column::Id)
+        .column(recipes::Column::Id)
+        .column(recipes::Column::Name)
+        .column(recipe_cooks::Column::CookedAt)
+        .column(users::Column::Id)
+        .column(users::Column::Name)
+        .column(recipe_cooks::Column::Rating)
+        .inner_join(recipes::Entity)
+        .inner_join(users::Entity)
+        .filter(has_access)
+        .filter(recipe_cooks::Column::UserId.is_in(friend_ids))
+        .filter(query.after_cursor(
+            FeedKind::RecipeCooked,
+            recipe_cooks::Column::CookedAt,
+            recipe_cooks::Column::Id,
+        ))
+        .order_by_desc(recipe_cooks::Column::CookedAt)
+        .order_by_desc(recipe_cooks::Column::Id)
+        .limit(limit)
+        .into_tuple::<(
+            i32,
+            i32,
+            String,
+            DateTime<FixedOffset>,
+            i32,
+            String,
+            Option<i32>,
+        )>()
+        .all(&state.db)
+        .await?;
+
+    let mut feed = created
+        .into_iter()
+        .map(|it| (FeedKind::RecipeCreated, it.0, it, None))
+        .chain(
+            updated
+                .into_iter()
+                .map(|it| (FeedKind::RecipeUpdated, it.0, it, None)),
+        )
+        .chain(cooked.into_iter().map(
+            |(id, recipe_id, recipe_name, at, user_id, user_name, rating)| {
+                (
+                    FeedKind::RecipeCooked,
+                    id,
+                    (recipe_id, recipe_name, at, user_id, user_name),
+                    rating,
+                )
+            },
+        ))
+        .map(
+            |(kind, id, (recipe_id, recipe_name, at, user_id, user_name), rating)| FeedItem {
+                kind,
+                id,
+                user_id,
+                user_name,
+                recipe_id,
+                recipe_name,
+                rating,
+                at,
+            },
+        )
+        .collect::<Vec<_>>();
+
+    feed.sort_by_key(|it| Reverse((it.at, it.kind, it.id)));
+    feed.truncate(limit as usize);
+
+    Ok(Json(feed))
+}
+
+// Selects the recipe, the time in the column and the owner of the recipe, the newest first
+fn recipe_activity(
+    query: Select<recipes::Entity>,
+    column: recipes::Column,
+) -> Select<recipes::Entity> {
+    query
+        .select_only()
+        .column(recipes::Column::Id)
+        .column(recipes::Column::Name)
+        .column(column)
+        .column(users::Column::Id)
+        .column(users::Column::Name)
+        .inner_join(users::Entity)
+        .order_by_desc(column)
+        .order_by_desc(recipes::Column::Id)
+}
@@ -1,10 +1,15 @@
 use crate::{
-    api::users::fetch_user_relationships,
+    api::{
+        notification::{notify, NotificationTarget},
+        users::fetch_user_relationships,
+        websocket::send_message,
+    },
     app::AppState,
     auth_backend::AuthSession,
     entities::{
         friendships,
-        sea_orm_active_enums::{self, FriendshipStatus},
+        sea_orm_active_enums::{self, FriendshipStatus, NotificationKind},
+        users,
     },
     storage::FoodieStorage,
     ApiError,
@@ -14,17 +19,23 @@ use axum::{
     response::IntoResponse,
     Json,
 };
-use common::{friendship::FriendshipAnswer, user::UserWithRelation};
+use common::{friendship::FriendshipAnswer, user::UserWithRelation, websocket::FoodieMessageType};
 use hyper::StatusCode;
 use sea_orm::{
-    sea_query::OnConflict,
-    ActiveModelTrait,
-    ActiveValue::{NotSet, Set},
-    ConnectionTrait, EntityTrait, IntoActiveModel,
+    sea_query::OnConflict, ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition,
+    ConnectionTrait, DbErr, EntityTrait, FromQueryResult, IntoActiveModel, QueryFilter, Statement,
 };
 
+// How long the requester has to wait before they can send a new request after being rejected
+const REQUEST_COOLDOWN: chrono::Duration = chrono::Duration::days(7);
+
+// How many ingredients two recipes must share to be similar when suggesting friends
+const SIMILAR_RECIPE_INGREDIENTS: i64 = 3;
+const MAX_SUGGESTIONS: i64 = 10;
+
 // Send a friend request and make it pending.
-// If it already exists a friend request/friendship, then do nothing
+// If it already exists a friend request/friendship, then do nothing. A rejected request can be
+// sent again after the cooldown, or at any time by the one who rejected it
 pub async fn send_friend_request<T>(
     auth: AuthSession,
     Path(recipient_id): Path<i32>,
@@ -34,26 +45,97 @@ where
     T: FoodieStorage + Send + Sync + Clone,
 {
     let user = auth.user.unwrap();
+    let now = chrono::Utc::now();
+    check_other_user(&state.db, user.id, recipient_id).await?;
 
-    let friendship = friendships::ActiveModel {
-        requester_id: Set(user.id),
-        recipient_id: Set(recipient_id),
-        status: Set(FriendshipStatus::Pending),
-        requested_at: Set(chrono::Utc::now().into()),
-        responded_at: NotSet,
-    };
+    let Some(friendship) = find_friendship(&state.db, user.id, recipient_id).await? else {
+        let friendship = friendships::ActiveModel {
+            requester_id: Set(user.id),
+            recipient_id: Set(recipient_id),
+            status: Set(FriendshipStatus::Pending),
+            requested_at: Set(now.into()),
+            responded_at: Set(None),
+        };
 
-    friendships::Entity::insert(friendship)
-        .on_conflict(
-            OnConflict::columns([
-                friendships::Column::RequesterId,
-                friendships::Column::RecipientId,
-            ])
-            .do_nothing()
-            .to_owned(),
+        friendships::Entity::insert(friendship)
+            .on_conflict(
+                OnConflict::columns([
+                    friendships::Column::RequesterId,
+                    friendships::Column::RecipientId,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(&state.db)
+            .await?;
+
+        notify(
+            &state,
+            &[recipient_id],
+            NotificationKind::FriendRequest,
+            &user,
+            NotificationTarget::default(),
         )
-        .exec(&state.db)
-        .await?;
+        .await;
+        send_message(
+            &state.connections,
+            recipient_id,
+            FoodieMessageType::FriendRequest(user),
+        );
+
+        return Ok(());
+    };
+
+    match friendship.status {
+        FriendshipStatus::Pending | FriendshipStatus::Accepted => return Ok(()),
+        // The blocked user should not know that they are blocked
+        FriendshipStatus::Blocked if friendship.requester_id != user.id => {
+            return Err(ApiError::RecordNotFound)
+        }
+        FriendshipStatus::Blocked => {
+            return Err(ApiError::StatusCode(
+                StatusCode::BAD_REQUEST,
+                "Unblock the user before sending a friend request".to_string(),
+            ))
+        }
+        FriendshipStatus::Rejected if friendship.requester_id == user.id => {
+            let rejected_at = friendship.responded_at.unwrap_or(friendship.requested_at);
+            if now < rejected_at + REQUEST_COOLDOWN {
+                return Err(ApiError::StatusCode(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "The friend request was rejected recently".to_string(),
+                ));
+            }
+        }
+        FriendshipStatus::Rejected => (),
+    }
+
+    replace_friendship(
+        &state.db,
+        &friendship,
+        friendships::Model {
+            requester_id: user.id,
+            recipient_id,
+            status: FriendshipStatus::Pending,
+            requested_at: now.into(),
+            responded_at: None,
+        },
+    )
+    .await?;
+
+    notify(
+        &state,
+        &[recipient_id],
+        NotificationKind::FriendRequest,
+        &user,
+        NotificationTarget::default(),
+    )
+    .await;
+    send_message(
+        &state.connections,
+        recipient_id,
+        FoodieMessageType::FriendRequest(user),
+    );
 
     Ok(())
 }
@@ -77,6 +159,20 @@ where
     )
     .await?;
 
+    notify(
+        &state,
+        &[recipient_id],
+        NotificationKind::FriendRequestAccepted,
+        &user,
+        NotificationTarget::default(),
+    )
+    .await;
+    send_message(
+        &state.connections,
+        recipient_id,
+        FoodieMessageType::FriendRequestAccepted(user),
+    );
+
     Ok(())
 }
 
@@ -121,6 +217,363 @@ where
     Ok(Json(users))
 }
 
+// The accepted friends of the user
+pub async fn get_friends<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+) -> Result<Json<Vec<UserWithRelation>>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+    let users = fetch_user_relationships(&state.db, user.id, "")
+        .await?
+        .into_iter()
+        .filter(|it| it.status == Some(FriendshipStatus::Accepted.into()))
+        .collect::<Vec<_>>();
+
+    Ok(Json(users))
+}
+
+// The friend requests the user has sent that are not answered yet
+pub async fn get_outgoing<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+) -> Result<Json<Vec<UserWithRelation>>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+    let users = fetch_user_relationships(&state.db, user.id, "")
+        .await?
+        .into_iter()
+        .filter(|it| {
+            it.status == Some(FriendshipStatus::Pending.into()) && it.requester_id == Some(user.id)
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(users))
+}
+
+// Cancels a friend request the user has sent, as long as it is not answered
+pub async fn cancel_friend_request<T>(
+    auth: AuthSession,
+    Path(recipient_id): Path<i32>,
+    State(state): State<AppState<T>>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let res = friendships::Entity::delete_by_id((user.id, recipient_id))
+        .filter(friendships::Column::Status.eq(FriendshipStatus::Pending))
+        .exec(&state.db)
+        .await?;
+
+    if res.rows_affected == 0 {
+        return Err(ApiError::RecordNotFound);
+    }
+
+    Ok(())
+}
+
+// Removes an accepted friend, no matter who sent the request. They lose access to the recipes of
+// each other right away
+pub async fn remove_friend<T>(
+    auth: AuthSession,
+    Path(friend_id): Path<i32>,
+    State(state): State<AppState<T>>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let friendship = find_friendship(&state.db, user.id, friend_id)
+        .await?
+        .filter(|it| it.status == FriendshipStatus::Accepted)
+        .ok_or(ApiError::RecordNotFound)?;
+
+    friendships::Entity::delete_by_id((friendship.requester_id, friendship.recipient_id))
+        .exec(&state.db)
+        .await?;
+
+    send_message(
+        &state.connections,
+        friend_id,
+        FoodieMessageType::FriendRemoved(user),
+    );
+
+    Ok(())
+}
+
+// Blocks the user, whatever the friendship is. They can no longer find each other or see the
+// recipes of each other
+pub async fn block_user<T>(
+    auth: AuthSession,
+    Path(recipient_id): Path<i32>,
+    State(state): State<AppState<T>>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+    check_other_user(&state.db, user.id, recipient_id).await?;
+
+    if find_friendship(&state.db, user.id, recipient_id)
+        .await?
+        .is_none()
+    {
+        friendships::Entity::insert(friendships::ActiveModel {
+            requester_id: Set(user.id),
+            recipient_id: Set(recipient_id),
+            status: Set(FriendshipStatus::Blocked),
+            requested_at: Set(chrono::Utc::now().into()),
+            responded_at: Set(Some(chrono::Utc::now().into())),
+        })
+        .exec_without_returning(&state.db)
+        .await?;
+
+        return Ok(());
+    }
+
+    set_friendship_status(
+        &state.db,
+        user.id,
+        FriendshipAnswer {
+            user_id: recipient_id,
+            status: FriendshipStatus::Blocked.into(),
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+// Removes the block, so they are strangers again. Only the one who blocked can unblock
+pub async fn unblock_user<T>(
+    auth: AuthSession,
+    Path(recipient_id): Path<i32>,
+    State(state): State<AppState<T>>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let res = friendships::Entity::delete_by_id((user.id, recipient_id))
+        .filter(friendships::Column::Status.eq(FriendshipStatus::Blocked))
+        .exec(&state.db)
+        .await?;
+
+    if res.rows_affected == 0 {
+        return Err(ApiError::RecordNotFound);
+    }
+
+    Ok(())
+}
+
+// The users the user has blocked
+pub async fn get_blocked<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+) -> Result<Json<Vec<UserWithRelation>>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+    let users = fetch_user_relationships(&state.db, user.id, "")
+        .await?
+        .into_iter()
+        .filter(|it| {
+            it.status == Some(FriendshipStatus::Blocked.into()) && it.requester_id == Some(user.id)
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(users))
+}
+
+// Users the user might know, ranked by mutual friends, shared voting and swipe sessions and
+// similar recipes. Anyone the user already has a relationship with, including blocked and
+// rejected users, is left out
+pub async fn get_friend_suggestions<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+) -> Result<Json<Vec<common::friendship::FriendSuggestion>>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let sql_query = r#"
+        WITH related AS (
+            SELECT CASE WHEN requester_id = $1 THEN recipient_id ELSE requester_id END AS id, status
+            FROM friendships
+            WHERE requester_id = $1 OR recipient_id = $1
+        ),
+        mutual AS (
+            SELECT
+                CASE WHEN f.requester_id = r.id THEN f.recipient_id ELSE f.requester_id END AS id,
+                COUNT(*) AS count
+            FROM friendships f
+            JOIN related r ON r.status = 'accepted' AND r.id IN (f.requester_id, f.recipient_id)
+            WHERE f.status = 'accepted'
+            GROUP BY 1
+        ),
+        sessions AS (
+            SELECT id, COUNT(*) AS count
+            FROM (
+                SELECT other.user_id AS id
+                FROM voting_session_participants me
+                JOIN voting_session_participants other ON other.session_id = me.session_id
+                WHERE me.user_id = $1
+                UNION ALL
+                SELECT other.user_id AS id
+                FROM swipe_session_participants me
+                JOIN swipe_session_participants other ON other.session_id = me.session_id
+                WHERE me.user_id = $1
+            ) shared
+            GROUP BY id
+        ),
+        similar_recipes AS (
+            SELECT user_id AS id, COUNT(DISTINCT recipe_id) AS count
+            FROM (
+                SELECT theirs.user_id, theirs.id AS recipe_id
+                FROM recipes theirs
+                JOIN recipe_ingredients their_ri ON their_ri.recipe_id = theirs.id
+                JOIN recipe_ingredients my_ri ON my_ri.ingredient_id = their_ri.ingredient_id
+                JOIN recipes mine ON mine.id = my_ri.recipe_id
+                WHERE mine.user_id = $1 AND theirs.user_id != $1 AND theirs.visibility <> 'private'
+                GROUP BY theirs.user_id, theirs.id, mine.id
+                HAVING COUNT(DISTINCT their_ri.ingredient_id) >= $2
+            ) pairs
+            GROUP BY user_id
+        )
+        SELECT
+            u.id,
+            u.name,
+            u.email,
+            COALESCE(m.count, 0) AS mutual_friends,
+            COALESCE(s.count, 0) AS shared_sessions,
+            COALESCE(r.count, 0) AS similar_recipes
+        FROM users u
+        LEFT JOIN mutual m ON m.id = u.id
+        LEFT JOIN sessions s ON s.id = u.id
+        LEFT JOIN similar_recipes r ON r.id = u.id
+        WHERE u.id != $1
+          AND u.id NOT IN (SELECT id FROM related)
+          AND COALESCE(m.count, 0) + COALESCE(s.count, 0) + COALESCE(r.count, 0) > 0
+        ORDER BY
+            3 * COALESCE(m.count, 0) + 2 * COALESCE(s.count, 0) + COALESCE(r.count, 0) DESC,
+            u.name
+        LIMIT $3;
+    "#;
+
+    let suggestions = FriendSuggestion::find_by_statement(Statement::from_sql_and_values(
+        state.db.get_database_backend(),
+        sql_query,
+        vec![
+            user.id.into(),
+            SIMILAR_RECIPE_INGREDIENTS.into(),
+            MAX_SUGGESTIONS.into(),
+        ],
+    ))
+    .all(&state.db)
+    .await?
+    .into_iter()
+    .map(|it| common::friendship::FriendSuggestion {
+        id: it.id,
+        name: it.name,
+        email: it.email,
+        mutual_friends: it.mutual_friends,
+        shared_sessions: it.shared_sessions,
+        similar_recipes: it.similar_recipes,
+    })
+    .collect();
+
+    Ok(Json(suggestions))
+}
+
+#[derive(Debug, FromQueryResult)]
+struct FriendSuggestion {
+    id: i32,
+    name: String,
+    email: String,
+    mutual_friends: i64,
+    shared_sessions: i64,
+    similar_recipes: i64,
+}
+
+// A friend request or block needs someone else who exists
+async fn check_other_user<C>(db: &C, user_id: i32, other_id: i32) -> Result<(), ApiError>
+where
+    C: ConnectionTrait,
+{
+    if other_id == user_id {
+        return Err(ApiError::StatusCode(
+            StatusCode::BAD_REQUEST,
+            "You can't do that to yourself".to_string(),
+        ));
+    }
+
+    users::Entity::find_by_id(other_id)
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    Ok(())
+}
+
+// Finds the friendship between the users, no matter who sent the request
+async fn find_friendship<C>(
+    db: &C,
+    id: i32,
+    other_id: i32,
+) -> Result<Option<friendships::Model>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    friendships::Entity::find()
+        .filter(
+            Condition::any()
+                .add(
+                    Condition::all()
+                        .add(friendships::Column::RequesterId.eq(id))
+                        .add(friendships::Column::RecipientId.eq(other_id)),
+                )
+                .add(
+                    Condition::all()
+                        .add(friendships::Column::RequesterId.eq(other_id))
+                        .add(friendships::Column::RecipientId.eq(id)),
+                ),
+        )
+        .one(db)
+        .await
+}
+
+// Replaces the friendship, since the requester and recipient are the primary key and changes when
+// the other user sends a new request, or blocks
+async fn replace_friendship<C>(
+    db: &C,
+    old: &friendships::Model,
+    new: friendships::Model,
+) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    friendships::Entity::update_many()
+        .set(new.into_active_model().reset_all())
+        .filter(friendships::Column::RequesterId.eq(old.requester_id))
+        .filter(friendships::Column::RecipientId.eq(old.recipient_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+// The recipient answers a pending request, or changes their mind about a rejected one. Anyone can
+// block at any time, and the one who blocks becomes the requester, since only they can unblock
 pub async fn set_friendship_status<C>(
     db: &C,
     id: i32,
@@ -129,46 +582,57 @@ pub async fn set_friendship_status<C>(
 where
     C: ConnectionTrait,
 {
-    // HACK: I don't like this, but I have a constraint that it cannot save like this
-    // recipient_id: 1
-    // requester_id: 2
-    //
-    // recipient_id: 2
-    // requester_id: 1
-    let ids = (
-        id.min(friendship_answer.user_id),
-        id.max(friendship_answer.user_id),
-    );
-
-    let friendship = friendships::Entity::find_by_id(ids).one(db).await?;
-
-    let Some(mut friendship) = friendship.map(|f| f.into_active_model()) else {
+    let Some(friendship) = find_friendship(db, id, friendship_answer.user_id).await? else {
         return Err(ApiError::RecordNotFound);
     };
 
-    match friendship.status.as_ref() {
-        FriendshipStatus::Accepted => {
-            if friendship_answer.status != FriendshipStatus::Blocked.into() {
-                return Err(ApiError::StatusCode(
-                    StatusCode::BAD_REQUEST,
-                    "Only possible to block an accepted friendship".to_string(),
-                ));
-            }
+    let status: FriendshipStatus = friendship_answer.status.into();
+    let is_recipient = friendship.recipient_id == id;
+
+    match (&friendship.status, &status) {
+        (FriendshipStatus::Blocked, _) if friendship.requester_id != id => {
+            return Err(ApiError::RecordNotFound);
         }
-        FriendshipStatus::Pending => {
-            if friendship.requester_id.as_ref() == &id {
+        (_, FriendshipStatus::Blocked) => {
+            replace_friendship(
+                db,
+                &friendship,
+                friendships::Model {
+                    requester_id: id,
+                    recipient_id: friendship_answer.user_id,
+                    status,
+                    requested_at: friendship.requested_at,
+                    responded_at: Some(chrono::Utc::now().into()),
+                },
+            )
+            .await?;
+
+            return Ok(());
+        }
+        (FriendshipStatus::Pending, FriendshipStatus::Accepted | FriendshipStatus::Rejected)
+        | (FriendshipStatus::Rejected, FriendshipStatus::Accepted) => {
+            if !is_recipient {
                 return Err(ApiError::StatusCode(
                     StatusCode::BAD_REQUEST,
                     "Only recipient can accept/reject".to_string(),
                 ));
             }
         }
-        // TODO(seb): What should be possible to do for a `blocked/rejected` friendship?
-        FriendshipStatus::Blocked => todo!("Not implemented yet"),
-        FriendshipStatus::Rejected => todo!("Not implemeted yet"),
+        (current, _) => {
+            return Err(ApiError::StatusCode(
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Not possible to change a {} friendship to {}",
+                    common::friendship::FriendshipStatus::from(current.clone()),
+                    friendship_answer.status
+                ),
+            ));
+        }
     }
 
-    friendship.status = Set(friendship_answer.status.into());
+    let mut friendship = friendship.into_active_model();
+    friendship.status = Set(status);
+    friendship.responded_at = Set(Some(chrono::Utc::now().into()));
     friendship.update(db).await?;
 
     Ok(())
@@ -1,18 +1,36 @@
+use std::collections::HashMap;
+
 use crate::{
+    api::is_admin,
     auth_backend::AuthSession,
-    entities::{self, ingredients, recipe_ingredients},
+    entities::{
+        ingredient_aliases, ingredients, recipe_ingredients, sea_orm_active_enums,
+        user_aisle_orders, user_ingredient_categories,
+    },
     ApiError,
 };
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
-use common::ingredient::{CreateIngredient, Ingredient};
-use futures_util::StreamExt;
+use common::{
+    ingredient::{
+        normalize_ingredient_name, Allergen, CreateIngredient, CreateIngredientAlias, Diet,
+        Ingredient, IngredientAlias, IngredientCategory, MergeIngredient, UpdateIngredientCategory,
+        UpdateIngredientDietary,
+    },
+    strum::IntoEnumIterator,
+    user::User,
+};
+use hyper::StatusCode;
+use itertools::Itertools;
 use sea_orm::{
-    sea_query::OnConflict, ActiveValue::NotSet, ColumnTrait, DatabaseConnection, EntityTrait,
-    QueryFilter, Set,
+    sea_query::{Expr, OnConflict},
+    ActiveValue::NotSet,
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    FromQueryResult, QueryFilter, Set, Statement, TransactionTrait,
 };
+use serde::Deserialize;
 
 pub async fn post_ingredient(
     State(db): State<DatabaseConnection>,
@@ -20,53 +38,57 @@ pub async fn post_ingredient(
     Json(ingredient): Json<CreateIngredient>,
 ) -> Result<Json<Ingredient>, ApiError> {
     let user = auth.user.unwrap();
-    let created_ingredient = ingredients::Entity::insert(ingredients::ActiveModel {
-        id: NotSet,
-        name: Set(ingredient.name),
-        user_id: Set(user.id),
-    })
-    .on_conflict(
-        OnConflict::column(ingredients::Column::Name)
-            .update_column(ingredients::Column::Name)
-            .to_owned(),
-    )
-    .exec_with_returning(&db)
-    .await?;
+    let normalized_name = normalize_ingredient_name(&ingredient.name);
 
-    Ok(Json(Ingredient {
-        id: created_ingredient.id,
-        name: created_ingredient.name,
-    }))
+    let mut resolved = resolve_ingredients(&db, user.id, [ingredient.name.as_str()]).await?;
+    let created_ingredient = resolved
+        .remove(&normalized_name)
+        .ok_or(ApiError::RecordNotFound)?;
+
+    Ok(Json(created_ingredient.into()))
 }
 
+// Gets the global catalog together with the private ingredients of the user, ordered by the
+// aisle order of the user
 pub async fn get_ingredients(
     auth: AuthSession,
     State(db): State<DatabaseConnection>,
 ) -> Result<Json<Vec<Ingredient>>, ApiError> {
     let user = auth.user.unwrap();
 
-    let ingredients = entities::ingredients::Entity::find()
-        .filter(entities::ingredients::Column::UserId.eq(user.id))
-        .stream(&db)
+    let ingredients = ingredients::Entity::find()
+        .filter(visible_ingredients(user.id))
+        .all(&db)
+        .await?;
+
+    let aisles = aisle_order(&db, user.id).await?;
+    let ingredients = with_user_categories(&db, user.id, ingredients)
         .await?
-        .map(|i| {
-            // TODO: I don't want to unwrap in here maybe
-            let i = i.unwrap();
-            Ingredient {
-                id: i.id,
-                name: i.name,
-            }
-        })
-        .collect::<Vec<_>>()
-        .await;
+        .into_iter()
+        .sorted_by_cached_key(|it| (aisle_position(&aisles, it.category), it.name.to_lowercase()))
+        .collect::<Vec<_>>();
 
     Ok(Json(ingredients))
 }
 
+// Deletes a private ingredient. Admins are also able to delete from the global catalog
 pub async fn delete_ingredient(
     State(db): State<DatabaseConnection>,
+    auth: AuthSession,
     Path(ingredient_id): Path<i32>,
 ) -> Result<Json<i32>, ApiError> {
+    let user = auth.user.unwrap();
+
+    let ingredient = ingredients::Entity::find_by_id(ingredient_id)
+        .filter(visible_ingredients(user.id))
+        .one(&db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    if !can_manage_ingredient(&user, &ingredient) {
+        return Err(forbidden());
+    }
+
     let recipe_ingredients = recipe_ingredients::Entity::find()
         .filter(recipe_ingredients::Column::IngredientId.eq(ingredient_id))
         .one(&db)
@@ -84,6 +106,91 @@ pub async fn delete_ingredient(
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct IngredientQuery {
+    q: String,
+    limit: Option<u64>,
+}
+
+// Autocomplete for ingredients. Matches on prefix and uses trigram similarity to be tolerant for
+// typos, also for aliases. The ingredients the user has used the most are ranked first
+pub async fn search_ingredients(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Query(query): Query<IngredientQuery>,
+) -> Result<Json<Vec<Ingredient>>, ApiError> {
+    let user = auth.user.unwrap();
+    let search = normalize_ingredient_name(&query.q);
+    if search.is_empty() {
+        return Ok(Json(vec![]));
+    }
+
+    let prefix = format!(
+        "{}%",
+        search
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    let limit = query.limit.unwrap_or(10).min(50);
+
+    let sql_query = r#"
+        WITH usage AS (
+            SELECT ri.ingredient_id, COUNT(*) AS uses
+            FROM recipe_ingredients ri
+            JOIN recipes r ON r.id = ri.recipe_id
+            WHERE r.user_id = $1
+            GROUP BY ri.ingredient_id
+        ),
+        names AS (
+            SELECT i.id, i.normalized_name
+            FROM ingredients i
+            UNION ALL
+            SELECT a.ingredient_id, a.normalized_name
+            FROM ingredient_aliases a
+        ),
+        matches AS (
+            SELECT
+                id,
+                bool_or(normalized_name LIKE $3) AS is_prefix,
+                max(similarity(normalized_name, $2)) AS score
+            FROM names
+            WHERE normalized_name LIKE $3 OR normalized_name % $2
+            GROUP BY id
+        )
+        SELECT
+            i.id,
+            i.name,
+            i.user_id,
+            i.normalized_name,
+            i.category::text AS category,
+            i.allergens,
+            i.vegetarian,
+            i.vegan
+        FROM matches m
+        JOIN ingredients i ON i.id = m.id
+        LEFT JOIN usage u ON u.ingredient_id = i.id
+        WHERE i.user_id IS NULL OR i.user_id = $1
+        ORDER BY COALESCE(u.uses, 0) DESC, m.is_prefix DESC, m.score DESC, i.name
+        LIMIT $4;
+    "#;
+
+    let ingredients = ingredients::Model::find_by_statement(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        sql_query,
+        vec![
+            user.id.into(),
+            search.into(),
+            prefix.into(),
+            (limit as i64).into(),
+        ],
+    ))
+    .all(&db)
+    .await?;
+
+    Ok(Json(with_user_categories(&db, user.id, ingredients).await?))
+}
+
 pub async fn get_ingredient(
     State(db): State<DatabaseConnection>,
     auth: AuthSession,
@@ -91,13 +198,651 @@ pub async fn get_ingredient(
 ) -> Result<Json<Ingredient>, ApiError> {
     let user = auth.user.unwrap();
     let ingredient = ingredients::Entity::find_by_id(ingredient_id)
-        .filter(ingredients::Column::UserId.eq(user.id))
+        .filter(visible_ingredients(user.id))
+        .one(&db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    let ingredient = with_user_categories(&db, user.id, vec![ingredient])
+        .await?
+        .remove(0);
+
+    Ok(Json(ingredient))
+}
+
+pub async fn get_ingredient_aliases(
+    State(db): State<DatabaseConnection>,
+    auth: AuthSession,
+    Path(ingredient_id): Path<i32>,
+) -> Result<Json<Vec<IngredientAlias>>, ApiError> {
+    let user = auth.user.unwrap();
+    let ingredient = ingredients::Entity::find_by_id(ingredient_id)
+        .filter(visible_ingredients(user.id))
+        .one(&db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    let aliases = ingredient_aliases::Entity::find()
+        .filter(ingredient_aliases::Column::IngredientId.eq(ingredient.id))
+        .all(&db)
+        .await?
+        .into_iter()
+        .map(IngredientAlias::from)
+        .collect::<Vec<_>>();
+
+    Ok(Json(aliases))
+}
+
+// Adds an alias to an ingredient in the global catalog, e.g. "scallion" for "spring onion".
+// Only admins are allowed to do this, since aliases are shared between all users
+pub async fn post_ingredient_alias(
+    State(db): State<DatabaseConnection>,
+    auth: AuthSession,
+    Path(ingredient_id): Path<i32>,
+    Json(alias): Json<CreateIngredientAlias>,
+) -> Result<Json<IngredientAlias>, ApiError> {
+    let user = auth.user.unwrap();
+    if !is_admin(&user) {
+        return Err(forbidden());
+    }
+
+    let ingredient = ingredients::Entity::find_by_id(ingredient_id)
+        .filter(ingredients::Column::UserId.is_null())
+        .one(&db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    let normalized_name = normalize_ingredient_name(&alias.name);
+
+    let existing = ingredients::Entity::find()
+        .filter(ingredients::Column::UserId.is_null())
+        .filter(ingredients::Column::NormalizedName.eq(&normalized_name))
+        .one(&db)
+        .await?;
+
+    if existing.is_some() {
+        return Err(ApiError::ConflictError(
+            "An ingredient with that name already exists. Merge them instead".to_string(),
+        ));
+    }
+
+    let created_alias = ingredient_aliases::Entity::insert(ingredient_aliases::ActiveModel {
+        id: NotSet,
+        ingredient_id: Set(ingredient.id),
+        name: Set(alias.name.trim().to_string()),
+        normalized_name: Set(normalized_name),
+    })
+    .on_conflict(
+        OnConflict::column(ingredient_aliases::Column::NormalizedName)
+            .do_nothing()
+            .to_owned(),
+    )
+    .exec_with_returning(&db)
+    .await
+    .map_err(|err| match err {
+        DbErr::RecordNotInserted => ApiError::ConflictError("Alias is already in use".to_string()),
+        err => err.into(),
+    })?;
+
+    Ok(Json(created_alias.into()))
+}
+
+// Makes a private ingredient a part of the global catalog
+pub async fn promote_ingredient(
+    State(db): State<DatabaseConnection>,
+    auth: AuthSession,
+    Path(ingredient_id): Path<i32>,
+) -> Result<Json<Ingredient>, ApiError> {
+    let user = auth.user.unwrap();
+    if !is_admin(&user) {
+        return Err(forbidden());
+    }
+
+    let ingredient = ingredients::Entity::find_by_id(ingredient_id)
+        .one(&db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    let existing = ingredients::Entity::find()
+        .filter(ingredients::Column::UserId.is_null())
+        .filter(ingredients::Column::NormalizedName.eq(&ingredient.normalized_name))
+        .one(&db)
+        .await?;
+
+    if existing.is_some_and(|it| it.id != ingredient.id) {
+        return Err(ApiError::ConflictError(
+            "An ingredient with that name already exists. Merge them instead".to_string(),
+        ));
+    }
+
+    let promoted = ingredients::Entity::update(ingredients::ActiveModel {
+        id: Set(ingredient.id),
+        user_id: Set(None),
+        ..Default::default()
+    })
+    .exec(&db)
+    .await?;
+
+    Ok(Json(promoted.into()))
+}
+
+// Merges an ingredient into another one. All recipes using the merged ingredient are rewritten
+// to use the kept ingredient, and the merged name is kept as an alias so that it resolves to the
+// kept ingredient in the future
+pub async fn merge_ingredient(
+    State(db): State<DatabaseConnection>,
+    auth: AuthSession,
+    Path(ingredient_id): Path<i32>,
+    Json(merge): Json<MergeIngredient>,
+) -> Result<Json<Ingredient>, ApiError> {
+    let user = auth.user.unwrap();
+    if !is_admin(&user) {
+        return Err(forbidden());
+    }
+
+    if ingredient_id == merge.into_id {
+        return Err(ApiError::StatusCode(
+            StatusCode::BAD_REQUEST,
+            "Cannot merge an ingredient into itself".to_string(),
+        ));
+    }
+
+    let source = ingredients::Entity::find_by_id(ingredient_id)
+        .one(&db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    let target = ingredients::Entity::find_by_id(merge.into_id)
+        .one(&db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    // Recipes of other users must never end up pointing at a private ingredient they can't see, so
+    // only the owner of both can merge into a private ingredient
+    if target
+        .user_id
+        .is_some_and(|it| it != user.id || source.user_id != Some(user.id))
+    {
+        return Err(ApiError::StatusCode(
+            StatusCode::FORBIDDEN,
+            "Can only merge into a global ingredient or one of your own".to_string(),
+        ));
+    }
+
+    let tx = db.begin().await?;
+
+    recipe_ingredients::Entity::update_many()
+        .col_expr(
+            recipe_ingredients::Column::IngredientId,
+            Expr::value(target.id),
+        )
+        .filter(recipe_ingredients::Column::IngredientId.eq(source.id))
+        .exec(&tx)
+        .await?;
+
+    ingredient_aliases::Entity::update_many()
+        .col_expr(
+            ingredient_aliases::Column::IngredientId,
+            Expr::value(target.id),
+        )
+        .filter(ingredient_aliases::Column::IngredientId.eq(source.id))
+        .exec(&tx)
+        .await?;
+
+    ingredients::Entity::delete_by_id(source.id)
+        .exec(&tx)
+        .await?;
+
+    if target.user_id.is_none() && target.normalized_name != source.normalized_name {
+        ingredient_aliases::Entity::insert(ingredient_aliases::ActiveModel {
+            id: NotSet,
+            ingredient_id: Set(target.id),
+            name: Set(source.name),
+            normalized_name: Set(source.normalized_name),
+        })
+        .on_conflict(
+            OnConflict::column(ingredient_aliases::Column::NormalizedName)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(&tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(Json(target.into()))
+}
+
+// Sets the category of an ingredient only for the current user
+pub async fn put_ingredient_category(
+    State(db): State<DatabaseConnection>,
+    auth: AuthSession,
+    Path(ingredient_id): Path<i32>,
+    Json(update): Json<UpdateIngredientCategory>,
+) -> Result<Json<Ingredient>, ApiError> {
+    let user = auth.user.unwrap();
+    let ingredient = ingredients::Entity::find_by_id(ingredient_id)
+        .filter(visible_ingredients(user.id))
         .one(&db)
         .await?
         .ok_or(ApiError::RecordNotFound)?;
 
+    user_ingredient_categories::Entity::insert(user_ingredient_categories::ActiveModel {
+        user_id: Set(user.id),
+        ingredient_id: Set(ingredient.id),
+        category: Set(update.category.into()),
+    })
+    .on_conflict(
+        OnConflict::columns([
+            user_ingredient_categories::Column::UserId,
+            user_ingredient_categories::Column::IngredientId,
+        ])
+        .update_column(user_ingredient_categories::Column::Category)
+        .to_owned(),
+    )
+    .exec_without_returning(&db)
+    .await?;
+
     Ok(Json(Ingredient {
-        id: ingredient.id,
-        name: ingredient.name,
+        category: update.category,
+        ..ingredient.into()
     }))
 }
+
+// Removes the category override of the current user, so that the default is used again
+pub async fn delete_ingredient_category(
+    State(db): State<DatabaseConnection>,
+    auth: AuthSession,
+    Path(ingredient_id): Path<i32>,
+) -> Result<Json<Ingredient>, ApiError> {
+    let user = auth.user.unwrap();
+    let ingredient = ingredients::Entity::find_by_id(ingredient_id)
+        .filter(visible_ingredients(user.id))
+        .one(&db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    user_ingredient_categories::Entity::delete_by_id((user.id, ingredient.id))
+        .exec(&db)
+        .await?;
+
+    Ok(Json(ingredient.into()))
+}
+
+// Sets the default category of an ingredient, which is used by everyone without an override.
+// Only admins are able to change it for the global catalog
+pub async fn put_ingredient_default_category(
+    State(db): State<DatabaseConnection>,
+    auth: AuthSession,
+    Path(ingredient_id): Path<i32>,
+    Json(update): Json<UpdateIngredientCategory>,
+) -> Result<Json<Ingredient>, ApiError> {
+    let user = auth.user.unwrap();
+    let ingredient = ingredients::Entity::find_by_id(ingredient_id)
+        .filter(visible_ingredients(user.id))
+        .one(&db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    if !can_manage_ingredient(&user, &ingredient) {
+        return Err(forbidden());
+    }
+
+    let updated = ingredients::Entity::update(ingredients::ActiveModel {
+        id: Set(ingredient.id),
+        category: Set(update.category.into()),
+        ..Default::default()
+    })
+    .exec(&db)
+    .await?;
+
+    Ok(Json(updated.into()))
+}
+
+// Sets the allergens and diets of an ingredient. These are shared by everyone using the ingredient,
+// so only admins are able to change them for the global catalog
+pub async fn put_ingredient_dietary(
+    State(db): State<DatabaseConnection>,
+    auth: AuthSession,
+    Path(ingredient_id): Path<i32>,
+    Json(update): Json<UpdateIngredientDietary>,
+) -> Result<Json<Ingredient>, ApiError> {
+    let user = auth.user.unwrap();
+    let ingredient = ingredients::Entity::find_by_id(ingredient_id)
+        .filter(visible_ingredients(user.id))
+        .one(&db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    if !can_manage_ingredient(&user, &ingredient) {
+        return Err(forbidden());
+    }
+
+    let allergens = update
+        .allergens
+        .into_iter()
+        .sorted()
+        .dedup()
+        .map(|it| it.to_string())
+        .collect::<Vec<_>>();
+
+    let updated = ingredients::Entity::update(ingredients::ActiveModel {
+        id: Set(ingredient.id),
+        allergens: Set(allergens),
+        vegetarian: Set(update.vegetarian || update.vegan),
+        vegan: Set(update.vegan),
+        ..Default::default()
+    })
+    .exec(&db)
+    .await?;
+
+    let updated = with_user_categories(&db, user.id, vec![updated])
+        .await?
+        .remove(0);
+
+    Ok(Json(updated))
+}
+
+// Gets the order the user walks through the categories in their usual store
+pub async fn get_aisle_order(
+    State(db): State<DatabaseConnection>,
+    auth: AuthSession,
+) -> Result<Json<Vec<IngredientCategory>>, ApiError> {
+    let user = auth.user.unwrap();
+
+    Ok(Json(aisle_order(&db, user.id).await?))
+}
+
+// Replaces the aisle order of the user. Categories that are left out are placed last, in the
+// default order
+pub async fn put_aisle_order(
+    State(db): State<DatabaseConnection>,
+    auth: AuthSession,
+    Json(categories): Json<Vec<IngredientCategory>>,
+) -> Result<Json<Vec<IngredientCategory>>, ApiError> {
+    let user = auth.user.unwrap();
+    let tx = db.begin().await?;
+
+    user_aisle_orders::Entity::delete_many()
+        .filter(user_aisle_orders::Column::UserId.eq(user.id))
+        .exec(&tx)
+        .await?;
+
+    let models = categories
+        .into_iter()
+        .unique()
+        .enumerate()
+        .map(|(position, category)| user_aisle_orders::ActiveModel {
+            user_id: Set(user.id),
+            category: Set(category.into()),
+            position: Set(position as i32),
+        })
+        .collect::<Vec<_>>();
+
+    if !models.is_empty() {
+        user_aisle_orders::Entity::insert_many(models)
+            .exec_without_returning(&tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(Json(aisle_order(&db, user.id).await?))
+}
+
+// The ingredients a user is able to see and use: the global catalog and their own
+pub fn visible_ingredients(user_id: i32) -> Condition {
+    Condition::any()
+        .add(ingredients::Column::UserId.is_null())
+        .add(ingredients::Column::UserId.eq(user_id))
+}
+
+// Finds the ingredient to use for each of the names, keyed by the normalized name.
+// The global catalog is preferred, then aliases in the catalog and then the private ingredients
+// of the user. Names that doesn't match anything are created as private ingredients.
+pub async fn resolve_ingredients<'a, C, I>(
+    db: &C,
+    user_id: i32,
+    names: I,
+) -> Result<HashMap<String, ingredients::Model>, DbErr>
+where
+    C: ConnectionTrait,
+    I: IntoIterator<Item = &'a str>,
+{
+    // Keep the order the names were given in, so new ingredients are created in the same order as
+    // they appear in the recipe
+    let names = names
+        .into_iter()
+        .map(|name| (normalize_ingredient_name(name), name.trim().to_string()))
+        .unique_by(|(normalized_name, _)| normalized_name.clone())
+        .collect::<Vec<_>>();
+
+    let normalized_names = names.iter().map(|it| it.0.clone()).collect::<Vec<_>>();
+
+    let (global, private): (Vec<_>, Vec<_>) = ingredients::Entity::find()
+        .filter(visible_ingredients(user_id))
+        .filter(ingredients::Column::NormalizedName.is_in(normalized_names.clone()))
+        .all(db)
+        .await?
+        .into_iter()
+        .partition(|it| it.user_id.is_none());
+
+    let aliased = ingredient_aliases::Entity::find()
+        .filter(ingredient_aliases::Column::NormalizedName.is_in(normalized_names))
+        .find_also_related(ingredients::Entity)
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|(alias, ingredient)| Some((alias.normalized_name, ingredient?)));
+
+    let mut resolved = HashMap::new();
+    for (normalized_name, ingredient) in global
+        .into_iter()
+        .map(|it| (it.normalized_name.clone(), it))
+        .chain(aliased)
+        .chain(
+            private
+                .into_iter()
+                .map(|it| (it.normalized_name.clone(), it)),
+        )
+    {
+        resolved.entry(normalized_name).or_insert(ingredient);
+    }
+
+    let missing = names
+        .into_iter()
+        .filter(|(normalized_name, _)| !resolved.contains_key(normalized_name))
+        .collect::<Vec<_>>();
+
+    if missing.is_empty() {
+        return Ok(resolved);
+    }
+
+    let missing_names = missing.iter().map(|it| it.0.clone()).collect::<Vec<_>>();
+    let models = missing
+        .into_iter()
+        .map(|(normalized_name, name)| ingredients::ActiveModel {
+            id: NotSet,
+            name: Set(name),
+            user_id: Set(Some(user_id)),
+            normalized_name: Set(normalized_name),
+            category: NotSet,
+            allergens: NotSet,
+            vegetarian: NotSet,
+            vegan: NotSet,
+        });
+
+    ingredients::Entity::insert_many(models)
+        .on_conflict(OnConflict::new().do_nothing().to_owned())
+        .exec_without_returning(db)
+        .await?;
+
+    let created = ingredients::Entity::find()
+        .filter(ingredients::Column::UserId.eq(user_id))
+        .filter(ingredients::Column::NormalizedName.is_in(missing_names))
+        .all(db)
+        .await?;
+
+    for ingredient in created {
+        resolved.insert(ingredient.normalized_name.clone(), ingredient);
+    }
+
+    Ok(resolved)
+}
+
+// Converts the ingredients to what the user should see, with their own category overrides
+pub async fn with_user_categories<C>(
+    db: &C,
+    user_id: i32,
+    ingredients: Vec<ingredients::Model>,
+) -> Result<Vec<Ingredient>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let overrides = user_ingredient_categories::Entity::find()
+        .filter(user_ingredient_categories::Column::UserId.eq(user_id))
+        .filter(
+            user_ingredient_categories::Column::IngredientId
+                .is_in(ingredients.iter().map(|it| it.id)),
+        )
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|it| (it.ingredient_id, it.category))
+        .collect::<HashMap<_, _>>();
+
+    Ok(ingredients
+        .into_iter()
+        .map(|ingredient| {
+            let category = overrides
+                .get(&ingredient.id)
+                .cloned()
+                .unwrap_or(ingredient.category.clone());
+
+            Ingredient {
+                category: category.into(),
+                ..ingredient.into()
+            }
+        })
+        .collect())
+}
+
+// The full aisle order of the user. Their own order is first, followed by the categories they
+// haven't placed in the default order
+pub async fn aisle_order<C>(db: &C, user_id: i32) -> Result<Vec<IngredientCategory>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let own = user_aisle_orders::Entity::find()
+        .filter(user_aisle_orders::Column::UserId.eq(user_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .sorted_by_key(|it| it.position)
+        .map(|it| IngredientCategory::from(it.category));
+
+    Ok(own.chain(IngredientCategory::iter()).unique().collect())
+}
+
+pub fn aisle_position(aisles: &[IngredientCategory], category: IngredientCategory) -> usize {
+    aisles
+        .iter()
+        .position(|it| *it == category)
+        .unwrap_or(aisles.len())
+}
+
+// The allergens and diets of a dish made from the ingredients. It contains every allergen of the
+// ingredients, and only the diets all of them follows. Nothing is known about the diet of a dish
+// without ingredients, so it doesn't follow any
+pub fn dietary_flags(ingredients: &[ingredients::Model]) -> (Vec<Allergen>, Vec<Diet>) {
+    let allergens = ingredients
+        .iter()
+        .flat_map(|it| &it.allergens)
+        .filter_map(|it| it.parse::<Allergen>().ok())
+        .sorted()
+        .dedup()
+        .collect();
+
+    let diets = [
+        (Diet::Vegetarian, ingredients.iter().all(|it| it.vegetarian)),
+        (Diet::Vegan, ingredients.iter().all(|it| it.vegan)),
+    ]
+    .into_iter()
+    .filter_map(|(diet, follows)| (follows && !ingredients.is_empty()).then_some(diet))
+    .collect();
+
+    (allergens, diets)
+}
+
+fn forbidden() -> ApiError {
+    ApiError::StatusCode(
+        StatusCode::FORBIDDEN,
+        "Only admins are allowed to do this".to_string(),
+    )
+}
+
+fn can_manage_ingredient(user: &User, ingredient: &ingredients::Model) -> bool {
+    match ingredient.user_id {
+        Some(user_id) => user_id == user.id,
+        None => is_admin(user),
+    }
+}
+
+impl From<ingredients::Model> for Ingredient {
+    fn from(value: ingredients::Model) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            user_id: value.user_id,
+            category: value.category.into(),
+            allergens: value
+                .allergens
+                .iter()
+                .filter_map(|it| it.parse().ok())
+                .collect(),
+            vegetarian: value.vegetarian,
+            vegan: value.vegan,
+        }
+    }
+}
+
+impl From<ingredient_aliases::Model> for IngredientAlias {
+    fn from(value: ingredient_aliases::Model) -> Self {
+        Self {
+            id: value.id,
+            ingredient_id: value.ingredient_id,
+            name: value.name,
+        }
+    }
+}
+
+macro_rules! convert_category {
+    ($first:ty, $second: ty) => {
+        impl From<$first> for $second {
+            fn from(value: $first) -> Self {
+                match value {
+                    <$first>::Produce => <$second>::Produce,
+                    <$first>::Dairy => <$second>::Dairy,
+                    <$first>::Meat => <$second>::Meat,
+                    <$first>::Seafood => <$second>::Seafood,
+                    <$first>::Bakery => <$second>::Bakery,
+                    <$first>::DryGoods => <$second>::DryGoods,
+                    <$first>::Spices => <$second>::Spices,
+                    <$first>::Frozen => <$second>::Frozen,
+                    <$first>::Beverages => <$second>::Beverages,
+                    <$first>::Other => <$second>::Other,
+                }
+            }
+        }
+    };
+}
+
+convert_category!(
+    common::ingredient::IngredientCategory,
+    sea_orm_active_enums::IngredientCategory
+);
+convert_category!(
+    sea_orm_active_enums::IngredientCategory,
+    common::ingredient::IngredientCategory
+);
@@ -0,0 +1,205 @@
+use axum::{extract::State, Json};
+use chrono::Utc;
+use common::invite::{CreateInvite, Invite};
+use hyper::StatusCode;
+use sea_orm::{
+    sea_query::{Expr, Func},
+    ActiveValue::NotSet,
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    auth_backend::AuthSession,
+    entities::{friendships, invites, sea_orm_active_enums::FriendshipStatus, users},
+    mailer::Mail,
+    storage::FoodieStorage,
+    ApiError,
+};
+
+// How long the link in an invite can be used to register
+const INVITE_EXPIRY: chrono::Duration = chrono::Duration::days(7);
+
+// Invites someone by email. The mail has a link that lets them register even if they are not in
+// the list of allowed emails, and they become friends with the inviter when they do
+pub async fn post_invite<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Json(invite): Json<CreateInvite>,
+) -> Result<(StatusCode, Json<Invite>), ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+    let email = invite.email.trim().to_string();
+
+    if !is_valid_email(&email) {
+        return Err(ApiError::StatusCode(
+            StatusCode::BAD_REQUEST,
+            "Not a valid email".to_string(),
+        ));
+    }
+
+    if has_user_with_email(&state.db, &email).await? {
+        return Err(ApiError::ConflictError(
+            "There is already a user with this email".to_string(),
+        ));
+    }
+
+    let token = Uuid::new_v4().simple().to_string();
+    let expires_at = Utc::now() + INVITE_EXPIRY;
+    let invite = invites::Entity::insert(invites::ActiveModel {
+        id: NotSet,
+        inviter_id: Set(user.id),
+        email: Set(email.clone()),
+        token: Set(token.clone()),
+        created_at: NotSet,
+        expires_at: Set(expires_at.into()),
+        accepted_at: Set(None),
+    })
+    .exec_with_returning(&state.db)
+    .await?;
+
+    let frontend_url = dotenv::var("FRONTEND_URL").expect("FRONTEND_URL is not set");
+    let mail = Mail {
+        to: email,
+        subject: format!("{} invited you to Foodie", user.name),
+        body: format!(
+            "{} has invited you to Foodie, to share recipes and decide what to eat together.\n\n\
+             Register with this link before {}:\n{frontend_url}/register?invite={token}\n",
+            user.name,
+            expires_at.format("%e %B %Y"),
+        ),
+    };
+
+    // An invite nobody got is no use, so it is removed again to let the user try once more
+    if let Err(err) = state.mailer.send(&mail).await {
+        invites::Entity::delete_by_id(invite.id)
+            .exec(&state.db)
+            .await?;
+        return Err(ApiError::UnknownError(format!(
+            "Failed to send the invite: {err}"
+        )));
+    }
+
+    Ok((StatusCode::CREATED, Json(to_invite(invite))))
+}
+
+// The invites the user has sent, newest first
+pub async fn get_invites<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+) -> Result<Json<Vec<Invite>>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let invites = invites::Entity::find()
+        .filter(invites::Column::InviterId.eq(user.id))
+        .order_by_desc(invites::Column::CreatedAt)
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(to_invite)
+        .collect();
+
+    Ok(Json(invites))
+}
+
+// Finds the invite with the token, as long as it can still be used to register with the email
+pub async fn find_invite<C>(db: &C, token: &str, email: &str) -> Result<invites::Model, ApiError>
+where
+    C: ConnectionTrait,
+{
+    let invite = invites::Entity::find()
+        .filter(invites::Column::Token.eq(token))
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    if invite.accepted_at.is_some() || invite.expires_at < Utc::now() {
+        return Err(ApiError::StatusCode(
+            StatusCode::GONE,
+            "The invite is already used or has expired".to_string(),
+        ));
+    }
+
+    if !invite.email.eq_ignore_ascii_case(email.trim()) {
+        return Err(ApiError::StatusCode(
+            StatusCode::FORBIDDEN,
+            "The invite is for another email".to_string(),
+        ));
+    }
+
+    Ok(invite)
+}
+
+// Uses up the invite for the registered user, and makes them friends with the inviter
+pub async fn accept_invite<C>(db: &C, invite: &invites::Model, user_id: i32) -> Result<(), ApiError>
+where
+    C: ConnectionTrait,
+{
+    let now = Utc::now();
+
+    // Only one registration can use the invite, even if two are racing for it
+    let accepted = invites::Entity::update_many()
+        .col_expr(invites::Column::AcceptedAt, Expr::value(now))
+        .filter(invites::Column::Id.eq(invite.id))
+        .filter(invites::Column::AcceptedAt.is_null())
+        .exec(db)
+        .await?;
+    if accepted.rows_affected == 0 {
+        return Err(ApiError::StatusCode(
+            StatusCode::GONE,
+            "The invite is already used or has expired".to_string(),
+        ));
+    }
+
+    friendships::Entity::insert(friendships::ActiveModel {
+        requester_id: Set(invite.inviter_id),
+        recipient_id: Set(user_id),
+        status: Set(FriendshipStatus::Accepted),
+        requested_at: Set(invite.created_at),
+        responded_at: Set(Some(now.into())),
+    })
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(())
+}
+
+// Emails are compared without case, since that is how people expect them to work
+pub async fn has_user_with_email<C>(db: &C, email: &str) -> Result<bool, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let user = users::Entity::find()
+        .filter(Expr::expr(Func::lower(Expr::col(users::Column::Email))).eq(email.to_lowercase()))
+        .one(db)
+        .await?;
+    Ok(user.is_some())
+}
+
+fn is_valid_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.contains('@')
+                && !email.chars().any(|c| c.is_whitespace() || c.is_control())
+        }
+        None => false,
+    }
+}
+
+fn to_invite(invite: invites::Model) -> Invite {
+    Invite {
+        id: invite.id,
+        email: invite.email,
+        created_at: invite.created_at,
+        expires_at: invite.expires_at,
+        accepted: invite.accepted_at.is_some(),
+    }
+}
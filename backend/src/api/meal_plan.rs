@@ -0,0 +1,236 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
+use common::meal_plan::{CalendarFeed, CreatePlannedMeal, PlannedMeal};
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue::NotSet, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    api::recipe::{duration, has_access_to_recipe, recipe_total_time},
+    auth_backend::AuthSession,
+    calendar::{meal_plan_calendar, CalendarMeal},
+    entities::{calendar_feeds, planned_meals, recipes},
+    ApiError,
+};
+
+// Calendar apps keep the events they have already fetched, so the feed only needs the recent past
+const CALENDAR_HISTORY_DAYS: i64 = 30;
+
+// Plans a recipe to be ready to eat at a given time
+pub async fn post_planned_meal(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Json(meal): Json<CreatePlannedMeal>,
+) -> Result<Json<PlannedMeal>, ApiError> {
+    let user = auth.user.unwrap();
+
+    let recipe = recipes::Entity::find_by_id(meal.recipe_id)
+        .filter(has_access_to_recipe(&db, user.id).await?)
+        .one(&db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    let planned_meal = planned_meals::Entity::insert(planned_meals::ActiveModel {
+        id: NotSet,
+        user_id: Set(user.id),
+        recipe_id: Set(recipe.id),
+        eat_at: Set(meal.eat_at),
+        created_at: NotSet,
+    })
+    .exec_with_returning(&db)
+    .await?;
+
+    Ok(Json(to_planned_meal(planned_meal, recipe)))
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct MealPlanQuery {
+    // Defaults to the start of yesterday, so the meals of today are always included
+    from: Option<DateTime<FixedOffset>>,
+    to: Option<DateTime<FixedOffset>>,
+}
+
+// Gets the planned meals of the user, the earliest first
+pub async fn get_meal_plan(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Query(query): Query<MealPlanQuery>,
+) -> Result<Json<Vec<PlannedMeal>>, ApiError> {
+    let user = auth.user.unwrap();
+
+    let from = query
+        .from
+        .unwrap_or_else(|| (Utc::now() - TimeDelta::days(1)).into());
+
+    let mut select = planned_meals::Entity::find()
+        .filter(planned_meals::Column::UserId.eq(user.id))
+        .filter(planned_meals::Column::EatAt.gte(from));
+    if let Some(to) = query.to {
+        select = select.filter(planned_meals::Column::EatAt.lt(to));
+    }
+
+    let meals = select
+        .find_also_related(recipes::Entity)
+        .order_by_asc(planned_meals::Column::EatAt)
+        .all(&db)
+        .await?
+        .into_iter()
+        .filter_map(|(meal, recipe)| Some(to_planned_meal(meal, recipe?)))
+        .collect();
+
+    Ok(Json(meals))
+}
+
+pub async fn delete_planned_meal(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Path(meal_id): Path<i32>,
+) -> Result<(), ApiError> {
+    let user = auth.user.unwrap();
+
+    let res = planned_meals::Entity::delete_many()
+        .filter(planned_meals::Column::Id.eq(meal_id))
+        .filter(planned_meals::Column::UserId.eq(user.id))
+        .exec(&db)
+        .await?;
+
+    match res.rows_affected {
+        0 => Err(ApiError::RecordNotFound),
+        _ => Ok(()),
+    }
+}
+
+// Gets the url of the calendar feed of the user, and creates the feed the first time
+pub async fn get_calendar_feed(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+) -> Result<Json<CalendarFeed>, ApiError> {
+    let user = auth.user.unwrap();
+
+    let feed = calendar_feeds::Entity::find_by_id(user.id).one(&db).await?;
+    let token = match feed {
+        Some(feed) => feed.token,
+        None => save_calendar_token(&db, user.id).await?,
+    };
+
+    Ok(Json(CalendarFeed {
+        url: calendar_url(&token),
+    }))
+}
+
+// Replaces the token of the calendar feed, so the old url stops working. Used if the url has
+// been shared with someone it shouldn't have been
+pub async fn reset_calendar_feed(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+) -> Result<Json<CalendarFeed>, ApiError> {
+    let user = auth.user.unwrap();
+
+    let token = save_calendar_token(&db, user.id).await?;
+
+    Ok(Json(CalendarFeed {
+        url: calendar_url(&token),
+    }))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CalendarQuery {
+    // Whether to include prep reminders for recipes that have to rest. Defaults to true
+    reminders: Option<bool>,
+}
+
+// The calendar feed itself. Calendar apps can't log in, so it is public and found by the secret
+// token in the url
+pub async fn get_calendar(
+    State(db): State<DatabaseConnection>,
+    Path(file): Path<String>,
+    Query(query): Query<CalendarQuery>,
+) -> Result<Response, ApiError> {
+    let token = file.strip_suffix(".ics").unwrap_or(&file);
+
+    let feed = calendar_feeds::Entity::find()
+        .filter(calendar_feeds::Column::Token.eq(token))
+        .one(&db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    let meals = planned_meals::Entity::find()
+        .filter(planned_meals::Column::UserId.eq(feed.user_id))
+        .filter(
+            planned_meals::Column::EatAt.gte(Utc::now() - TimeDelta::days(CALENDAR_HISTORY_DAYS)),
+        )
+        .find_also_related(recipes::Entity)
+        .order_by_asc(planned_meals::Column::EatAt)
+        .all(&db)
+        .await?
+        .into_iter()
+        .filter_map(|(meal, recipe)| {
+            let recipe = recipe?;
+            Some(CalendarMeal {
+                id: meal.id,
+                recipe_id: recipe.id,
+                total_time: recipe_total_time(&recipe),
+                resting_time: duration(recipe.resting_minutes),
+                name: recipe.name,
+                eat_at: meal.eat_at.to_utc(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let frontend_url = dotenv::var("FRONTEND_URL").expect("FRONTEND_URL is not set");
+    let calendar = meal_plan_calendar(&meals, &frontend_url, query.reminders.unwrap_or(true));
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        calendar,
+    )
+        .into_response())
+}
+
+async fn save_calendar_token<C>(db: &C, user_id: i32) -> Result<String, ApiError>
+where
+    C: ConnectionTrait,
+{
+    let token = Uuid::new_v4().simple().to_string();
+
+    calendar_feeds::Entity::insert(calendar_feeds::ActiveModel {
+        user_id: Set(user_id),
+        token: Set(token.clone()),
+        created_at: Set(Utc::now().into()),
+    })
+    .on_conflict(
+        OnConflict::column(calendar_feeds::Column::UserId)
+            .update_columns([
+                calendar_feeds::Column::Token,
+                calendar_feeds::Column::CreatedAt,
+            ])
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(token)
+}
+
+fn calendar_url(token: &str) -> String {
+    let base_url = dotenv::var("BASE_URL").expect("BASE_URL is not set");
+    format!("{base_url}/api/calendar/{token}.ics")
+}
+
+fn to_planned_meal(meal: planned_meals::Model, recipe: recipes::Model) -> PlannedMeal {
+    PlannedMeal {
+        id: meal.id,
+        recipe_id: recipe.id,
+        total_time: recipe_total_time(&recipe),
+        name: recipe.name,
+        eat_at: meal.eat_at,
+    }
+}
@@ -1,9 +1,24 @@
+use common::user::User;
+
 pub mod auth;
+pub mod collection;
+pub mod duplicate;
+pub mod feed;
 pub mod friends;
 pub mod ingredient;
+pub mod invite;
+pub mod meal_plan;
+pub mod notification;
 pub mod oauth;
+pub mod pdf;
+pub mod profile;
 pub mod recipe;
+pub mod shopping_list;
+pub mod suggestion;
+pub mod swipe;
+pub mod takeout;
 pub mod users;
+pub mod voting;
 pub mod websocket;
 
 pub fn allowed_mails() -> Vec<String> {
@@ -13,3 +28,16 @@ pub fn allowed_mails() -> Vec<String> {
         .map(|it| it.to_string())
         .collect::<Vec<_>>()
 }
+
+pub fn admin_mails() -> Vec<String> {
+    dotenv::var("ADMIN_MAILS")
+        .unwrap_or_default()
+        .split(',')
+        .filter(|it| !it.is_empty())
+        .map(|it| it.to_string())
+        .collect::<Vec<_>>()
+}
+
+pub fn is_admin(user: &User) -> bool {
+    admin_mails().contains(&user.email)
+}
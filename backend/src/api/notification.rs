@@ -0,0 +1,196 @@
+use axum::{extract::State, Json};
+use common::{
+    notification::{Notification, ReadNotifications, UnreadNotifications},
+    user::User,
+    websocket::FoodieMessageType,
+};
+use sea_orm::{
+    sea_query::Expr, ActiveValue::NotSet, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+
+use crate::{
+    api::websocket::send_message,
+    app::AppState,
+    auth_backend::AuthSession,
+    entities::{notifications, sea_orm_active_enums::NotificationKind, users},
+    storage::FoodieStorage,
+    ApiError,
+};
+
+// The most notifications that are listed, the older ones are still counted as unread
+const MAX_NOTIFICATIONS: u64 = 50;
+
+// What the notification is about, besides the user that caused it
+#[derive(Default)]
+pub struct NotificationTarget {
+    pub recipe_id: Option<i32>,
+    pub voting_session_id: Option<i32>,
+}
+
+// Stores a notification for each of the users and pushes it to the ones that are connected. The
+// event it is about has already happened, so failing to notify is only logged
+pub async fn notify<T>(
+    state: &AppState<T>,
+    user_ids: &[i32],
+    kind: NotificationKind,
+    actor: &User,
+    target: NotificationTarget,
+) where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    if user_ids.is_empty() {
+        return;
+    }
+
+    let models = user_ids.iter().map(|user_id| notifications::ActiveModel {
+        id: NotSet,
+        user_id: Set(*user_id),
+        kind: Set(kind.clone()),
+        actor_id: Set(Some(actor.id)),
+        recipe_id: Set(target.recipe_id),
+        voting_session_id: Set(target.voting_session_id),
+        created_at: NotSet,
+        read_at: NotSet,
+    });
+
+    let created = match notifications::Entity::insert_many(models)
+        .exec_with_returning_many(&state.db)
+        .await
+    {
+        Ok(created) => created,
+        Err(err) => {
+            log::warn!("Failed to store {kind:?} notifications: {err}");
+            return;
+        }
+    };
+
+    for model in created {
+        let user_id = model.user_id;
+        send_message(
+            &state.connections,
+            user_id,
+            FoodieMessageType::Notification(to_notification(model, Some(actor.name.clone()))),
+        );
+    }
+}
+
+// Gets the latest notifications of the user, the newest first
+pub async fn get_notifications<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+) -> Result<Json<Vec<Notification>>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let models = notifications::Entity::find()
+        .filter(notifications::Column::UserId.eq(user.id))
+        .order_by_desc(notifications::Column::CreatedAt)
+        .order_by_desc(notifications::Column::Id)
+        .limit(MAX_NOTIFICATIONS)
+        .all(&state.db)
+        .await?;
+
+    let names = users::Entity::find()
+        .filter(users::Column::Id.is_in(models.iter().filter_map(|it| it.actor_id)))
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|it| (it.id, it.name))
+        .collect::<std::collections::HashMap<_, _>>();
+
+    Ok(Json(
+        models
+            .into_iter()
+            .map(|it| {
+                let name = it.actor_id.and_then(|id| names.get(&id).cloned());
+                to_notification(it, name)
+            })
+            .collect(),
+    ))
+}
+
+pub async fn get_unread_notifications<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+) -> Result<Json<UnreadNotifications>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+    Ok(Json(unread_notifications(&state.db, user.id).await?))
+}
+
+// Marks the notifications as read, or all of them if no ids are given
+pub async fn read_notifications<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Json(read): Json<ReadNotifications>,
+) -> Result<Json<UnreadNotifications>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let mut query = notifications::Entity::update_many()
+        .col_expr(
+            notifications::Column::ReadAt,
+            Expr::current_timestamp().into(),
+        )
+        .filter(notifications::Column::UserId.eq(user.id))
+        .filter(notifications::Column::ReadAt.is_null());
+
+    if !read.ids.is_empty() {
+        query = query.filter(notifications::Column::Id.is_in(read.ids));
+    }
+
+    query.exec(&state.db).await?;
+
+    Ok(Json(unread_notifications(&state.db, user.id).await?))
+}
+
+async fn unread_notifications<C>(db: &C, user_id: i32) -> Result<UnreadNotifications, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let count = notifications::Entity::find()
+        .filter(notifications::Column::UserId.eq(user_id))
+        .filter(notifications::Column::ReadAt.is_null())
+        .count(db)
+        .await?;
+
+    Ok(UnreadNotifications { count })
+}
+
+fn to_notification(model: notifications::Model, actor_name: Option<String>) -> Notification {
+    Notification {
+        id: model.id,
+        kind: model.kind.into(),
+        actor_id: model.actor_id,
+        actor_name,
+        recipe_id: model.recipe_id,
+        voting_session_id: model.voting_session_id,
+        created_at: model.created_at,
+        read: model.read_at.is_some(),
+    }
+}
+
+macro_rules! convert_kind {
+    ($first:ty, $second: ty) => {
+        impl From<$first> for $second {
+            fn from(value: $first) -> Self {
+                match value {
+                    <$first>::FriendRequest => <$second>::FriendRequest,
+                    <$first>::FriendRequestAccepted => <$second>::FriendRequestAccepted,
+                    <$first>::FriendRecipe => <$second>::FriendRecipe,
+                    <$first>::VotingSessionInvite => <$second>::VotingSessionInvite,
+                }
+            }
+        }
+    };
+}
+
+convert_kind!(NotificationKind, common::notification::NotificationKind);
+convert_kind!(common::notification::NotificationKind, NotificationKind);
@@ -0,0 +1,124 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use common::shopping_list::CreateShoppingList;
+use hyper::StatusCode;
+use image::DynamicImage;
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+
+use crate::{
+    api::{recipe::find_recipe, shopping_list::build_shopping_list},
+    app::AppState,
+    auth_backend::AuthSession,
+    pdf::{recipe_pdf, scale_recipe, shopping_list_pdf},
+    storage::FoodieStorage,
+    ApiError,
+};
+
+// Large photos are scaled down, since they are never printed bigger than this anyway
+const MAX_IMAGE_SIZE: u32 = 1200;
+
+#[derive(Deserialize, Debug, Default)]
+pub struct RecipePdfQuery {
+    // The servings to print the ingredients for. Defaults to the servings of the recipe
+    servings: Option<i32>,
+}
+
+// Renders a printable PDF of a recipe
+pub async fn get_recipe_pdf<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Path(recipe_id): Path<i32>,
+    Query(query): Query<RecipePdfQuery>,
+) -> Result<Response, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let recipe = find_recipe(&state, user.id, recipe_id).await?;
+    let recipe = match query.servings {
+        Some(servings) if servings <= 0 => {
+            return Err(ApiError::StatusCode(
+                StatusCode::BAD_REQUEST,
+                "Servings must be positive".to_string(),
+            ))
+        }
+        Some(servings) => scale_recipe(recipe, servings),
+        None => recipe,
+    };
+
+    // The recipe is still worth printing without the image, so failing to get it is not an error
+    let image = match &recipe.img {
+        Some(url) => fetch_image(url)
+            .await
+            .inspect_err(|err| log::warn!("Failed to get the image of recipe {recipe_id}: {err}"))
+            .ok(),
+        None => None,
+    };
+
+    let filename = format!("{}.pdf", file_stem(&recipe.name));
+    let pdf = tokio::task::spawn_blocking(move || recipe_pdf(&recipe, image.as_ref()))
+        .await
+        .map_err(|err| ApiError::UnknownError(err.to_string()))??;
+
+    Ok(pdf_response(pdf, &filename))
+}
+
+// Renders a printable PDF of the shopping list for a set of recipes
+pub async fn post_shopping_list_pdf(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Json(shopping_list): Json<CreateShoppingList>,
+) -> Result<Response, ApiError> {
+    let user = auth.user.unwrap();
+
+    let shopping_list = build_shopping_list(&db, user.id, shopping_list.recipe_ids).await?;
+    let pdf = shopping_list_pdf(&shopping_list)?;
+
+    Ok(pdf_response(pdf, "shopping-list.pdf"))
+}
+
+async fn fetch_image(url: &str) -> Result<DynamicImage, anyhow::Error> {
+    let bytes = reqwest::get(url).await?.error_for_status()?.bytes().await?;
+    let image = image::load_from_memory(&bytes)?;
+
+    Ok(match image.width().max(image.height()) > MAX_IMAGE_SIZE {
+        true => image.thumbnail(MAX_IMAGE_SIZE, MAX_IMAGE_SIZE),
+        false => image,
+    })
+}
+
+fn pdf_response(pdf: Vec<u8>, filename: &str) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{filename}\""),
+            ),
+        ],
+        pdf,
+    )
+        .into_response()
+}
+
+// Keeps the file name to characters that are safe in a header, e.g. "Pizza Margherita" becomes
+// "pizza-margherita"
+fn file_stem(name: &str) -> String {
+    let stem = name
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|it| !it.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    match stem.is_empty() {
+        true => "recipe".to_string(),
+        false => stem,
+    }
+}
@@ -0,0 +1,239 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use common::user::{
+    FriendProfile, Profile, UnitSystem as CommonUnitSystem, UpdateAvatar, UpdateProfile,
+};
+use hyper::{Method, StatusCode};
+use sea_orm::{ActiveValue::Set, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    api::{
+        recipe::{_get_recipes, has_access_to_recipe},
+        users::fetch_user_relationships,
+    },
+    app::AppState,
+    auth_backend::AuthSession,
+    entities::{
+        recipes,
+        sea_orm_active_enums::{FriendshipStatus, UnitSystem},
+        users,
+    },
+    storage::FoodieStorage,
+    ApiError,
+};
+
+const MAX_DISPLAY_NAME_LENGTH: usize = 50;
+const MAX_BIO_LENGTH: usize = 500;
+
+// The profile of the user, as their friends see it
+pub async fn get_profile<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+) -> Result<Json<Profile>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+    let model = find_user(&state, user.id).await?;
+    Ok(Json(to_profile(&state.storage, model).await))
+}
+
+// Updates the profile of the user. Empty text is the same as not setting it
+pub async fn update_profile<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Json(profile): Json<UpdateProfile>,
+) -> Result<Json<Profile>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let display_name = non_empty(profile.display_name);
+    let bio = non_empty(profile.bio);
+    if display_name
+        .as_ref()
+        .is_some_and(|it| it.chars().count() > MAX_DISPLAY_NAME_LENGTH)
+        || bio
+            .as_ref()
+            .is_some_and(|it| it.chars().count() > MAX_BIO_LENGTH)
+    {
+        return Err(ApiError::StatusCode(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "The display name can be {MAX_DISPLAY_NAME_LENGTH} characters, and the bio {MAX_BIO_LENGTH}"
+            ),
+        ));
+    }
+
+    let model = users::Entity::update(users::ActiveModel {
+        id: Set(user.id),
+        display_name: Set(display_name),
+        bio: Set(bio),
+        preferred_units: Set(profile.preferred_units.into()),
+        ..Default::default()
+    })
+    .exec(&state.db)
+    .await?;
+
+    Ok(Json(to_profile(&state.storage, model).await))
+}
+
+// Sets an uploaded image as the avatar of the user, and deletes the one it replaces
+pub async fn put_avatar<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Json(avatar): Json<UpdateAvatar>,
+) -> Result<Json<Profile>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    // The image is deleted when it is replaced, so it can't be one that is used by something else
+    let recipe = recipes::Entity::find()
+        .filter(recipes::Column::Img.eq(avatar.id))
+        .one(&state.db)
+        .await?;
+    let other_user = users::Entity::find()
+        .filter(users::Column::Avatar.eq(avatar.id))
+        .filter(users::Column::Id.ne(user.id))
+        .one(&state.db)
+        .await?;
+    if recipe.is_some() || other_user.is_some() {
+        return Err(ApiError::ConflictError(
+            "The image is already in use".to_string(),
+        ));
+    }
+
+    set_avatar(&state, user.id, Some(avatar.id)).await
+}
+
+// Removes the uploaded avatar, so the picture from Google is shown again if there is one
+pub async fn delete_avatar<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+) -> Result<Json<Profile>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+    set_avatar(&state, user.id, None).await
+}
+
+// The profile of a friend, with the recipes they share with the user
+pub async fn get_friend_profile<T>(
+    auth: AuthSession,
+    Path(id): Path<i32>,
+    State(state): State<AppState<T>>,
+) -> Result<Json<FriendProfile>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let is_friend = fetch_user_relationships(&state.db, user.id, "")
+        .await?
+        .into_iter()
+        .any(|it| it.id == id && it.status == Some(FriendshipStatus::Accepted.into()));
+    if id != user.id && !is_friend {
+        return Err(ApiError::RecordNotFound);
+    }
+
+    let model = find_user(&state, id).await?;
+    let profile = to_profile(&state.storage, model).await;
+
+    let recipes = recipes::Entity::find()
+        .filter(
+            Condition::all()
+                .add(recipes::Column::UserId.eq(id))
+                .add(has_access_to_recipe(&state.db, user.id).await?),
+        )
+        .order_by_desc(recipes::Column::UpdatedAt)
+        .all(&state.db)
+        .await?;
+    let Json(recipes) = _get_recipes(recipes, state).await?;
+
+    Ok(Json(FriendProfile { profile, recipes }))
+}
+
+async fn set_avatar<T>(
+    state: &AppState<T>,
+    user_id: i32,
+    avatar: Option<uuid::Uuid>,
+) -> Result<Json<Profile>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let old = find_user(state, user_id).await?.avatar;
+
+    let model = users::Entity::update(users::ActiveModel {
+        id: Set(user_id),
+        avatar: Set(avatar),
+        ..Default::default()
+    })
+    .exec(&state.db)
+    .await?;
+
+    // The avatar is already replaced, so failing to clean up the old image is not an error for
+    // the user
+    if let Some(old) = old.filter(|it| Some(*it) != avatar) {
+        if let Err(err) = state.storage.delete(old).await {
+            log::warn!("Failed to delete avatar {old} of user {user_id}: {err}");
+        }
+    }
+
+    Ok(Json(to_profile(&state.storage, model).await))
+}
+
+async fn find_user<T>(state: &AppState<T>, id: i32) -> Result<users::Model, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    users::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)
+}
+
+async fn to_profile<T>(storage: &T, model: users::Model) -> Profile
+where
+    T: FoodieStorage,
+{
+    let avatar = match model.avatar {
+        Some(avatar) => storage.get_presigned_url(avatar, Method::GET).await.ok(),
+        None => model.picture,
+    };
+
+    Profile {
+        id: model.id,
+        name: model.name,
+        display_name: model.display_name,
+        bio: model.bio,
+        avatar,
+        preferred_units: model.preferred_units.into(),
+    }
+}
+
+fn non_empty(text: Option<String>) -> Option<String> {
+    text.map(|it| it.trim().to_string())
+        .filter(|it| !it.is_empty())
+}
+
+macro_rules! convert_unit_system {
+    ($first:ty, $second: ty) => {
+        impl From<$first> for $second {
+            fn from(value: $first) -> Self {
+                match value {
+                    <$first>::Metric => <$second>::Metric,
+                    <$first>::Imperial => <$second>::Imperial,
+                }
+            }
+        }
+    };
+}
+
+convert_unit_system!(UnitSystem, CommonUnitSystem);
+convert_unit_system!(CommonUnitSystem, UnitSystem);
@@ -1,35 +1,59 @@
+use std::collections::HashMap;
+
 use crate::{
-    api::users::fetch_user_relationships,
+    api::{
+        duplicate::find_duplicates,
+        ingredient::{dietary_flags, resolve_ingredients},
+        notification::{notify, NotificationTarget},
+        users::fetch_user_relationships,
+        websocket::send_message,
+    },
     app::AppState,
     auth_backend::AuthSession,
     entities::{
-        ingredients, recipe_ingredients, recipes,
-        sea_orm_active_enums::{self, FriendshipStatus, RecipeVisibility},
+        ingredients, recipe_ingredients, recipe_views, recipes,
+        sea_orm_active_enums::{self, FriendshipStatus, NotificationKind, RecipeVisibility},
     },
     storage::FoodieStorage,
     ApiError,
 };
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::IntoResponse,
     Json,
 };
 use common::{
-    recipe::{CreateRecipe, Recipe, RecipeImage, RecipeIngredient},
+    ingredient::{normalize_ingredient_name, Allergen, Diet},
+    recipe::{
+        CreateRecipe, CreateRecipeIngredient, Duration, Recipe, RecipeDifficulty, RecipeImage,
+        RecipeIngredient,
+    },
     websocket::FoodieMessageType,
 };
 use futures_util::{future::join_all, StreamExt};
-use hyper::Method;
+use hyper::{Method, StatusCode};
+use itertools::Itertools;
+use sea_orm::sea_query::{Expr, Func, OnConflict};
 use sea_orm::{
-    sea_query::OnConflict, ActiveValue::NotSet, ColumnTrait, Condition, ConnectionTrait,
-    DatabaseConnection, EntityTrait, LoaderTrait, QueryFilter, Set, StreamTrait, TransactionTrait,
+    ActiveValue::NotSet, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, LoaderTrait, QueryFilter, QueryOrder, QuerySelect, Set, StreamTrait,
+    TransactionTrait,
 };
+use serde::Deserialize;
 use uuid::Uuid;
 
+#[derive(Deserialize, Debug, Default)]
+pub struct CreateRecipeQuery {
+    // Creates the recipe even if it looks like a duplicate of another recipe
+    #[serde(default)]
+    allow_duplicate: bool,
+}
+
 // Creates a recipe. Dependant on that the ingredients are already created
 pub async fn post_recipe<T>(
     auth: AuthSession,
     State(state): State<AppState<T>>,
+    Query(query): Query<CreateRecipeQuery>,
     Json(recipe): Json<CreateRecipe>,
 ) -> Result<Json<Recipe>, ApiError>
 where
@@ -37,6 +61,21 @@ where
 {
     let user = auth.user.unwrap();
 
+    validate_source_url(&recipe.source_url)?;
+
+    if !query.allow_duplicate {
+        let ingredient_names = recipe
+            .ingredients
+            .iter()
+            .map(|it| it.name.as_str())
+            .collect::<Vec<_>>();
+        let duplicates =
+            find_duplicates(&state.db, user.id, &recipe.name, &ingredient_names).await?;
+        if !duplicates.is_empty() {
+            return Err(ApiError::DuplicateRecipes(duplicates));
+        }
+    }
+
     let created_ingredients = create_ingredients(&recipe, user.id, &state.db).await?;
 
     let tx = state.db.begin().await?;
@@ -49,8 +88,15 @@ where
         instructions: Set(recipe.instructions),
         img: Set(recipe.img),
         servings: Set(recipe.servings),
-        prep_time: Set(recipe.prep_time),
-        baking_time: Set(recipe.baking_time),
+        prep_minutes: Set(minutes(recipe.prep_time)),
+        cooking_minutes: Set(minutes(recipe.cooking_time)),
+        baking_minutes: Set(minutes(recipe.baking_time)),
+        resting_minutes: Set(minutes(recipe.resting_time)),
+        source_url: Set(non_empty(&recipe.source_url)),
+        source_name: Set(non_empty(&recipe.source_name)),
+        cuisine: Set(non_empty(&recipe.cuisine)),
+        difficulty: Set(recipe.difficulty.map(|it| it.into())),
+        yield_unit: Set(non_empty(&recipe.yield_unit)),
         created_at: NotSet,
         updated_at: NotSet,
         visibility: Set(recipe.visibility.into()),
@@ -58,37 +104,51 @@ where
     .exec_with_returning(&tx)
     .await?;
 
-    let models = created_ingredients.into_iter().filter_map(|i| {
-        let ri = recipe.ingredients.iter().find(|ri| ri.name == i.name)?;
-        Some(recipe_ingredients::ActiveModel {
-            recipe_id: Set(created_recipe.id),
-            ingredient_id: Set(i.id),
-            unit: Set(ri.unit.map(|u| u.into())),
-            amount: Set(ri.amount),
-        })
-    });
+    let models =
+        recipe_ingredient_models(created_recipe.id, &recipe.ingredients, &created_ingredients);
 
     recipe_ingredients::Entity::insert_many(models)
-        .exec(&tx)
+        .exec_without_returning(&tx)
         .await?;
 
     tx.commit().await?;
 
     let ingredients = get_recipe_ingredients(&state.db, created_recipe.id).await?;
+    let (allergens, diets) = get_recipe_dietary_flags(&state.db, created_recipe.id).await?;
 
-    let recipe_image = get_presigned_url_for_get(state.storage, created_recipe.img).await?;
+    let recipe_image = get_presigned_url_for_get(state.storage.clone(), created_recipe.img).await?;
 
-    let friends = fetch_user_relationships(&state.db, user.id, "")
+    let friend_ids = fetch_user_relationships(&state.db, user.id, "")
         .await?
         .into_iter()
-        .filter(|it| it.status == Some(FriendshipStatus::Accepted.into()));
+        .filter(|it| it.status == Some(FriendshipStatus::Accepted.into()))
+        .map(|it| it.id)
+        .collect::<Vec<_>>();
 
-    for friend in friends {
-        if let Some(tx) = state.connections.read().unwrap().get(&friend.id) {
-            let _ = tx.send(FoodieMessageType::RecipeCreate);
-        }
+    for friend_id in friend_ids.iter() {
+        send_message(
+            &state.connections,
+            *friend_id,
+            FoodieMessageType::RecipeCreate,
+        );
     }
 
+    if created_recipe.visibility == RecipeVisibility::Friends {
+        notify(
+            &state,
+            &friend_ids,
+            NotificationKind::FriendRecipe,
+            &user,
+            NotificationTarget {
+                recipe_id: Some(created_recipe.id),
+                ..Default::default()
+            },
+        )
+        .await;
+    }
+
+    let total_time = recipe_total_time(&created_recipe);
+
     Ok(Json(Recipe {
         id: created_recipe.id,
         user_id: created_recipe.user_id,
@@ -98,10 +158,20 @@ where
         img: recipe_image,
         servings: created_recipe.servings,
         updated_at: created_recipe.updated_at,
-        prep_time: created_recipe.prep_time,
-        baking_time: created_recipe.baking_time,
+        total_time,
+        prep_time: duration(created_recipe.prep_minutes),
+        cooking_time: duration(created_recipe.cooking_minutes),
+        baking_time: duration(created_recipe.baking_minutes),
+        resting_time: duration(created_recipe.resting_minutes),
         visibility: created_recipe.visibility.into(),
+        source_url: created_recipe.source_url,
+        source_name: created_recipe.source_name,
+        cuisine: created_recipe.cuisine,
+        difficulty: created_recipe.difficulty.map(|it| it.into()),
+        yield_unit: created_recipe.yield_unit,
         ingredients,
+        allergens,
+        diets,
     }))
 }
 
@@ -116,7 +186,22 @@ where
 {
     let user = auth.user.unwrap();
 
-    let has_access = has_access_to_recipe(&state.db, user.id).await?;
+    let recipe = find_recipe(&state, user.id, recipe_id).await?;
+    record_view(&state.db, user.id, recipe.id).await?;
+
+    Ok(Json(recipe))
+}
+
+// Finds a recipe the user has access to, with everything that is shown on the recipe page
+pub async fn find_recipe<T>(
+    state: &AppState<T>,
+    user_id: i32,
+    recipe_id: i32,
+) -> Result<Recipe, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let has_access = has_access_to_recipe(&state.db, user_id).await?;
     let recipe_model = recipes::Entity::find_by_id(recipe_id)
         .filter(has_access)
         .one(&state.db)
@@ -124,10 +209,13 @@ where
         .ok_or(ApiError::RecordNotFound)?;
 
     let ingredients = get_recipe_ingredients(&state.db, recipe_model.id).await?;
+    let (allergens, diets) = get_recipe_dietary_flags(&state.db, recipe_model.id).await?;
 
-    let recipe_image = get_presigned_url_for_get(state.storage, recipe_model.img).await?;
+    let recipe_image = get_presigned_url_for_get(state.storage.clone(), recipe_model.img).await?;
 
-    Ok(Json(Recipe {
+    let total_time = recipe_total_time(&recipe_model);
+
+    Ok(Recipe {
         id: recipe_model.id,
         user_id: recipe_model.user_id,
         name: recipe_model.name,
@@ -136,50 +224,66 @@ where
         img: recipe_image,
         servings: recipe_model.servings,
         updated_at: recipe_model.updated_at,
-        prep_time: recipe_model.prep_time,
-        baking_time: recipe_model.baking_time,
+        total_time,
+        prep_time: duration(recipe_model.prep_minutes),
+        cooking_time: duration(recipe_model.cooking_minutes),
+        baking_time: duration(recipe_model.baking_minutes),
+        resting_time: duration(recipe_model.resting_minutes),
         visibility: recipe_model.visibility.into(),
+        source_url: recipe_model.source_url,
+        source_name: recipe_model.source_name,
+        cuisine: recipe_model.cuisine,
+        difficulty: recipe_model.difficulty.map(|it| it.into()),
+        yield_unit: recipe_model.yield_unit,
         ingredients,
-    }))
+        allergens,
+        diets,
+    })
 }
 
-async fn _get_recipes<T>(
+pub async fn _get_recipes<T>(
     recipes: Vec<recipes::Model>,
     state: AppState<T>,
 ) -> Result<Json<Vec<Recipe>>, ApiError>
 where
     T: FoodieStorage + Send + Sync + Clone,
 {
-    let ingredients = recipes
-        .load_many_to_many(ingredients::Entity, recipe_ingredients::Entity, &state.db)
+    let lines = recipes
+        .load_many(
+            recipe_ingredients::Entity::find().order_by_asc(recipe_ingredients::Column::Position),
+            &state.db,
+        )
         .await?;
 
-    let ingredients_with_units = recipes
-        .load_many(recipe_ingredients::Entity, &state.db)
-        .await?;
+    let ingredients = ingredients::Entity::find()
+        .filter(
+            ingredients::Column::Id
+                .is_in(lines.iter().flatten().map(|it| it.ingredient_id).unique()),
+        )
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|it| (it.id, it))
+        .collect::<HashMap<_, _>>();
 
     let recipes = recipes
         .into_iter()
-        .zip(
-            ingredients
-                .into_iter()
-                .zip(ingredients_with_units.into_iter()),
-        )
+        .zip(lines)
         .map(|r| {
             let state = state.storage.clone();
+            let recipe_ingredients =
+                r.1.iter()
+                    .filter_map(|line| ingredients.get(&line.ingredient_id).cloned())
+                    .collect::<Vec<_>>();
             async move {
+                let (allergens, diets) = dietary_flags(&recipe_ingredients);
                 let ingredients =
-                    r.1 .0
-                        .into_iter()
-                        .zip(r.1 .1.into_iter())
-                        .map(|i| RecipeIngredient {
-                            ingredient_id: i.0.id,
-                            ingredient_name: i.0.name,
-                            unit: i.1.unit.map(|u| u.into()),
-                            amount: i.1.amount,
-                        })
+                    r.1.into_iter()
+                        .zip(recipe_ingredients)
+                        .map(|(line, ingredient)| recipe_ingredient(line, ingredient.name))
                         .collect();
 
+                let total_time = recipe_total_time(&r.0);
                 let recipe_image = get_presigned_url_for_get(state, r.0.img)
                     .await
                     .ok()
@@ -194,10 +298,20 @@ where
                     img: recipe_image,
                     servings: r.0.servings,
                     updated_at: r.0.updated_at,
-                    prep_time: r.0.prep_time,
-                    baking_time: r.0.baking_time,
+                    total_time,
+                    prep_time: duration(r.0.prep_minutes),
+                    cooking_time: duration(r.0.cooking_minutes),
+                    baking_time: duration(r.0.baking_minutes),
+                    resting_time: duration(r.0.resting_minutes),
                     visibility: r.0.visibility.into(),
+                    source_url: r.0.source_url,
+                    source_name: r.0.source_name,
+                    cuisine: r.0.cuisine,
+                    difficulty: r.0.difficulty.map(|it| it.into()),
+                    yield_unit: r.0.yield_unit,
                     ingredients,
+                    allergens,
+                    diets,
                 }
             }
         })
@@ -208,6 +322,56 @@ where
     Ok(Json(recipes))
 }
 
+pub fn duration(minutes: Option<i32>) -> Option<Duration> {
+    minutes
+        .and_then(|it| u32::try_from(it).ok())
+        .map(Duration::from_minutes)
+}
+
+pub fn minutes(duration: Option<Duration>) -> Option<i32> {
+    duration.map(|it| i32::try_from(it.as_minutes()).unwrap_or(i32::MAX))
+}
+
+pub fn recipe_total_time(recipe: &recipes::Model) -> Option<Duration> {
+    let times = [
+        recipe.prep_minutes,
+        recipe.cooking_minutes,
+        recipe.baking_minutes,
+        recipe.resting_minutes,
+    ];
+
+    match times.iter().all(Option::is_none) {
+        true => None,
+        false => Some(times.into_iter().filter_map(duration).sum()),
+    }
+}
+
+// Remembers when the user last looked at a recipe, so suggestions can avoid what was just made
+async fn record_view<C>(db: &C, user_id: i32, recipe_id: i32) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    recipe_views::Entity::insert(recipe_views::ActiveModel {
+        user_id: Set(user_id),
+        recipe_id: Set(recipe_id),
+        view_count: Set(1),
+        last_viewed_at: Set(chrono::Utc::now().into()),
+    })
+    .on_conflict(
+        OnConflict::columns([recipe_views::Column::UserId, recipe_views::Column::RecipeId])
+            .value(
+                recipe_views::Column::ViewCount,
+                Expr::col((recipe_views::Entity, recipe_views::Column::ViewCount)).add(1),
+            )
+            .update_column(recipe_views::Column::LastViewedAt)
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(())
+}
+
 pub async fn has_access_to_recipe<C>(db: &C, user_id: i32) -> Result<Condition, anyhow::Error>
 where
     C: ConnectionTrait,
@@ -228,11 +392,56 @@ where
         ))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RecipeQuery {
+    // Comma separated list of allergens the recipes can't contain, e.g. `Gluten,Nuts`
+    exclude: Option<String>,
+    diet: Option<Diet>,
+    // Matched without case, so `italian` finds recipes with `Italian` as cuisine
+    cuisine: Option<String>,
+    difficulty: Option<RecipeDifficulty>,
+}
+
+// Parses a comma separated list of allergens, e.g. `Gluten,Nuts`
+pub fn parse_allergens(allergens: &Option<String>) -> Result<Vec<Allergen>, ApiError> {
+    allergens
+        .iter()
+        .flat_map(|it| it.split(','))
+        .filter(|it| !it.is_empty())
+        .map(|it| {
+            it.parse::<Allergen>().map_err(|_| {
+                ApiError::StatusCode(StatusCode::BAD_REQUEST, format!("Unknown allergen {it}"))
+            })
+        })
+        .collect()
+}
+
+// Filters on the cuisine, matched without case, and the difficulty
+pub fn metadata_filter(
+    cuisine: &Option<String>,
+    difficulty: Option<RecipeDifficulty>,
+) -> Condition {
+    let mut filter = Condition::all();
+    if let Some(cuisine) = non_empty(cuisine) {
+        filter = filter.add(
+            Expr::expr(Func::lower(Expr::col(recipes::Column::Cuisine))).eq(cuisine.to_lowercase()),
+        );
+    }
+    if let Some(difficulty) = difficulty {
+        filter = filter.add(
+            recipes::Column::Difficulty
+                .eq(sea_orm_active_enums::RecipeDifficulty::from(difficulty)),
+        );
+    }
+    filter
+}
+
 // Gets all the recipes for the user, which includes the ones
 // that friends have shared with them
 pub async fn get_recipes<T>(
     auth: AuthSession,
     State(state): State<AppState<T>>,
+    Query(query): Query<RecipeQuery>,
 ) -> Result<Json<Vec<Recipe>>, ApiError>
 where
     T: FoodieStorage + Send + Sync + Clone,
@@ -240,12 +449,48 @@ where
     let user = auth.user.unwrap();
     let has_access = has_access_to_recipe(&state.db, user.id).await?;
 
+    let excluded = parse_allergens(&query.exclude)?;
+
     let recipes = recipes::Entity::find()
         .filter(has_access)
+        .filter(metadata_filter(&query.cuisine, query.difficulty))
         .all(&state.db)
         .await?;
 
-    _get_recipes(recipes, state).await
+    let Json(recipes) = _get_recipes(recipes, state).await?;
+
+    let recipes = recipes
+        .into_iter()
+        .filter(|it| !it.allergens.iter().any(|a| excluded.contains(a)))
+        .filter(|it| query.diet.is_none_or(|diet| it.diets.contains(&diet)))
+        .collect();
+
+    Ok(Json(recipes))
+}
+
+// Gets the cuisines of the recipes the user has access to, to be able to filter on them
+pub async fn get_cuisines(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+) -> Result<Json<Vec<String>>, ApiError> {
+    let user = auth.user.unwrap();
+    let has_access = has_access_to_recipe(&db, user.id).await?;
+
+    let cuisines = recipes::Entity::find()
+        .select_only()
+        .column(recipes::Column::Cuisine)
+        .distinct()
+        .filter(has_access)
+        .filter(recipes::Column::Cuisine.is_not_null())
+        .into_tuple::<String>()
+        .all(&db)
+        .await?
+        .into_iter()
+        .sorted_by_key(|it| it.to_lowercase())
+        .unique_by(|it| it.to_lowercase())
+        .collect();
+
+    Ok(Json(cuisines))
 }
 
 pub async fn update_recipe<T>(
@@ -260,6 +505,8 @@ where
 {
     let user = auth.user.unwrap();
 
+    validate_source_url(&recipe.source_url)?;
+
     let created_ingredients = create_ingredients(&recipe, user.id, &db).await?;
 
     let tx = db.begin().await?;
@@ -272,8 +519,15 @@ where
         instructions: Set(recipe.instructions),
         img: Set(recipe.img),
         servings: Set(recipe.servings),
-        prep_time: Set(recipe.prep_time),
-        baking_time: Set(recipe.baking_time),
+        prep_minutes: Set(minutes(recipe.prep_time)),
+        cooking_minutes: Set(minutes(recipe.cooking_time)),
+        baking_minutes: Set(minutes(recipe.baking_time)),
+        resting_minutes: Set(minutes(recipe.resting_time)),
+        source_url: Set(non_empty(&recipe.source_url)),
+        source_name: Set(non_empty(&recipe.source_name)),
+        cuisine: Set(non_empty(&recipe.cuisine)),
+        difficulty: Set(recipe.difficulty.map(|it| it.into())),
+        yield_unit: Set(non_empty(&recipe.yield_unit)),
         created_at: NotSet,
         updated_at: Set(chrono::Utc::now().into()),
         visibility: Set(recipe.visibility.into()),
@@ -283,15 +537,8 @@ where
     .exec(&tx)
     .await?;
 
-    let models = created_ingredients.into_iter().filter_map(|i| {
-        let ri = recipe.ingredients.iter().find(|ri| ri.name == i.name)?;
-        Some(recipe_ingredients::ActiveModel {
-            recipe_id: Set(updated_recipe.id),
-            ingredient_id: Set(i.id),
-            unit: Set(ri.unit.map(|u| u.into())),
-            amount: Set(ri.amount),
-        })
-    });
+    let models =
+        recipe_ingredient_models(updated_recipe.id, &recipe.ingredients, &created_ingredients);
 
     recipe_ingredients::Entity::delete_many()
         .filter(recipe_ingredients::Column::RecipeId.eq(recipe_id))
@@ -299,15 +546,18 @@ where
         .await?;
 
     recipe_ingredients::Entity::insert_many(models)
-        .exec(&tx)
+        .exec_without_returning(&tx)
         .await?;
 
     tx.commit().await?;
 
     let ingredients = get_recipe_ingredients(&db, recipe_id).await?;
+    let (allergens, diets) = get_recipe_dietary_flags(&db, recipe_id).await?;
 
     let recipe_image = get_presigned_url_for_get(state.storage, updated_recipe.img).await?;
 
+    let total_time = recipe_total_time(&updated_recipe);
+
     Ok(Json(Recipe {
         id: updated_recipe.id,
         user_id: updated_recipe.user_id,
@@ -317,10 +567,20 @@ where
         img: recipe_image,
         servings: updated_recipe.servings,
         updated_at: updated_recipe.updated_at,
-        prep_time: updated_recipe.prep_time,
-        baking_time: updated_recipe.baking_time,
+        total_time,
+        prep_time: duration(updated_recipe.prep_minutes),
+        cooking_time: duration(updated_recipe.cooking_minutes),
+        baking_time: duration(updated_recipe.baking_minutes),
+        resting_time: duration(updated_recipe.resting_minutes),
         visibility: updated_recipe.visibility.into(),
+        source_url: updated_recipe.source_url,
+        source_name: updated_recipe.source_name,
+        cuisine: updated_recipe.cuisine,
+        difficulty: updated_recipe.difficulty.map(|it| it.into()),
+        yield_unit: updated_recipe.yield_unit,
         ingredients,
+        allergens,
+        diets,
     }))
 }
 
@@ -356,47 +616,76 @@ where
         .filter(|it| it.status == Some(FriendshipStatus::Accepted.into()));
 
     for friend in friends {
-        if let Some(tx) = state.connections.read().unwrap().get(&friend.id) {
-            let _ = tx.send(FoodieMessageType::RecipeDelete);
-        }
+        send_message(
+            &state.connections,
+            friend.id,
+            FoodieMessageType::RecipeDelete,
+        );
     }
 
     Ok(())
 }
 
+// Resolves the ingredients of the recipe against the catalog, and creates the ones that doesn't
+// exist as private ingredients for the user
 async fn create_ingredients(
     recipe: &CreateRecipe,
     user_id: i32,
     db: &DatabaseConnection,
-) -> Result<Vec<ingredients::Model>, anyhow::Error> {
-    let ingredients: (Vec<String>, Vec<ingredients::ActiveModel>) = recipe
-        .ingredients
+) -> Result<HashMap<String, ingredients::Model>, anyhow::Error> {
+    Ok(resolve_ingredients(
+        db,
+        user_id,
+        recipe.ingredients.iter().map(|i| i.name.as_str()),
+    )
+    .await?)
+}
+
+fn non_empty(text: &Option<String>) -> Option<String> {
+    text.as_deref()
+        .map(str::trim)
+        .filter(|it| !it.is_empty())
+        .map(str::to_string)
+}
+
+fn validate_source_url(url: &Option<String>) -> Result<(), ApiError> {
+    match non_empty(url) {
+        Some(url) if !(url.starts_with("http://") || url.starts_with("https://")) => {
+            Err(ApiError::StatusCode(
+                StatusCode::BAD_REQUEST,
+                "The source must be a http or https url".to_string(),
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn recipe_ingredient_models(
+    recipe_id: i32,
+    recipe_ingredients: &[CreateRecipeIngredient],
+    ingredients: &HashMap<String, ingredients::Model>,
+) -> Vec<recipe_ingredients::ActiveModel> {
+    recipe_ingredients
         .iter()
-        .map(|i| {
-            (
-                i.name.clone(),
-                ingredients::ActiveModel {
-                    id: NotSet,
-                    name: Set(i.name.clone()),
-                    user_id: Set(user_id),
-                },
-            )
+        .filter_map(|ri| {
+            let ingredient = ingredients.get(&normalize_ingredient_name(&ri.name))?;
+            Some((ri, ingredient))
         })
-        .unzip();
-
-    ingredients::Entity::insert_many(ingredients.1)
-        .on_conflict(
-            OnConflict::column(ingredients::Column::Name)
-                .update_column(ingredients::Column::Name)
-                .to_owned(),
+        .enumerate()
+        .map(
+            |(position, (ri, ingredient))| recipe_ingredients::ActiveModel {
+                id: NotSet,
+                recipe_id: Set(recipe_id),
+                ingredient_id: Set(ingredient.id),
+                unit: Set(ri.unit.map(|u| u.into())),
+                amount: Set(ri.amount),
+                position: Set(position as i32),
+                note: Set(non_empty(&ri.note)),
+                optional: Set(ri.optional),
+                group_name: Set(non_empty(&ri.group)),
+            },
         )
-        .exec(db)
-        .await?;
-
-    Ok(ingredients::Entity::find()
-        .filter(ingredients::Column::Name.is_in(ingredients.0))
-        .all(db)
-        .await?)
+        .collect()
 }
 
 pub async fn get_presigned_url_for_upload<T>(
@@ -433,17 +722,13 @@ where
 {
     let ingredients = recipe_ingredients::Entity::find()
         .filter(recipe_ingredients::Column::RecipeId.eq(recipe_id))
+        .order_by_asc(recipe_ingredients::Column::Position)
         .find_also_related(ingredients::Entity)
         .stream(db)
         .await?
         .map(|i| {
             let i = i.unwrap();
-            RecipeIngredient {
-                ingredient_id: i.0.ingredient_id,
-                ingredient_name: i.1.unwrap().name,
-                unit: i.0.unit.map(|u| u.into()),
-                amount: i.0.amount,
-            }
+            recipe_ingredient(i.0, i.1.unwrap().name)
         })
         .collect::<Vec<_>>()
         .await;
@@ -451,6 +736,35 @@ where
     Ok(ingredients)
 }
 
+fn recipe_ingredient(line: recipe_ingredients::Model, ingredient_name: String) -> RecipeIngredient {
+    RecipeIngredient {
+        ingredient_id: line.ingredient_id,
+        ingredient_name,
+        unit: line.unit.map(|u| u.into()),
+        amount: line.amount,
+        position: line.position,
+        note: line.note,
+        optional: line.optional,
+        group: line.group_name,
+    }
+}
+
+async fn get_recipe_dietary_flags<C>(
+    db: &C,
+    recipe_id: i32,
+) -> Result<(Vec<Allergen>, Vec<Diet>), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let ingredients = ingredients::Entity::find()
+        .inner_join(recipe_ingredients::Entity)
+        .filter(recipe_ingredients::Column::RecipeId.eq(recipe_id))
+        .all(db)
+        .await?;
+
+    Ok(dietary_flags(&ingredients))
+}
+
 macro_rules! convert_unit {
     ($first:ty, $second: ty) => {
         impl From<$first> for $second {
@@ -498,3 +812,26 @@ convert_visibility!(
     sea_orm_active_enums::RecipeVisibility,
     common::recipe::RecipeVisibility
 );
+
+macro_rules! convert_difficulty {
+    ($first:ty, $second: ty) => {
+        impl From<$first> for $second {
+            fn from(value: $first) -> Self {
+                match value {
+                    <$first>::Easy => <$second>::Easy,
+                    <$first>::Medium => <$second>::Medium,
+                    <$first>::Hard => <$second>::Hard,
+                }
+            }
+        }
+    };
+}
+
+convert_difficulty!(
+    common::recipe::RecipeDifficulty,
+    sea_orm_active_enums::RecipeDifficulty
+);
+convert_difficulty!(
+    sea_orm_active_enums::RecipeDifficulty,
+    common::recipe::RecipeDifficulty
+);
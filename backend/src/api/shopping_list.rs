@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use crate::{
+    api::{
+        ingredient::{aisle_order, with_user_categories},
+        recipe::has_access_to_recipe,
+    },
+    auth_backend::AuthSession,
+    entities::{ingredients, recipe_ingredients, recipes},
+    ApiError,
+};
+use axum::{extract::State, Json};
+use common::{
+    recipe::Unit,
+    shopping_list::{CreateShoppingList, ShoppingList, ShoppingListItem, ShoppingListSection},
+};
+use itertools::Itertools;
+use rust_decimal::Decimal;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+};
+
+// Creates a shopping list from a set of recipes. Amounts of the same ingredient and unit are added
+// together, and the list is grouped by category in the aisle order of the user
+pub async fn post_shopping_list(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Json(shopping_list): Json<CreateShoppingList>,
+) -> Result<Json<ShoppingList>, ApiError> {
+    let user = auth.user.unwrap();
+
+    Ok(Json(
+        build_shopping_list(&db, user.id, shopping_list.recipe_ids).await?,
+    ))
+}
+
+pub async fn build_shopping_list<C>(
+    db: &C,
+    user_id: i32,
+    recipe_ids: Vec<i32>,
+) -> Result<ShoppingList, ApiError>
+where
+    C: ConnectionTrait,
+{
+    let recipe_ids = recipe_ids.into_iter().unique().collect::<Vec<_>>();
+
+    let accessible = recipes::Entity::find()
+        .filter(recipes::Column::Id.is_in(recipe_ids.clone()))
+        .filter(has_access_to_recipe(db, user_id).await?)
+        .count(db)
+        .await?;
+
+    if accessible != recipe_ids.len() as u64 {
+        return Err(ApiError::RecordNotFound);
+    }
+
+    let lines = recipe_ingredients::Entity::find()
+        .filter(recipe_ingredients::Column::RecipeId.is_in(recipe_ids))
+        .find_also_related(ingredients::Entity)
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|(line, ingredient)| Some((line, ingredient?)))
+        .collect::<Vec<_>>();
+
+    let mut amounts: HashMap<(i32, Option<Unit>), Option<Decimal>> = HashMap::new();
+    for (line, _) in &lines {
+        let unit = line.unit.clone().map(Unit::from);
+        let amount = amounts.entry((line.ingredient_id, unit)).or_insert(None);
+
+        if let Some(line_amount) = line.amount {
+            *amount = Some(amount.unwrap_or_default() + line_amount);
+        }
+    }
+
+    let ingredients = lines
+        .into_iter()
+        .map(|(_, ingredient)| ingredient)
+        .unique_by(|it| it.id)
+        .collect::<Vec<_>>();
+
+    let ingredients = with_user_categories(db, user_id, ingredients)
+        .await?
+        .into_iter()
+        .map(|it| (it.id, it))
+        .collect::<HashMap<_, _>>();
+
+    let sections = aisle_order(db, user_id)
+        .await?
+        .into_iter()
+        .filter_map(|category| {
+            let items = amounts
+                .iter()
+                .filter_map(|((ingredient_id, unit), amount)| {
+                    let ingredient = ingredients.get(ingredient_id)?;
+                    (ingredient.category == category).then(|| ShoppingListItem {
+                        ingredient_id: *ingredient_id,
+                        name: ingredient.name.clone(),
+                        unit: *unit,
+                        amount: *amount,
+                    })
+                })
+                .sorted_by_cached_key(|it| (it.name.to_lowercase(), it.unit.map(|u| u.to_string())))
+                .collect::<Vec<_>>();
+
+            (!items.is_empty()).then_some(ShoppingListSection { category, items })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(ShoppingList { sections })
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::Utc;
+use common::{
+    ingredient::Diet,
+    recipe::{Recipe, RecipeDifficulty, RecipeSuggestions},
+};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::Deserialize;
+
+use crate::{
+    api::recipe::{_get_recipes, has_access_to_recipe, metadata_filter, parse_allergens},
+    app::AppState,
+    auth_backend::AuthSession,
+    entities::{favorites, recipe_cooks, recipe_views, recipes},
+    storage::FoodieStorage,
+    ApiError,
+};
+
+const MAX_SUGGESTIONS: usize = 20;
+
+#[derive(Deserialize, Debug, Default)]
+pub struct SuggestionQuery {
+    // How many recipes to pick. Defaults to one
+    count: Option<usize>,
+    // Gives the same pick for the same recipes and history at the same moment, as the weights
+    // depend on how long ago recipes were updated or viewed. Random if not set
+    seed: Option<u64>,
+    // The longest total time in minutes. Recipes without any times set are left out
+    max_time: Option<u32>,
+    // Comma separated list of allergens the recipes can't contain, e.g. `Gluten,Nuts`
+    exclude: Option<String>,
+    diet: Option<Diet>,
+    cuisine: Option<String>,
+    difficulty: Option<RecipeDifficulty>,
+}
+
+// Picks recipes for the user to make, weighted towards favorites and well rated recipes, and away
+// from recipes that were just changed or looked at
+pub async fn get_suggestions<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Query(query): Query<SuggestionQuery>,
+) -> Result<Json<RecipeSuggestions>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+    let has_access = has_access_to_recipe(&state.db, user.id).await?;
+
+    let excluded = parse_allergens(&query.exclude)?;
+    let count = query.count.unwrap_or(1).clamp(1, MAX_SUGGESTIONS);
+    let seed = query.seed.unwrap_or_else(rand::random);
+
+    // Ordered so the seed picks the same recipes each time
+    let recipes = recipes::Entity::find()
+        .filter(has_access)
+        .filter(metadata_filter(&query.cuisine, query.difficulty))
+        .order_by_asc(recipes::Column::Id)
+        .all(&state.db)
+        .await?;
+
+    let recipe_ids = recipes.iter().map(|it| it.id).collect::<Vec<_>>();
+
+    let favorites = favorites::Entity::find()
+        .filter(favorites::Column::RecipeId.is_in(recipe_ids.clone()))
+        .all(&state.db)
+        .await?;
+
+    let mut ratings = HashMap::<i32, Vec<i32>>::new();
+    for cook in recipe_cooks::Entity::find()
+        .filter(recipe_cooks::Column::RecipeId.is_in(recipe_ids.clone()))
+        .filter(recipe_cooks::Column::Rating.is_not_null())
+        .all(&state.db)
+        .await?
+    {
+        ratings
+            .entry(cook.recipe_id)
+            .or_default()
+            .extend(cook.rating);
+    }
+
+    let views = recipe_views::Entity::find()
+        .filter(recipe_views::Column::UserId.eq(user.id))
+        .filter(recipe_views::Column::RecipeId.is_in(recipe_ids))
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|it| (it.recipe_id, it.last_viewed_at.with_timezone(&Utc)))
+        .collect::<HashMap<_, _>>();
+
+    let Json(recipes) = _get_recipes(recipes, state).await?;
+
+    let candidates = recipes
+        .into_iter()
+        .filter(|it| !it.allergens.iter().any(|a| excluded.contains(a)))
+        .filter(|it| query.diet.is_none_or(|diet| it.diets.contains(&diet)))
+        .filter(|it| {
+            query
+                .max_time
+                .is_none_or(|max| it.total_time.is_some_and(|total| total.as_minutes() <= max))
+        })
+        .map(|recipe| {
+            let own_favorite = favorites
+                .iter()
+                .any(|it| it.recipe_id == recipe.id && it.user_id == user.id);
+            let other_favorites = favorites
+                .iter()
+                .filter(|it| it.recipe_id == recipe.id && it.user_id != user.id)
+                .count();
+            let weight = weight(
+                &recipe,
+                own_favorite,
+                other_favorites,
+                ratings
+                    .get(&recipe.id)
+                    .map(|it| it.iter().sum::<i32>() as f64 / it.len() as f64),
+                views.get(&recipe.id).copied(),
+            );
+            (recipe, weight)
+        })
+        .collect::<Vec<_>>();
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let recipes = candidates
+        .choose_multiple_weighted(&mut rng, count, |(_, weight)| *weight)
+        .map_err(|err| ApiError::UnknownError(err.to_string()))?
+        .map(|(recipe, _)| recipe.clone())
+        .collect();
+
+    Ok(Json(RecipeSuggestions { seed, recipes }))
+}
+
+// Every recipe starts at 1. Favorites and an average rating above 3 add to it, while a recipe that
+// was updated in the last week, or viewed in the last two weeks, is less likely to be picked
+fn weight(
+    recipe: &Recipe,
+    own_favorite: bool,
+    other_favorites: usize,
+    average_rating: Option<f64>,
+    last_viewed_at: Option<chrono::DateTime<Utc>>,
+) -> f64 {
+    let now = Utc::now();
+    let days_since = |at: chrono::DateTime<Utc>| (now - at).num_hours().max(0) as f64 / 24.0;
+
+    let mut weight = 1.0;
+    if own_favorite {
+        weight += 3.0;
+    }
+    weight += (other_favorites as f64 * 0.5).min(2.0);
+    weight += average_rating.map_or(0.0, |it| (it - 3.0).max(0.0));
+
+    let updated = days_since(recipe.updated_at.with_timezone(&Utc));
+    if updated < 7.0 {
+        weight *= 0.5 + updated / 14.0;
+    }
+
+    if let Some(viewed) = last_viewed_at {
+        weight *= (days_since(viewed) / 14.0).clamp(0.1, 1.0);
+    }
+
+    weight
+}
@@ -0,0 +1,386 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use common::{
+    recipe::Recipe,
+    swipe::{CreateSwipeSession, Swipe, SwipeMatch, SwipeParticipant, SwipeSession},
+    websocket::FoodieMessageType,
+};
+use hyper::StatusCode;
+use itertools::Itertools;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue::NotSet, ColumnTrait, Condition, ConnectionTrait,
+    DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+use serde::Deserialize;
+
+use crate::{
+    api::{
+        recipe::{_get_recipes, has_access_to_recipe},
+        users::fetch_user_relationships,
+        websocket::send_message,
+    },
+    app::AppState,
+    auth_backend::AuthSession,
+    entities::{
+        recipes, sea_orm_active_enums::FriendshipStatus, swipe_matches, swipe_session_participants,
+        swipe_sessions, swipes, users,
+    },
+    storage::FoodieStorage,
+    ApiError,
+};
+
+const MAX_DECK_SIZE: u64 = 50;
+
+// Starts a session for swiping on recipes together with friends
+pub async fn post_swipe_session(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Json(session): Json<CreateSwipeSession>,
+) -> Result<Json<SwipeSession>, ApiError> {
+    let user = auth.user.unwrap();
+
+    let friend_ids = fetch_user_relationships(&db, user.id, "")
+        .await?
+        .into_iter()
+        .filter(|it| it.status == Some(FriendshipStatus::Accepted.into()))
+        .map(|it| it.id)
+        .collect::<Vec<_>>();
+
+    let participant_ids = session
+        .participant_ids
+        .into_iter()
+        .filter(|it| *it != user.id)
+        .unique()
+        .collect::<Vec<_>>();
+    if participant_ids.is_empty() {
+        return Err(ApiError::StatusCode(
+            StatusCode::BAD_REQUEST,
+            "Invite someone to swipe with".to_string(),
+        ));
+    }
+    if participant_ids.iter().any(|it| !friend_ids.contains(it)) {
+        return Err(ApiError::StatusCode(
+            StatusCode::BAD_REQUEST,
+            "Only friends can be invited to swipe".to_string(),
+        ));
+    }
+
+    let name = session.name.trim();
+    let name = if name.is_empty() {
+        "Weekday dinner"
+    } else {
+        name
+    };
+
+    let tx = db.begin().await?;
+
+    let created_session = swipe_sessions::Entity::insert(swipe_sessions::ActiveModel {
+        id: NotSet,
+        owner_id: Set(user.id),
+        name: Set(name.to_string()),
+        created_at: NotSet,
+    })
+    .exec_with_returning(&tx)
+    .await?;
+
+    swipe_session_participants::Entity::insert_many(
+        std::iter::once(user.id)
+            .chain(participant_ids)
+            .map(|user_id| swipe_session_participants::ActiveModel {
+                session_id: Set(created_session.id),
+                user_id: Set(user_id),
+            }),
+    )
+    .exec_without_returning(&tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(to_swipe_session(&db, created_session).await?))
+}
+
+// Gets the sessions the user takes part in, the newest first
+pub async fn get_swipe_sessions(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+) -> Result<Json<Vec<SwipeSession>>, ApiError> {
+    let user = auth.user.unwrap();
+
+    let session_ids = swipe_session_participants::Entity::find()
+        .filter(swipe_session_participants::Column::UserId.eq(user.id))
+        .all(&db)
+        .await?
+        .into_iter()
+        .map(|it| it.session_id);
+
+    let models = swipe_sessions::Entity::find()
+        .filter(swipe_sessions::Column::Id.is_in(session_ids))
+        .order_by_desc(swipe_sessions::Column::CreatedAt)
+        .all(&db)
+        .await?;
+
+    let mut sessions = vec![];
+    for model in models {
+        sessions.push(to_swipe_session(&db, model).await?);
+    }
+
+    Ok(Json(sessions))
+}
+
+pub async fn get_swipe_session(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Path(session_id): Path<i32>,
+) -> Result<Json<SwipeSession>, ApiError> {
+    let user = auth.user.unwrap();
+
+    let model = find_participating_session(&db, user.id, session_id).await?;
+
+    Ok(Json(to_swipe_session(&db, model).await?))
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct DeckQuery {
+    // How many recipes to get. Defaults to 10
+    count: Option<u64>,
+}
+
+// Gets the next recipes for the user to swipe on. Only recipes everyone in the session has
+// access to are included, since the others can never be a match. The ones someone else already
+// said yes to come first, and the rest are shuffled the same way for everyone
+pub async fn get_swipe_deck<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Path(session_id): Path<i32>,
+    Query(query): Query<DeckQuery>,
+) -> Result<Json<Vec<Recipe>>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    find_participating_session(&state.db, user.id, session_id).await?;
+    let has_access = has_access_for_everyone(&state.db, session_id).await?;
+
+    let swiped = swipes::Entity::find()
+        .filter(swipes::Column::SessionId.eq(session_id))
+        .all(&state.db)
+        .await?;
+
+    let mut recipes = recipes::Entity::find()
+        .filter(has_access)
+        .filter(
+            recipes::Column::Id.is_not_in(
+                swiped
+                    .iter()
+                    .filter(|it| it.user_id == user.id)
+                    .map(|it| it.recipe_id),
+            ),
+        )
+        .order_by_asc(recipes::Column::Id)
+        .all(&state.db)
+        .await?;
+
+    recipes.shuffle(&mut StdRng::seed_from_u64(session_id as u64));
+    recipes.sort_by_key(|recipe| {
+        !swiped
+            .iter()
+            .any(|it| it.recipe_id == recipe.id && it.liked)
+    });
+    recipes.truncate(query.count.unwrap_or(10).clamp(1, MAX_DECK_SIZE) as usize);
+
+    _get_recipes(recipes, state).await
+}
+
+// Saves a yes or no for a recipe, replacing an earlier swipe on it. When everyone has said yes,
+// the recipe is a match and everyone in the session is told about it
+pub async fn put_swipe<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Path((session_id, recipe_id)): Path<(i32, i32)>,
+    Json(swipe): Json<Swipe>,
+) -> Result<Json<Option<SwipeMatch>>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    find_participating_session(&state.db, user.id, session_id).await?;
+    let has_access = has_access_for_everyone(&state.db, session_id).await?;
+
+    let recipe = recipes::Entity::find_by_id(recipe_id)
+        .filter(has_access)
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    swipes::Entity::insert(swipes::ActiveModel {
+        session_id: Set(session_id),
+        user_id: Set(user.id),
+        recipe_id: Set(recipe_id),
+        liked: Set(swipe.liked),
+        created_at: Set(chrono::Utc::now().into()),
+    })
+    .on_conflict(
+        OnConflict::columns([
+            swipes::Column::SessionId,
+            swipes::Column::UserId,
+            swipes::Column::RecipeId,
+        ])
+        .update_columns([swipes::Column::Liked, swipes::Column::CreatedAt])
+        .to_owned(),
+    )
+    .exec_without_returning(&state.db)
+    .await?;
+
+    if !swipe.liked {
+        return Ok(Json(None));
+    }
+
+    let swipe_match = SwipeMatch {
+        session_id,
+        recipe_id,
+        name: recipe.name,
+    };
+
+    let already_matched = swipe_matches::Entity::find_by_id((session_id, recipe_id))
+        .one(&state.db)
+        .await?
+        .is_some();
+    if already_matched {
+        return Ok(Json(Some(swipe_match)));
+    }
+
+    let participant_ids = participant_ids(&state.db, session_id).await?;
+    let likes = swipes::Entity::find()
+        .filter(swipes::Column::SessionId.eq(session_id))
+        .filter(swipes::Column::RecipeId.eq(recipe_id))
+        .filter(swipes::Column::Liked.eq(true))
+        .count(&state.db)
+        .await?;
+    if likes < participant_ids.len() as u64 {
+        return Ok(Json(None));
+    }
+
+    let inserted = swipe_matches::Entity::insert(swipe_matches::ActiveModel {
+        session_id: Set(session_id),
+        recipe_id: Set(recipe_id),
+        created_at: NotSet,
+    })
+    .on_conflict(
+        OnConflict::columns([
+            swipe_matches::Column::SessionId,
+            swipe_matches::Column::RecipeId,
+        ])
+        .do_nothing()
+        .to_owned(),
+    )
+    .exec_without_returning(&state.db)
+    .await?;
+
+    // Two last swipes at the same time could both see a match, but only one gets to insert it
+    if inserted > 0 {
+        for user_id in participant_ids {
+            send_message(
+                &state.connections,
+                user_id,
+                FoodieMessageType::SwipeMatch(swipe_match.clone()),
+            );
+        }
+    }
+
+    Ok(Json(Some(swipe_match)))
+}
+
+// Responds with not found for sessions the user doesn't take part in, to not leak that they exist
+async fn find_participating_session<C>(
+    db: &C,
+    user_id: i32,
+    session_id: i32,
+) -> Result<swipe_sessions::Model, ApiError>
+where
+    C: ConnectionTrait,
+{
+    swipe_session_participants::Entity::find_by_id((session_id, user_id))
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    swipe_sessions::Entity::find_by_id(session_id)
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)
+}
+
+async fn participant_ids<C>(db: &C, session_id: i32) -> Result<Vec<i32>, ApiError>
+where
+    C: ConnectionTrait,
+{
+    Ok(swipe_session_participants::Entity::find()
+        .filter(swipe_session_participants::Column::SessionId.eq(session_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|it| it.user_id)
+        .collect())
+}
+
+async fn has_access_for_everyone<C>(db: &C, session_id: i32) -> Result<Condition, ApiError>
+where
+    C: ConnectionTrait,
+{
+    let mut condition = Condition::all();
+    for user_id in participant_ids(db, session_id).await? {
+        condition = condition.add(has_access_to_recipe(db, user_id).await?);
+    }
+    Ok(condition)
+}
+
+async fn to_swipe_session<C>(db: &C, model: swipe_sessions::Model) -> Result<SwipeSession, ApiError>
+where
+    C: ConnectionTrait,
+{
+    let participants = swipe_session_participants::Entity::find()
+        .filter(swipe_session_participants::Column::SessionId.eq(model.id))
+        .find_also_related(users::Entity)
+        .order_by_asc(swipe_session_participants::Column::UserId)
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|(participant, user)| {
+            Some(SwipeParticipant {
+                user_id: participant.user_id,
+                name: user?.name,
+            })
+        })
+        .collect();
+
+    let matches = swipe_matches::Entity::find()
+        .filter(swipe_matches::Column::SessionId.eq(model.id))
+        .find_also_related(recipes::Entity)
+        .order_by_desc(swipe_matches::Column::CreatedAt)
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|(swipe_match, recipe)| {
+            Some(SwipeMatch {
+                session_id: swipe_match.session_id,
+                recipe_id: swipe_match.recipe_id,
+                name: recipe?.name,
+            })
+        })
+        .collect();
+
+    Ok(SwipeSession {
+        id: model.id,
+        owner_id: model.owner_id,
+        name: model.name,
+        created_at: model.created_at,
+        participants,
+        matches,
+    })
+}
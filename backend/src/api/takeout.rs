@@ -0,0 +1,525 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Cursor, Read, Write},
+};
+
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use common::{
+    ingredient::normalize_ingredient_name,
+    takeout::{
+        TakeoutFriendship, TakeoutImport, TakeoutIngredient, TakeoutManifest, TakeoutRecipe,
+        TakeoutRecipeIngredient, TAKEOUT_VERSION,
+    },
+};
+use hyper::StatusCode;
+use itertools::Itertools;
+use sea_orm::{
+    ActiveValue::NotSet, ColumnTrait, Condition, EntityTrait, LoaderTrait, QueryFilter, QueryOrder,
+    Set, TransactionTrait,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use uuid::Uuid;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{
+    api::{
+        duplicate::find_duplicates,
+        ingredient::{resolve_ingredients, with_user_categories},
+        recipe::{duration, minutes},
+    },
+    app::AppState,
+    auth_backend::AuthSession,
+    entities::{
+        friendships, ingredients, recipe_ingredients, recipes,
+        sea_orm_active_enums::FriendshipStatus, users,
+    },
+    storage::FoodieStorage,
+    ApiError,
+};
+
+// The biggest archive that can be imported, both uploaded and unpacked. Most of it is the images
+pub const MAX_TAKEOUT_SIZE: usize = 512 * 1024 * 1024;
+
+const MANIFEST: &str = "manifest.json";
+const INGREDIENTS: &str = "ingredients.json";
+const RECIPES: &str = "recipes.json";
+const FRIENDSHIPS: &str = "friendships.json";
+const IMAGES: &str = "images/";
+
+struct Takeout {
+    manifest: TakeoutManifest,
+    ingredients: Vec<TakeoutIngredient>,
+    recipes: Vec<TakeoutRecipe>,
+    friendships: Vec<TakeoutFriendship>,
+    images: HashMap<Uuid, Vec<u8>>,
+}
+
+// Exports everything the user owns as a zip archive
+pub async fn get_takeout<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+) -> Result<Response, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let recipes = recipes::Entity::find()
+        .filter(recipes::Column::UserId.eq(user.id))
+        .order_by_asc(recipes::Column::Id)
+        .all(&state.db)
+        .await?;
+    let lines = recipes
+        .load_many(recipe_ingredients::Entity, &state.db)
+        .await?;
+
+    let ingredient_ids = lines
+        .iter()
+        .flatten()
+        .map(|it| it.ingredient_id)
+        .unique()
+        .collect::<Vec<_>>();
+    let ingredients = ingredients::Entity::find()
+        .filter(
+            Condition::any()
+                .add(ingredients::Column::Id.is_in(ingredient_ids))
+                .add(ingredients::Column::UserId.eq(user.id)),
+        )
+        .order_by_asc(ingredients::Column::Id)
+        .all(&state.db)
+        .await?;
+    let ingredients = with_user_categories(&state.db, user.id, ingredients).await?;
+
+    // People who blocked the user are left out, as the user should not know that they are blocked
+    let friendships = friendships::Entity::find()
+        .filter(
+            Condition::any()
+                .add(friendships::Column::RequesterId.eq(user.id))
+                .add(
+                    Condition::all()
+                        .add(friendships::Column::RecipientId.eq(user.id))
+                        .add(friendships::Column::Status.ne(FriendshipStatus::Blocked)),
+                ),
+        )
+        .all(&state.db)
+        .await?;
+    let friends = users::Entity::find()
+        .filter(
+            users::Column::Id.is_in(
+                friendships
+                    .iter()
+                    .flat_map(|it| [it.requester_id, it.recipient_id]),
+            ),
+        )
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|it| (it.id, it))
+        .collect::<HashMap<_, _>>();
+
+    // The rest of the data is still worth exporting if an image is gone from the storage
+    let mut images = HashMap::new();
+    for img in recipes.iter().filter_map(|it| it.img) {
+        match state.storage.get(img).await {
+            Ok(bytes) => {
+                images.insert(img, bytes);
+            }
+            Err(err) => log::warn!("Failed to get image {img} for the takeout: {err}"),
+        }
+    }
+
+    let takeout = Takeout {
+        manifest: TakeoutManifest {
+            version: TAKEOUT_VERSION,
+            exported_at: Utc::now(),
+            name: user.name,
+            email: user.email,
+        },
+        ingredients: ingredients
+            .into_iter()
+            .map(|it| TakeoutIngredient {
+                id: it.id,
+                name: it.name,
+                private: it.user_id.is_some(),
+                category: it.category,
+                allergens: it.allergens,
+                vegetarian: it.vegetarian,
+                vegan: it.vegan,
+            })
+            .collect(),
+        recipes: recipes
+            .into_iter()
+            .zip(lines)
+            .map(|(recipe, lines)| takeout_recipe(recipe, lines))
+            .collect(),
+        friendships: friendships
+            .into_iter()
+            .filter_map(|it| {
+                let requested = it.requester_id == user.id;
+                let friend = match requested {
+                    true => friends.get(&it.recipient_id)?,
+                    false => friends.get(&it.requester_id)?,
+                };
+                Some(TakeoutFriendship {
+                    name: friend.name.clone(),
+                    email: friend.email.clone(),
+                    status: it.status.into(),
+                    requested,
+                    requested_at: it.requested_at,
+                })
+            })
+            .collect(),
+        images,
+    };
+
+    let archive = tokio::task::spawn_blocking(move || write_archive(&takeout))
+        .await
+        .map_err(|err| ApiError::UnknownError(err.to_string()))??;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"foodie-takeout.zip\"",
+            ),
+        ],
+        archive,
+    )
+        .into_response())
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct ImportQuery {
+    // Imports the recipes even if some of them look like duplicates of existing recipes
+    #[serde(default)]
+    allow_duplicate: bool,
+}
+
+// Imports an archive from `get_takeout` as recipes of the user. It can come from another instance,
+// so everything gets new ids, and the ingredients are matched against the catalog by name
+pub async fn post_takeout<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> Result<Json<TakeoutImport>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let takeout = tokio::task::spawn_blocking(move || read_archive(&body))
+        .await
+        .map_err(|err| ApiError::UnknownError(err.to_string()))?
+        .map_err(|err| {
+            ApiError::StatusCode(
+                StatusCode::BAD_REQUEST,
+                format!("Not a valid takeout archive: {err}"),
+            )
+        })?;
+
+    if takeout.manifest.version > TAKEOUT_VERSION {
+        return Err(ApiError::StatusCode(
+            StatusCode::BAD_REQUEST,
+            "The archive is from a newer version of Foodie".to_string(),
+        ));
+    }
+
+    if !query.allow_duplicate {
+        let names = takeout
+            .ingredients
+            .iter()
+            .map(|it| (it.id, it.name.as_str()))
+            .collect::<HashMap<_, _>>();
+
+        let mut duplicates = vec![];
+        for recipe in &takeout.recipes {
+            let ingredient_names = recipe
+                .ingredients
+                .iter()
+                .filter_map(|it| names.get(&it.ingredient_id).copied())
+                .collect::<Vec<_>>();
+            duplicates.extend(
+                find_duplicates(&state.db, user.id, &recipe.name, &ingredient_names).await?,
+            );
+        }
+
+        if !duplicates.is_empty() {
+            return Err(ApiError::DuplicateRecipes(
+                duplicates.into_iter().unique_by(|it| it.id).collect(),
+            ));
+        }
+    }
+
+    let recipes = takeout.recipes.len();
+    let mut images = HashMap::new();
+    let imported = import_takeout(&state, user.id, takeout, &mut images).await;
+
+    // Nothing refers to the uploaded images when the import fails
+    if imported.is_err() {
+        for name in images.values() {
+            if let Err(err) = state.storage.delete(*name).await {
+                log::warn!("Failed to delete image {name} of a failed import: {err}");
+            }
+        }
+    }
+    let ingredients = imported?;
+
+    Ok(Json(TakeoutImport {
+        recipes,
+        ingredients,
+        images: images.len(),
+    }))
+}
+
+// Uploads the images and creates the recipes of the archive for the user. The names of the images
+// are added to `images` as they are uploaded, so the caller can clean them up on errors. Returns
+// how many ingredients were imported
+async fn import_takeout<T>(
+    state: &AppState<T>,
+    user_id: i32,
+    takeout: Takeout,
+    images: &mut HashMap<Uuid, Uuid>,
+) -> Result<usize, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    // The images get new names, so importing the same archive twice doesn't make the recipes share
+    // an image, where deleting one of them deletes the image of the other
+    let used_images = takeout
+        .recipes
+        .iter()
+        .filter_map(|it| it.img)
+        .collect::<HashSet<_>>();
+    for (img, bytes) in takeout
+        .images
+        .into_iter()
+        .filter(|(img, _)| used_images.contains(img))
+    {
+        let name = Uuid::new_v4();
+        state.storage.put(name, bytes).await?;
+        images.insert(img, name);
+    }
+
+    let tx = state.db.begin().await?;
+
+    let resolved = resolve_ingredients(
+        &tx,
+        user_id,
+        takeout.ingredients.iter().map(|it| it.name.as_str()),
+    )
+    .await?;
+
+    let mut ingredient_ids = HashMap::new();
+    for ingredient in &takeout.ingredients {
+        let Some(model) = resolved.get(&normalize_ingredient_name(&ingredient.name)) else {
+            continue;
+        };
+        ingredient_ids.insert(ingredient.id, model.id);
+
+        // What the user had set on their own ingredients is kept, but the catalog is left alone
+        if !ingredient.private || model.user_id != Some(user_id) {
+            continue;
+        }
+        ingredients::Entity::update(ingredients::ActiveModel {
+            id: Set(model.id),
+            category: Set(ingredient.category.into()),
+            allergens: Set(ingredient
+                .allergens
+                .iter()
+                .sorted()
+                .dedup()
+                .map(|it| it.to_string())
+                .collect()),
+            vegetarian: Set(ingredient.vegetarian || ingredient.vegan),
+            vegan: Set(ingredient.vegan),
+            ..Default::default()
+        })
+        .exec(&tx)
+        .await?;
+    }
+
+    for recipe in &takeout.recipes {
+        let created = recipes::Entity::insert(recipes::ActiveModel {
+            id: NotSet,
+            user_id: Set(user_id),
+            name: Set(recipe.name.clone()),
+            description: Set(recipe.description.clone()),
+            instructions: Set(recipe.instructions.clone()),
+            img: Set(recipe.img.and_then(|img| images.get(&img).copied())),
+            servings: Set(recipe.servings),
+            prep_minutes: Set(minutes(recipe.prep_time)),
+            cooking_minutes: Set(minutes(recipe.cooking_time)),
+            baking_minutes: Set(minutes(recipe.baking_time)),
+            resting_minutes: Set(minutes(recipe.resting_time)),
+            source_url: Set(recipe.source_url.clone()),
+            source_name: Set(recipe.source_name.clone()),
+            cuisine: Set(recipe.cuisine.clone()),
+            difficulty: Set(recipe.difficulty.map(|it| it.into())),
+            yield_unit: Set(recipe.yield_unit.clone()),
+            created_at: Set(recipe.created_at),
+            updated_at: Set(recipe.updated_at),
+            visibility: Set(recipe.visibility.clone().into()),
+        })
+        .exec_with_returning(&tx)
+        .await?;
+
+        let models = recipe
+            .ingredients
+            .iter()
+            .filter_map(|line| Some((line, *ingredient_ids.get(&line.ingredient_id)?)))
+            .enumerate()
+            .map(
+                |(position, (line, ingredient_id))| recipe_ingredients::ActiveModel {
+                    id: NotSet,
+                    recipe_id: Set(created.id),
+                    ingredient_id: Set(ingredient_id),
+                    unit: Set(line.unit.map(|it| it.into())),
+                    amount: Set(line.amount),
+                    position: Set(position as i32),
+                    note: Set(line.note.clone()),
+                    optional: Set(line.optional),
+                    group_name: Set(line.group.clone()),
+                },
+            )
+            .collect::<Vec<_>>();
+
+        if !models.is_empty() {
+            recipe_ingredients::Entity::insert_many(models)
+                .exec_without_returning(&tx)
+                .await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(ingredient_ids.len())
+}
+
+fn takeout_recipe(recipe: recipes::Model, lines: Vec<recipe_ingredients::Model>) -> TakeoutRecipe {
+    TakeoutRecipe {
+        id: recipe.id,
+        name: recipe.name,
+        description: recipe.description,
+        instructions: recipe.instructions,
+        img: recipe.img,
+        servings: recipe.servings,
+        prep_time: duration(recipe.prep_minutes),
+        cooking_time: duration(recipe.cooking_minutes),
+        baking_time: duration(recipe.baking_minutes),
+        resting_time: duration(recipe.resting_minutes),
+        visibility: recipe.visibility.into(),
+        source_url: recipe.source_url,
+        source_name: recipe.source_name,
+        cuisine: recipe.cuisine,
+        difficulty: recipe.difficulty.map(|it| it.into()),
+        yield_unit: recipe.yield_unit,
+        created_at: recipe.created_at,
+        updated_at: recipe.updated_at,
+        ingredients: lines
+            .into_iter()
+            .sorted_by_key(|it| it.position)
+            .map(|it| TakeoutRecipeIngredient {
+                ingredient_id: it.ingredient_id,
+                unit: it.unit.map(|it| it.into()),
+                amount: it.amount,
+                note: it.note,
+                optional: it.optional,
+                group: it.group_name,
+            })
+            .collect(),
+    }
+}
+
+fn write_archive(takeout: &Takeout) -> Result<Vec<u8>, anyhow::Error> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+    write_json(&mut zip, MANIFEST, &takeout.manifest)?;
+    write_json(&mut zip, INGREDIENTS, &takeout.ingredients)?;
+    write_json(&mut zip, RECIPES, &takeout.recipes)?;
+    write_json(&mut zip, FRIENDSHIPS, &takeout.friendships)?;
+
+    // Images are already compressed, so compressing them again only costs time
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    for (img, bytes) in &takeout.images {
+        zip.start_file(format!("{IMAGES}{img}"), options)?;
+        zip.write_all(bytes)?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+fn write_json<T: Serialize>(
+    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+    name: &str,
+    value: &T,
+) -> Result<(), anyhow::Error> {
+    zip.start_file(name, SimpleFileOptions::default())?;
+    serde_json::to_writer_pretty(zip, value)?;
+    Ok(())
+}
+
+fn read_archive(bytes: &[u8]) -> Result<Takeout, anyhow::Error> {
+    let mut zip = ZipArchive::new(Cursor::new(bytes))?;
+    let mut remaining = MAX_TAKEOUT_SIZE as u64;
+
+    let mut images = HashMap::new();
+    let names = zip.file_names().map(str::to_string).collect::<Vec<_>>();
+    for name in names {
+        let Some(img) = name
+            .strip_prefix(IMAGES)
+            .and_then(|it| Uuid::parse_str(it).ok())
+        else {
+            continue;
+        };
+
+        images.insert(img, read_entry(&mut zip, &name, &mut remaining)?);
+    }
+
+    Ok(Takeout {
+        manifest: read_json(&mut zip, MANIFEST, &mut remaining)?,
+        ingredients: read_json(&mut zip, INGREDIENTS, &mut remaining)?,
+        recipes: read_json(&mut zip, RECIPES, &mut remaining)?,
+        friendships: read_json(&mut zip, FRIENDSHIPS, &mut remaining)?,
+        images,
+    })
+}
+
+fn read_json<T: DeserializeOwned>(
+    zip: &mut ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+    remaining: &mut u64,
+) -> Result<T, anyhow::Error> {
+    Ok(serde_json::from_slice(&read_entry(zip, name, remaining)?)?)
+}
+
+// Unpacks an entry, as long as the whole archive stays within `MAX_TAKEOUT_SIZE` unpacked. The size
+// written in the archive can't be trusted, so the reading itself is limited as well
+fn read_entry(
+    zip: &mut ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+    remaining: &mut u64,
+) -> Result<Vec<u8>, anyhow::Error> {
+    let too_big = || anyhow::anyhow!("It is bigger than {MAX_TAKEOUT_SIZE} bytes unpacked");
+
+    let file = zip.by_name(name)?;
+    if file.size() > *remaining {
+        return Err(too_big());
+    }
+
+    let mut bytes = Vec::new();
+    file.take(*remaining + 1).read_to_end(&mut bytes)?;
+    *remaining = remaining
+        .checked_sub(bytes.len() as u64)
+        .ok_or_else(too_big)?;
+
+    Ok(bytes)
+}
@@ -3,12 +3,22 @@ use axum::{
     Json,
 };
 
-use sea_orm::DatabaseConnection;
-use sea_orm::{ConnectionTrait, DbErr, FromQueryResult, Statement};
+use hyper::StatusCode;
+use sea_orm::{sea_query::Expr, DatabaseConnection, Set, TransactionTrait};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, FromQueryResult, QueryFilter, QuerySelect,
+    Statement,
+};
 use serde::Deserialize;
 
 use crate::{
-    auth_backend::AuthSession, entities::sea_orm_active_enums::FriendshipStatus, ApiError,
+    app::AppState,
+    auth_backend::AuthSession,
+    entities::{
+        ingredients, recipe_ingredients, recipes, sea_orm_active_enums::FriendshipStatus, users,
+    },
+    storage::FoodieStorage,
+    ApiError,
 };
 
 #[derive(Debug, Deserialize)]
@@ -23,10 +33,139 @@ pub async fn get_users(
     Query(query): Query<UserQuery>,
 ) -> Result<Json<Vec<common::user::UserWithRelation>>, ApiError> {
     let user = auth.user.unwrap();
-    let users = fetch_user_relationships(&db, user.id, &query.search).await?;
+    // Users that have blocked each other can't find each other
+    let users = fetch_user_relationships(&db, user.id, &query.search)
+        .await?
+        .into_iter()
+        .filter(|it| it.status != Some(FriendshipStatus::Blocked.into()))
+        .collect();
     Ok(Json(users))
 }
 
+// Deletes the account of the user with everything they own. Their private ingredients that the
+// recipes of other users uses are kept in the catalog
+pub async fn delete_me<T>(
+    mut auth: AuthSession,
+    State(state): State<AppState<T>>,
+) -> Result<StatusCode, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.clone().unwrap();
+
+    let tx = state.db.begin().await?;
+
+    let avatar = users::Entity::find_by_id(user.id)
+        .one(&tx)
+        .await?
+        .and_then(|it| it.avatar);
+    let images = recipes::Entity::find()
+        .filter(recipes::Column::UserId.eq(user.id))
+        .all(&tx)
+        .await?
+        .into_iter()
+        .filter_map(|it| it.img)
+        .chain(avatar)
+        .collect::<Vec<_>>();
+
+    recipes::Entity::delete_many()
+        .filter(recipes::Column::UserId.eq(user.id))
+        .exec(&tx)
+        .await?;
+
+    // The recipes of the user are gone, so the ingredients that are still used are used by others
+    let private = ingredients::Entity::find()
+        .filter(ingredients::Column::UserId.eq(user.id))
+        .all(&tx)
+        .await?;
+    let used = recipe_ingredients::Entity::find()
+        .select_only()
+        .column(recipe_ingredients::Column::IngredientId)
+        .distinct()
+        .filter(recipe_ingredients::Column::IngredientId.is_in(private.iter().map(|it| it.id)))
+        .into_tuple::<i32>()
+        .all(&tx)
+        .await?;
+
+    for ingredient in private.iter().filter(|it| used.contains(&it.id)) {
+        keep_in_catalog(&tx, ingredient).await?;
+    }
+
+    ingredients::Entity::delete_many()
+        .filter(ingredients::Column::UserId.eq(user.id))
+        .exec(&tx)
+        .await?;
+
+    // Everything else the user has, e.g. friendships, shares and collections, is deleted with them
+    users::Entity::delete_by_id(user.id).exec(&tx).await?;
+
+    tx.commit().await?;
+
+    // The account is already gone, so failing to clean up after it is not an error for the user
+    for img in images {
+        if let Err(err) = state.storage.delete(img).await {
+            log::warn!(
+                "Failed to delete image {img} of deleted user {}: {err}",
+                user.id
+            );
+        }
+    }
+
+    auth.logout()
+        .await
+        .map_err(|err| ApiError::UnknownError(err.to_string()))?;
+
+    if let Err(err) = state.sessions.delete_user_sessions(user.id).await {
+        log::warn!(
+            "Failed to delete the sessions of deleted user {}: {err}",
+            user.id
+        );
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Makes the ingredient a part of the catalog. If the catalog already has an ingredient with the
+// same name, the recipes are moved over to that one instead
+async fn keep_in_catalog<C>(db: &C, ingredient: &ingredients::Model) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let existing = ingredients::Entity::find()
+        .filter(ingredients::Column::UserId.is_null())
+        .filter(ingredients::Column::NormalizedName.eq(&ingredient.normalized_name))
+        .one(db)
+        .await?;
+
+    match existing {
+        Some(existing) => {
+            recipe_ingredients::Entity::update_many()
+                .col_expr(
+                    recipe_ingredients::Column::IngredientId,
+                    Expr::value(existing.id),
+                )
+                .filter(recipe_ingredients::Column::IngredientId.eq(ingredient.id))
+                .exec(db)
+                .await?;
+
+            ingredients::Entity::delete_by_id(ingredient.id)
+                .exec(db)
+                .await?;
+        }
+        None => {
+            ingredients::Entity::update(ingredients::ActiveModel {
+                id: Set(ingredient.id),
+                user_id: Set(None),
+                ..Default::default()
+            })
+            .exec(db)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
 pub async fn fetch_user_relationships<C>(
     db: &C,
     id: i32,
@@ -0,0 +1,417 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use common::{
+    voting::{CreateVotingSession, VotingCandidate, VotingParticipant, VotingSession},
+    websocket::FoodieMessageType,
+};
+use hyper::StatusCode;
+use itertools::Itertools;
+use sea_orm::{
+    ActiveValue::NotSet, ColumnTrait, ConnectionTrait, DatabaseConnection, DatabaseTransaction,
+    EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+
+use crate::{
+    api::{
+        notification::{notify, NotificationTarget},
+        recipe::has_access_to_recipe,
+        users::fetch_user_relationships,
+        websocket::send_message,
+    },
+    app::AppState,
+    auth_backend::AuthSession,
+    entities::{
+        recipes,
+        sea_orm_active_enums::{FriendshipStatus, NotificationKind},
+        users, voting_session_participants, voting_session_recipes, voting_sessions,
+    },
+    storage::FoodieStorage,
+    ApiError,
+};
+
+// Starts a vote between some recipes, and invites friends to it
+pub async fn post_voting_session<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Json(session): Json<CreateVotingSession>,
+) -> Result<Json<VotingSession>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let recipe_ids = session.recipe_ids.into_iter().unique().collect::<Vec<_>>();
+    if recipe_ids.len() < 2 {
+        return Err(ApiError::StatusCode(
+            StatusCode::BAD_REQUEST,
+            "A vote needs at least two recipes".to_string(),
+        ));
+    }
+
+    let has_access = has_access_to_recipe(&state.db, user.id).await?;
+    let accessible = recipes::Entity::find()
+        .filter(has_access)
+        .filter(recipes::Column::Id.is_in(recipe_ids.clone()))
+        .count(&state.db)
+        .await?;
+    if accessible != recipe_ids.len() as u64 {
+        return Err(ApiError::RecordNotFound);
+    }
+
+    let friend_ids = fetch_user_relationships(&state.db, user.id, "")
+        .await?
+        .into_iter()
+        .filter(|it| it.status == Some(FriendshipStatus::Accepted.into()))
+        .map(|it| it.id)
+        .collect::<Vec<_>>();
+
+    let participant_ids = session
+        .participant_ids
+        .into_iter()
+        .filter(|it| *it != user.id)
+        .unique()
+        .collect::<Vec<_>>();
+    if participant_ids.iter().any(|it| !friend_ids.contains(it)) {
+        return Err(ApiError::StatusCode(
+            StatusCode::BAD_REQUEST,
+            "Only friends can be invited to vote".to_string(),
+        ));
+    }
+
+    let name = session.name.trim();
+    let name = if name.is_empty() {
+        "What's for dinner?"
+    } else {
+        name
+    };
+
+    let tx = state.db.begin().await?;
+
+    let created_session = voting_sessions::Entity::insert(voting_sessions::ActiveModel {
+        id: NotSet,
+        owner_id: Set(user.id),
+        name: Set(name.to_string()),
+        created_at: NotSet,
+        closed_at: NotSet,
+        winner_recipe_id: NotSet,
+    })
+    .exec_with_returning(&tx)
+    .await?;
+
+    voting_session_recipes::Entity::insert_many(recipe_ids.iter().enumerate().map(
+        |(position, recipe_id)| voting_session_recipes::ActiveModel {
+            session_id: Set(created_session.id),
+            recipe_id: Set(*recipe_id),
+            position: Set(position as i32),
+        },
+    ))
+    .exec_without_returning(&tx)
+    .await?;
+
+    voting_session_participants::Entity::insert_many(
+        std::iter::once(user.id)
+            .chain(participant_ids)
+            .map(|user_id| voting_session_participants::ActiveModel {
+                session_id: Set(created_session.id),
+                user_id: Set(user_id),
+                recipe_id: NotSet,
+                voted_at: NotSet,
+            }),
+    )
+    .exec_without_returning(&tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let (session, votes) = to_voting_session(&state.db, created_session).await?;
+    let invited = votes
+        .keys()
+        .filter(|it| **it != user.id)
+        .copied()
+        .collect::<Vec<_>>();
+    notify(
+        &state,
+        &invited,
+        NotificationKind::VotingSessionInvite,
+        &user,
+        NotificationTarget {
+            voting_session_id: Some(session.id),
+            ..Default::default()
+        },
+    )
+    .await;
+    for user_id in votes.keys().filter(|it| **it != user.id) {
+        send_message(
+            &state.connections,
+            *user_id,
+            FoodieMessageType::VotingSessionInvite(with_vote(&session, &votes, *user_id)),
+        );
+    }
+
+    Ok(Json(with_vote(&session, &votes, user.id)))
+}
+
+// Gets the sessions the user takes part in, the open ones first
+pub async fn get_voting_sessions(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+) -> Result<Json<Vec<VotingSession>>, ApiError> {
+    let user = auth.user.unwrap();
+
+    let session_ids = voting_session_participants::Entity::find()
+        .filter(voting_session_participants::Column::UserId.eq(user.id))
+        .all(&db)
+        .await?
+        .into_iter()
+        .map(|it| it.session_id);
+
+    let models = voting_sessions::Entity::find()
+        .filter(voting_sessions::Column::Id.is_in(session_ids))
+        .order_by_asc(voting_sessions::Column::ClosedAt.is_not_null())
+        .order_by_desc(voting_sessions::Column::CreatedAt)
+        .all(&db)
+        .await?;
+
+    let mut sessions = vec![];
+    for model in models {
+        let (session, votes) = to_voting_session(&db, model).await?;
+        sessions.push(with_vote(&session, &votes, user.id));
+    }
+
+    Ok(Json(sessions))
+}
+
+pub async fn get_voting_session(
+    auth: AuthSession,
+    State(db): State<DatabaseConnection>,
+    Path(session_id): Path<i32>,
+) -> Result<Json<VotingSession>, ApiError> {
+    let user = auth.user.unwrap();
+
+    let model = find_participating_session(&db, user.id, session_id).await?;
+    let (session, votes) = to_voting_session(&db, model).await?;
+
+    Ok(Json(with_vote(&session, &votes, user.id)))
+}
+
+// Closes the vote and sends the result to everyone taking part
+pub async fn close_voting_session<T>(
+    auth: AuthSession,
+    State(state): State<AppState<T>>,
+    Path(session_id): Path<i32>,
+) -> Result<Json<VotingSession>, ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let user = auth.user.unwrap();
+
+    let tx = state.db.begin().await?;
+    let model = lock_participating_session(&tx, user.id, session_id).await?;
+    if model.owner_id != user.id {
+        return Err(ApiError::StatusCode(
+            StatusCode::FORBIDDEN,
+            "Only the one who started the vote can close it".to_string(),
+        ));
+    }
+    if model.closed_at.is_some() {
+        return Err(ApiError::ConflictError(
+            "The vote is already closed".to_string(),
+        ));
+    }
+
+    let (session, _) = to_voting_session(&tx, model).await?;
+
+    // The candidates are in the order they are shown, so ties go to the first one
+    let winner = session
+        .candidates
+        .iter()
+        .rev()
+        .filter(|it| it.votes > 0)
+        .max_by_key(|it| it.votes)
+        .map(|it| it.recipe_id);
+
+    let closed_session = voting_sessions::Entity::update(voting_sessions::ActiveModel {
+        id: Set(session_id),
+        closed_at: Set(Some(chrono::Utc::now().into())),
+        winner_recipe_id: Set(winner),
+        ..Default::default()
+    })
+    .exec(&tx)
+    .await?;
+    tx.commit().await?;
+
+    let (session, votes) = to_voting_session(&state.db, closed_session).await?;
+    for user_id in votes.keys() {
+        send_message(
+            &state.connections,
+            *user_id,
+            FoodieMessageType::VotingSessionClosed(with_vote(&session, &votes, *user_id)),
+        );
+    }
+
+    Ok(Json(with_vote(&session, &votes, user.id)))
+}
+
+// Votes for a recipe in an open session, replacing any earlier vote. Everyone taking part gets
+// the new count of votes. This is what the websocket does with a `FoodieMessageType::Vote`
+pub async fn cast_vote<T>(
+    state: &AppState<T>,
+    user_id: i32,
+    session_id: i32,
+    recipe_id: i32,
+) -> Result<(), ApiError>
+where
+    T: FoodieStorage + Send + Sync + Clone,
+{
+    let tx = state.db.begin().await?;
+    let model = lock_participating_session(&tx, user_id, session_id).await?;
+    if model.closed_at.is_some() {
+        return Err(ApiError::ConflictError(
+            "The vote is already closed".to_string(),
+        ));
+    }
+
+    voting_session_recipes::Entity::find_by_id((session_id, recipe_id))
+        .one(&tx)
+        .await?
+        .ok_or(ApiError::StatusCode(
+            StatusCode::BAD_REQUEST,
+            "The recipe is not part of the vote".to_string(),
+        ))?;
+
+    voting_session_participants::Entity::update(voting_session_participants::ActiveModel {
+        session_id: Set(session_id),
+        user_id: Set(user_id),
+        recipe_id: Set(Some(recipe_id)),
+        voted_at: Set(Some(chrono::Utc::now().into())),
+    })
+    .exec(&tx)
+    .await?;
+    tx.commit().await?;
+
+    let (session, votes) = to_voting_session(&state.db, model).await?;
+    for user_id in votes.keys() {
+        send_message(
+            &state.connections,
+            *user_id,
+            FoodieMessageType::VotingSessionUpdate(with_vote(&session, &votes, *user_id)),
+        );
+    }
+
+    Ok(())
+}
+
+// Responds with not found for sessions the user doesn't take part in, to not leak that they exist
+async fn find_participating_session<C>(
+    db: &C,
+    user_id: i32,
+    session_id: i32,
+) -> Result<voting_sessions::Model, ApiError>
+where
+    C: ConnectionTrait,
+{
+    voting_session_participants::Entity::find_by_id((session_id, user_id))
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)?;
+
+    voting_sessions::Entity::find_by_id(session_id)
+        .one(db)
+        .await?
+        .ok_or(ApiError::RecordNotFound)
+}
+
+// Like `find_participating_session`, but keeps the session locked until the transaction ends.
+// Voting and closing both take the lock, so no vote is saved after the winner is picked
+async fn lock_participating_session(
+    tx: &DatabaseTransaction,
+    user_id: i32,
+    session_id: i32,
+) -> Result<voting_sessions::Model, ApiError> {
+    find_participating_session(tx, user_id, session_id).await?;
+
+    voting_sessions::Entity::find_by_id(session_id)
+        .lock_exclusive()
+        .one(tx)
+        .await?
+        .ok_or(ApiError::RecordNotFound)
+}
+
+// Returns the session without the requesting user's vote, together with what everyone voted for
+async fn to_voting_session<C>(
+    db: &C,
+    model: voting_sessions::Model,
+) -> Result<(VotingSession, HashMap<i32, Option<i32>>), ApiError>
+where
+    C: ConnectionTrait,
+{
+    let participants = voting_session_participants::Entity::find()
+        .filter(voting_session_participants::Column::SessionId.eq(model.id))
+        .find_also_related(users::Entity)
+        .order_by_asc(voting_session_participants::Column::VotedAt.is_null())
+        .order_by_asc(voting_session_participants::Column::VotedAt)
+        .all(db)
+        .await?;
+
+    let candidates = voting_session_recipes::Entity::find()
+        .filter(voting_session_recipes::Column::SessionId.eq(model.id))
+        .find_also_related(recipes::Entity)
+        .order_by_asc(voting_session_recipes::Column::Position)
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|(candidate, recipe)| {
+            Some(VotingCandidate {
+                recipe_id: candidate.recipe_id,
+                name: recipe?.name,
+                votes: participants
+                    .iter()
+                    .filter(|(it, _)| it.recipe_id == Some(candidate.recipe_id))
+                    .count() as i32,
+            })
+        })
+        .collect();
+
+    let votes = participants
+        .iter()
+        .map(|(it, _)| (it.user_id, it.recipe_id))
+        .collect();
+
+    let session = VotingSession {
+        id: model.id,
+        owner_id: model.owner_id,
+        name: model.name,
+        created_at: model.created_at,
+        closed_at: model.closed_at,
+        winner_recipe_id: model.winner_recipe_id,
+        candidates,
+        participants: participants
+            .into_iter()
+            .filter_map(|(participant, user)| {
+                Some(VotingParticipant {
+                    user_id: participant.user_id,
+                    name: user?.name,
+                    has_voted: participant.recipe_id.is_some(),
+                })
+            })
+            .collect(),
+        vote: None,
+    };
+
+    Ok((session, votes))
+}
+
+fn with_vote(
+    session: &VotingSession,
+    votes: &HashMap<i32, Option<i32>>,
+    user_id: i32,
+) -> VotingSession {
+    VotingSession {
+        vote: votes.get(&user_id).copied().flatten(),
+        ..session.clone()
+    }
+}
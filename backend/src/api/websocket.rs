@@ -11,9 +11,17 @@ use axum::{
     response::Response,
 };
 use common::websocket::FoodieMessageType;
+use futures_util::{SinkExt, StreamExt};
 use tokio::sync::mpsc::{self, UnboundedSender};
 
-use crate::{app::AppState, auth_backend::AuthSession, storage::FoodieStorage};
+use crate::{
+    api::voting::cast_vote, app::AppState, auth_backend::AuthSession, storage::FoodieStorage,
+    ApiError,
+};
+
+// A user can be connected from several tabs or devices, and every page that listens has its own
+// connection
+pub type Connections = Arc<RwLock<HashMap<i32, Vec<UnboundedSender<FoodieMessageType>>>>>;
 
 pub async fn websocket_handler<T>(
     ws: WebSocketUpgrade,
@@ -21,26 +29,97 @@ pub async fn websocket_handler<T>(
     State(state): State<AppState<T>>,
 ) -> Response
 where
-    T: FoodieStorage + Send + Sync + Clone,
+    T: FoodieStorage + Send + Sync + Clone + 'static,
 {
     let auth = auth.user.unwrap();
-    ws.on_upgrade(move |socket| handle_socket(socket, auth.id, state.connections))
+    ws.on_upgrade(move |socket| handle_socket(socket, auth.id, state))
+}
+
+// Sends a message to every connection of the user, if they are connected
+pub fn send_message(connections: &Connections, user_id: i32, message: FoodieMessageType) {
+    for tx in connections
+        .read()
+        .unwrap()
+        .get(&user_id)
+        .into_iter()
+        .flatten()
+    {
+        let _ = tx.send(message.clone());
+    }
 }
 
-async fn handle_socket(
-    mut socket: WebSocket,
-    user_id: i32,
-    connections: Arc<RwLock<HashMap<i32, UnboundedSender<FoodieMessageType>>>>,
-) {
+async fn handle_socket<T>(socket: WebSocket, user_id: i32, state: AppState<T>)
+where
+    T: FoodieStorage + Send + Sync + Clone + 'static,
+{
+    let (mut sender, mut receiver) = socket.split();
+
     let (tx, mut rx) = mpsc::unbounded_channel::<FoodieMessageType>();
-    connections.write().unwrap().insert(user_id, tx);
+    state
+        .connections
+        .write()
+        .unwrap()
+        .entry(user_id)
+        .or_default()
+        .push(tx.clone());
 
-    while let Some(msg) = rx.recv().await {
-        let bytes = Utf8Bytes::from(serde_json::to_string(&msg).unwrap());
-        if socket.send(Message::Text(bytes)).await.is_err() {
-            break;
+    let mut send_task = tokio::spawn(async move {
+        while let Some(msg) = rx.recv().await {
+            let bytes = Utf8Bytes::from(serde_json::to_string(&msg).unwrap());
+            if sender.send(Message::Text(bytes)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let recv_state = state.clone();
+    let recv_tx = tx.clone();
+    let mut recv_task = tokio::spawn(async move {
+        while let Some(Ok(msg)) = receiver.next().await {
+            let Message::Text(text) = msg else {
+                continue;
+            };
+
+            match text.parse::<FoodieMessageType>() {
+                Ok(FoodieMessageType::Vote {
+                    session_id,
+                    recipe_id,
+                }) => {
+                    if let Err(err) = cast_vote(&recv_state, user_id, session_id, recipe_id).await {
+                        log::warn!("Failed to vote in session {session_id}: {err:?}");
+                        // Only this connection voted, so the other ones don't need to know
+                        let _ = recv_tx.send(FoodieMessageType::VoteFailed {
+                            session_id,
+                            error: vote_error(err),
+                        });
+                    }
+                }
+                Ok(msg) => log::warn!("Unexpected message from client: {msg:?}"),
+                Err(err) => log::warn!("Invalid message from client: {err}"),
+            }
+        }
+    });
+
+    tokio::select! {
+        _ = &mut send_task => recv_task.abort(),
+        _ = &mut recv_task => send_task.abort(),
+    }
+
+    // The user might have other connections, so only remove this one
+    let mut connections = state.connections.write().unwrap();
+    if let Some(senders) = connections.get_mut(&user_id) {
+        senders.retain(|it| !it.same_channel(&tx));
+        if senders.is_empty() {
+            connections.remove(&user_id);
         }
     }
+}
 
-    connections.write().unwrap().remove(&user_id);
+// The reasons a vote is refused are safe to show, while other errors are only logged
+fn vote_error(err: ApiError) -> String {
+    match err {
+        ApiError::ConflictError(err) | ApiError::StatusCode(_, err) => err,
+        ApiError::RecordNotFound => "The vote was not found".to_string(),
+        _ => "Failed to vote".to_string(),
+    }
 }
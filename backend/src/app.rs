@@ -1,24 +1,59 @@
 use crate::{
     api::{
         auth::{get_me, login, logout, register},
-        friends::{accept_friendship, get_pending, reject_friendship, send_friend_request},
-        ingredient::{delete_ingredient, get_ingredient, get_ingredients, post_ingredient},
+        collection::{
+            delete_collection, delete_collection_recipe, delete_favorite, get_collection,
+            get_collection_recipes, get_collections, get_favorites, get_recipe_bookmarks,
+            post_collection, put_collection_recipe, put_favorite, update_collection,
+        },
+        duplicate::merge_recipe,
+        feed::{get_feed, post_cooked},
+        friends::{
+            accept_friendship, block_user, cancel_friend_request, get_blocked,
+            get_friend_suggestions, get_friends, get_outgoing, get_pending, reject_friendship,
+            remove_friend, send_friend_request, unblock_user,
+        },
+        ingredient::{
+            delete_ingredient, delete_ingredient_category, get_aisle_order, get_ingredient,
+            get_ingredient_aliases, get_ingredients, merge_ingredient, post_ingredient,
+            post_ingredient_alias, promote_ingredient, put_aisle_order, put_ingredient_category,
+            put_ingredient_default_category, put_ingredient_dietary, search_ingredients,
+        },
+        invite::{get_invites, post_invite},
+        meal_plan::{
+            delete_planned_meal, get_calendar, get_calendar_feed, get_meal_plan, post_planned_meal,
+            reset_calendar_feed,
+        },
+        notification::{get_notifications, get_unread_notifications, read_notifications},
         oauth::{google_callback, google_login},
+        pdf::{get_recipe_pdf, post_shopping_list_pdf},
+        profile::{delete_avatar, get_friend_profile, get_profile, put_avatar, update_profile},
         recipe::{
-            delete_recipe, get_presigned_url_for_upload, get_recipe, get_recipes, post_recipe,
-            update_recipe,
+            delete_recipe, get_cuisines, get_presigned_url_for_upload, get_recipe, get_recipes,
+            post_recipe, update_recipe,
         },
-        users::get_users,
-        websocket::websocket_handler,
+        shopping_list::post_shopping_list,
+        suggestion::get_suggestions,
+        swipe::{
+            get_swipe_deck, get_swipe_session, get_swipe_sessions, post_swipe_session, put_swipe,
+        },
+        takeout::{get_takeout, post_takeout, MAX_TAKEOUT_SIZE},
+        users::{delete_me, get_users},
+        voting::{
+            close_voting_session, get_voting_session, get_voting_sessions, post_voting_session,
+        },
+        websocket::{websocket_handler, Connections},
     },
     auth_backend::{get_oauth_client, Backend},
+    mailer::Mailer,
+    redis_store::UserSessionStore,
     storage::{self, FoodieStorage},
 };
 use axum::{
     error_handling::HandleErrorLayer,
-    extract::FromRef,
+    extract::{DefaultBodyLimit, FromRef},
     http::{HeaderValue, StatusCode},
-    routing::{any, get, post},
+    routing::{any, delete, get, post, put},
     Router,
 };
 use axum_login::{
@@ -26,14 +61,12 @@ use axum_login::{
     tower_sessions::{cookie::time::Duration, Expiry, SessionManagerLayer},
     AuthManagerLayerBuilder,
 };
-use common::websocket::FoodieMessageType;
 use hyper::{header::CONTENT_TYPE, Method};
 use sea_orm::DatabaseConnection;
 use std::{
     collections::HashMap,
     sync::{Arc, Once, RwLock},
 };
-use tokio::sync::mpsc::UnboundedSender;
 use tower::ServiceBuilder;
 use tower_http::{catch_panic::CatchPanicLayer, cors::CorsLayer};
 use tower_sessions_core::SessionStore;
@@ -45,7 +78,9 @@ where
 {
     pub db: DatabaseConnection,
     pub storage: T,
-    pub connections: Arc<RwLock<HashMap<i32, UnboundedSender<FoodieMessageType>>>>,
+    pub connections: Connections,
+    pub sessions: Arc<dyn UserSessionStore + Send + Sync>,
+    pub mailer: Arc<dyn Mailer + Send + Sync>,
 }
 
 impl<T> FromRef<AppState<T>> for DatabaseConnection
@@ -64,9 +99,13 @@ pub struct App {
 static INIT: Once = Once::new();
 
 impl App {
-    pub async fn new<S>(db: DatabaseConnection, session_store: S) -> Result<Self, anyhow::Error>
+    pub async fn new<S>(
+        db: DatabaseConnection,
+        session_store: S,
+        mailer: Arc<dyn Mailer + Send + Sync>,
+    ) -> Result<Self, anyhow::Error>
     where
-        S: SessionStore + Clone,
+        S: SessionStore + UserSessionStore + Clone,
     {
         INIT.call_once(|| {
             env_logger::builder()
@@ -78,6 +117,7 @@ impl App {
 
         let oauth_client = get_oauth_client()?;
 
+        let sessions = Arc::new(session_store.clone());
         let session_layer = SessionManagerLayer::new(session_store)
             .with_secure(!cfg!(debug_assertions))
             .with_expiry(Expiry::OnInactivity(Duration::days(1)));
@@ -95,6 +135,8 @@ impl App {
             db,
             storage,
             connections,
+            sessions,
+            mailer,
         };
 
         let cors = CorsLayer::new()
@@ -110,35 +152,126 @@ impl App {
                     .nest(
                         "/friends",
                         Router::new()
+                            .route("/", get(get_friends))
                             .route("/new/{id}", post(send_friend_request))
+                            .route("/cancel/{id}", post(cancel_friend_request))
+                            .route("/remove/{id}", post(remove_friend))
                             .route("/accept/{id}", post(accept_friendship))
                             .route("/reject/{id}", post(reject_friendship))
-                            .route("/pending", get(get_pending)),
+                            .route("/block/{id}", post(block_user))
+                            .route("/unblock/{id}", post(unblock_user))
+                            .route("/pending", get(get_pending))
+                            .route("/outgoing", get(get_outgoing))
+                            .route("/blocked", get(get_blocked))
+                            .route("/suggestions", get(get_friend_suggestions)),
                     )
                     .nest(
                         "/recipes",
                         Router::new()
                             .route("/", get(get_recipes).post(post_recipe))
+                            .route("/cuisines", get(get_cuisines))
+                            .route("/suggestions", get(get_suggestions))
                             .route(
                                 "/{id}",
                                 get(get_recipe).delete(delete_recipe).put(update_recipe),
+                            )
+                            .route("/{id}/favorite", put(put_favorite).delete(delete_favorite))
+                            .route("/{id}/bookmarks", get(get_recipe_bookmarks))
+                            .route("/{id}/pdf", get(get_recipe_pdf))
+                            .route("/{id}/merge", post(merge_recipe))
+                            .route("/{id}/cooked", post(post_cooked)),
+                    )
+                    .route("/favorites", get(get_favorites))
+                    .nest(
+                        "/collections",
+                        Router::new()
+                            .route("/", get(get_collections).post(post_collection))
+                            .route(
+                                "/{id}",
+                                get(get_collection)
+                                    .put(update_collection)
+                                    .delete(delete_collection),
+                            )
+                            .route("/{id}/recipes", get(get_collection_recipes))
+                            .route(
+                                "/{id}/recipes/{recipe_id}",
+                                put(put_collection_recipe).delete(delete_collection_recipe),
                             ),
                     )
                     .nest(
                         "/ingredients",
                         Router::new()
-                            .route("/ingredients", post(post_ingredient).get(get_ingredients))
+                            .route("/", post(post_ingredient).get(get_ingredients))
+                            .route("/search", get(search_ingredients))
+                            .route("/aisles", get(get_aisle_order).put(put_aisle_order))
+                            .route("/{id}", get(get_ingredient).delete(delete_ingredient))
+                            .route(
+                                "/{id}/aliases",
+                                get(get_ingredient_aliases).post(post_ingredient_alias),
+                            )
+                            .route("/{id}/merge", post(merge_ingredient))
+                            .route("/{id}/promote", post(promote_ingredient))
+                            .route(
+                                "/{id}/category",
+                                put(put_ingredient_category).delete(delete_ingredient_category),
+                            )
+                            .route("/{id}/dietary", put(put_ingredient_dietary))
                             .route(
-                                "/ingredients/{id}",
-                                get(get_ingredient).delete(delete_ingredient),
+                                "/{id}/default-category",
+                                put(put_ingredient_default_category),
                             ),
                     )
+                    .nest(
+                        "/voting",
+                        Router::new()
+                            .route("/", get(get_voting_sessions).post(post_voting_session))
+                            .route("/{id}", get(get_voting_session))
+                            .route("/{id}/close", post(close_voting_session)),
+                    )
+                    .nest(
+                        "/swipe",
+                        Router::new()
+                            .route("/", get(get_swipe_sessions).post(post_swipe_session))
+                            .route("/{id}", get(get_swipe_session))
+                            .route("/{id}/deck", get(get_swipe_deck))
+                            .route("/{id}/recipes/{recipe_id}", put(put_swipe)),
+                    )
+                    .nest(
+                        "/meal-plan",
+                        Router::new()
+                            .route("/", get(get_meal_plan).post(post_planned_meal))
+                            .route("/{id}", delete(delete_planned_meal))
+                            .route("/calendar", get(get_calendar_feed))
+                            .route("/calendar/reset", post(reset_calendar_feed)),
+                    )
+                    .nest(
+                        "/notifications",
+                        Router::new()
+                            .route("/", get(get_notifications))
+                            .route("/unread", get(get_unread_notifications))
+                            .route("/read", post(read_notifications)),
+                    )
+                    .route("/invites", get(get_invites).post(post_invite))
+                    .route("/feed", get(get_feed))
+                    .route("/shopping-list", post(post_shopping_list))
+                    .route("/shopping-list/pdf", post(post_shopping_list_pdf))
+                    .route(
+                        "/takeout",
+                        get(get_takeout)
+                            .post(post_takeout)
+                            .layer(DefaultBodyLimit::max(MAX_TAKEOUT_SIZE)),
+                    )
                     .route("/uploads/recipes/images", get(get_presigned_url_for_upload))
+                    .route("/uploads/avatars", get(get_presigned_url_for_upload))
                     .route("/users", get(get_users))
-                    .route("/me", get(get_me))
+                    .route("/users/{id}/profile", get(get_friend_profile))
+                    .route("/me", get(get_me).delete(delete_me))
+                    .route("/me/profile", get(get_profile).put(update_profile))
+                    .route("/me/avatar", put(put_avatar).delete(delete_avatar))
                     .route("/ws", any(websocket_handler))
                     .route_layer(login_required!(Backend))
                     .route("/health-check", get(|| async {}))
+                    .route("/calendar/{file}", get(get_calendar))
                     .route("/register", post(register))
                     .route("/login", post(login))
                     .route("/logout", post(logout))
@@ -104,10 +104,13 @@ impl AuthnBackend for Backend {
             password: NotSet,
             name: Set(google_user.name),
             email: Set(google_user.email),
+            picture: Set(Some(google_user.picture)),
+            ..Default::default()
         })
+        // The picture is kept up to date with the one in the Google account
         .on_conflict(
             OnConflict::column(users::Column::Email)
-                .update_column(users::Column::Email)
+                .update_columns([users::Column::Email, users::Column::Picture])
                 .to_owned(),
         )
         .exec_with_returning(&self.db)
@@ -116,21 +119,22 @@ impl AuthnBackend for Backend {
 
         Ok(Some(User {
             id: user_model.id,
-            name: user_model.email,
-            email: user_model.name,
+            name: user_model.name,
+            email: user_model.email,
         }))
     }
 
     async fn get_user(&self, user_id: &UserId<Self>) -> Result<Option<Self::User>, Self::Error> {
+        // The user is gone if the account has been deleted, which logs out the session
         let user = entities::users::Entity::find_by_id(*user_id)
             .one(&self.db)
             .await
-            .unwrap()
             .unwrap();
 
-        Ok(Some(User {
+        // The display name is what everyone else sees, so it is used everywhere the user is shown
+        Ok(user.map(|user| User {
             id: user.id,
-            name: user.name,
+            name: user.display_name.unwrap_or(user.name),
             email: user.email,
         }))
     }
@@ -0,0 +1,123 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use common::recipe::Duration;
+
+// How long cooking is assumed to take when the recipe has no times
+const DEFAULT_COOKING_TIME: Duration = Duration::from_minutes(30);
+// Resting shorter than this happens while cooking anyway, so it isn't worth a reminder
+const MIN_RESTING_FOR_REMINDER: Duration = Duration::from_hours(1);
+const REMINDER_LENGTH: Duration = Duration::from_minutes(15);
+
+pub struct CalendarMeal {
+    pub id: i32,
+    pub recipe_id: i32,
+    pub name: String,
+    pub eat_at: DateTime<Utc>,
+    pub total_time: Option<Duration>,
+    pub resting_time: Option<Duration>,
+}
+
+/// Renders the meals as an iCalendar feed. Every meal is an event from when cooking has to start,
+/// from the total time of the recipe, until it is ready to eat. With `reminders`, recipes that
+/// have to rest for a while, e.g. marinating or proofing, also get a prep reminder with an alarm
+/// when the resting has to start
+pub fn meal_plan_calendar(meals: &[CalendarMeal], frontend_url: &str, reminders: bool) -> String {
+    let now = Utc::now();
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Foodie//Meal plan//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Meal plan".to_string(),
+    ];
+
+    for meal in meals {
+        let url = format!("{frontend_url}/recipes/{}", meal.recipe_id);
+        let total_time = meal.total_time.unwrap_or(DEFAULT_COOKING_TIME);
+        let start = meal.eat_at - minutes(total_time);
+
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:meal-{}@foodie", meal.id),
+            format!("DTSTAMP:{}", format_time(now)),
+            format!("DTSTART:{}", format_time(start)),
+            format!("DTEND:{}", format_time(meal.eat_at)),
+            format!("SUMMARY:{}", escape(&meal.name)),
+            format!(
+                "DESCRIPTION:{}",
+                escape(&format!("Takes {total_time}\n{url}"))
+            ),
+            format!("URL:{url}"),
+            "END:VEVENT".to_string(),
+        ]);
+
+        let resting_time = meal.resting_time.unwrap_or_default();
+        if !reminders || resting_time < MIN_RESTING_FOR_REMINDER {
+            continue;
+        }
+
+        let description = format!(
+            "{} needs {resting_time} of resting, e.g. marinating or proofing, so start it now to \
+             have it ready in time\n{url}",
+            meal.name
+        );
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:prep-{}@foodie", meal.id),
+            format!("DTSTAMP:{}", format_time(now)),
+            format!("DTSTART:{}", format_time(start)),
+            format!("DTEND:{}", format_time(start + minutes(REMINDER_LENGTH))),
+            format!("SUMMARY:{}", escape(&format!("Prep {}", meal.name))),
+            format!("DESCRIPTION:{}", escape(&description)),
+            format!("URL:{url}"),
+            "BEGIN:VALARM".to_string(),
+            "ACTION:DISPLAY".to_string(),
+            format!("DESCRIPTION:{}", escape(&format!("Prep {}", meal.name))),
+            "TRIGGER:PT0M".to_string(),
+            "END:VALARM".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold(line))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+fn minutes(duration: Duration) -> TimeDelta {
+    TimeDelta::minutes(duration.as_minutes() as i64)
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// Lines can be at most 75 bytes long, and longer lines continue on the next line after a space
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        // The continued lines start with a space, so they have room for one byte less
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
@@ -22,7 +22,7 @@ pub enum Relation {
         from = "Column::RecipientId",
         to = "super::users::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "Cascade"
     )]
     Users2,
     #[sea_orm(
@@ -30,7 +30,7 @@ pub enum Relation {
         from = "Column::RequesterId",
         to = "super::users::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "Cascade"
     )]
     Users1,
 }
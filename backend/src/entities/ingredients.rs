@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+use super::sea_orm_active_enums::IngredientCategory;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -7,31 +8,51 @@ use sea_orm::entity::prelude::*;
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
-    #[sea_orm(unique)]
     pub name: String,
-    pub user_id: i32,
+    pub user_id: Option<i32>,
+    pub normalized_name: String,
+    pub category: IngredientCategory,
+    pub allergens: Vec<String>,
+    pub vegetarian: bool,
+    pub vegan: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::ingredient_aliases::Entity")]
+    IngredientAliases,
     #[sea_orm(has_many = "super::recipe_ingredients::Entity")]
     RecipeIngredients,
+    #[sea_orm(has_many = "super::user_ingredient_categories::Entity")]
+    UserIngredientCategories,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::UserId",
         to = "super::users::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "SetNull"
     )]
     Users,
 }
 
+impl Related<super::ingredient_aliases::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::IngredientAliases.def()
+    }
+}
+
 impl Related<super::recipe_ingredients::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::RecipeIngredients.def()
     }
 }
 
+impl Related<super::user_ingredient_categories::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserIngredientCategories.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Users.def()
@@ -2,10 +2,29 @@
 
 pub mod prelude;
 
+pub mod calendar_feeds;
+pub mod collection_recipes;
+pub mod collections;
+pub mod favorites;
 pub mod friendships;
+pub mod ingredient_aliases;
 pub mod ingredients;
+pub mod invites;
+pub mod notifications;
+pub mod planned_meals;
+pub mod recipe_cooks;
 pub mod recipe_ingredients;
 pub mod recipe_share;
+pub mod recipe_views;
 pub mod recipes;
 pub mod sea_orm_active_enums;
+pub mod swipe_matches;
+pub mod swipe_session_participants;
+pub mod swipe_sessions;
+pub mod swipes;
+pub mod user_aisle_orders;
+pub mod user_ingredient_categories;
 pub mod users;
+pub mod voting_session_participants;
+pub mod voting_session_recipes;
+pub mod voting_sessions;
@@ -1,8 +1,27 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+pub use super::calendar_feeds::Entity as CalendarFeeds;
+pub use super::collection_recipes::Entity as CollectionRecipes;
+pub use super::collections::Entity as Collections;
+pub use super::favorites::Entity as Favorites;
 pub use super::friendships::Entity as Friendships;
+pub use super::ingredient_aliases::Entity as IngredientAliases;
 pub use super::ingredients::Entity as Ingredients;
+pub use super::invites::Entity as Invites;
+pub use super::notifications::Entity as Notifications;
+pub use super::planned_meals::Entity as PlannedMeals;
+pub use super::recipe_cooks::Entity as RecipeCooks;
 pub use super::recipe_ingredients::Entity as RecipeIngredients;
 pub use super::recipe_share::Entity as RecipeShare;
+pub use super::recipe_views::Entity as RecipeViews;
 pub use super::recipes::Entity as Recipes;
+pub use super::swipe_matches::Entity as SwipeMatches;
+pub use super::swipe_session_participants::Entity as SwipeSessionParticipants;
+pub use super::swipe_sessions::Entity as SwipeSessions;
+pub use super::swipes::Entity as Swipes;
+pub use super::user_aisle_orders::Entity as UserAisleOrders;
+pub use super::user_ingredient_categories::Entity as UserIngredientCategories;
 pub use super::users::Entity as Users;
+pub use super::voting_session_participants::Entity as VotingSessionParticipants;
+pub use super::voting_session_recipes::Entity as VotingSessionRecipes;
+pub use super::voting_sessions::Entity as VotingSessions;
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+use super::sea_orm_active_enums::RecipeDifficulty;
 use super::sea_orm_active_enums::RecipeVisibility;
 use sea_orm::entity::prelude::*;
 
@@ -13,28 +14,69 @@ pub struct Model {
     pub description: Option<String>,
     pub img: Option<Uuid>,
     pub servings: i32,
-    pub prep_time: Option<Time>,
-    pub baking_time: Option<Time>,
+    pub prep_minutes: Option<i32>,
+    pub baking_minutes: Option<i32>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
     pub instructions: Option<Vec<String>>,
     pub visibility: RecipeVisibility,
+    pub cooking_minutes: Option<i32>,
+    pub resting_minutes: Option<i32>,
+    pub source_url: Option<String>,
+    pub source_name: Option<String>,
+    pub cuisine: Option<String>,
+    pub difficulty: Option<RecipeDifficulty>,
+    pub yield_unit: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::collection_recipes::Entity")]
+    CollectionRecipes,
+    #[sea_orm(has_many = "super::favorites::Entity")]
+    Favorites,
     #[sea_orm(has_many = "super::recipe_ingredients::Entity")]
     RecipeIngredients,
     #[sea_orm(has_many = "super::recipe_share::Entity")]
     RecipeShare,
+    #[sea_orm(has_many = "super::recipe_views::Entity")]
+    RecipeViews,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::UserId",
         to = "super::users::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "Cascade"
     )]
     Users,
+    #[sea_orm(has_many = "super::voting_session_participants::Entity")]
+    VotingSessionParticipants,
+    #[sea_orm(has_many = "super::voting_session_recipes::Entity")]
+    VotingSessionRecipes,
+    #[sea_orm(has_many = "super::voting_sessions::Entity")]
+    VotingSessions,
+    #[sea_orm(has_many = "super::swipe_matches::Entity")]
+    SwipeMatches,
+    #[sea_orm(has_many = "super::swipes::Entity")]
+    Swipes,
+    #[sea_orm(has_many = "super::planned_meals::Entity")]
+    PlannedMeals,
+    #[sea_orm(has_many = "super::recipe_cooks::Entity")]
+    RecipeCooks,
+    #[sea_orm(has_many = "super::notifications::Entity")]
+    Notifications,
+}
+
+impl Related<super::collection_recipes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CollectionRecipes.def()
+    }
+}
+
+impl Related<super::favorites::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Favorites.def()
+    }
 }
 
 impl Related<super::recipe_ingredients::Entity> for Entity {
@@ -64,4 +106,58 @@ impl Related<super::ingredients::Entity> for Entity {
     }
 }
 
+impl Related<super::recipe_views::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecipeViews.def()
+    }
+}
+
+impl Related<super::voting_session_participants::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::VotingSessionParticipants.def()
+    }
+}
+
+impl Related<super::voting_session_recipes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::VotingSessionRecipes.def()
+    }
+}
+
+impl Related<super::voting_sessions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::VotingSessions.def()
+    }
+}
+
+impl Related<super::swipe_matches::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SwipeMatches.def()
+    }
+}
+
+impl Related<super::swipes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Swipes.def()
+    }
+}
+
+impl Related<super::planned_meals::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PlannedMeals.def()
+    }
+}
+
+impl Related<super::notifications::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notifications.def()
+    }
+}
+
+impl Related<super::recipe_cooks::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecipeCooks.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -15,6 +15,56 @@ pub enum FriendshipStatus {
     Blocked,
 }
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "ingredient_category"
+)]
+pub enum IngredientCategory {
+    #[sea_orm(string_value = "produce")]
+    Produce,
+    #[sea_orm(string_value = "dairy")]
+    Dairy,
+    #[sea_orm(string_value = "meat")]
+    Meat,
+    #[sea_orm(string_value = "seafood")]
+    Seafood,
+    #[sea_orm(string_value = "bakery")]
+    Bakery,
+    #[sea_orm(string_value = "dry_goods")]
+    DryGoods,
+    #[sea_orm(string_value = "spices")]
+    Spices,
+    #[sea_orm(string_value = "frozen")]
+    Frozen,
+    #[sea_orm(string_value = "beverages")]
+    Beverages,
+    #[sea_orm(string_value = "other")]
+    Other,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "notification_kind")]
+pub enum NotificationKind {
+    #[sea_orm(string_value = "friend_recipe")]
+    FriendRecipe,
+    #[sea_orm(string_value = "friend_request")]
+    FriendRequest,
+    #[sea_orm(string_value = "friend_request_accepted")]
+    FriendRequestAccepted,
+    #[sea_orm(string_value = "voting_session_invite")]
+    VotingSessionInvite,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "recipe_difficulty")]
+pub enum RecipeDifficulty {
+    #[sea_orm(string_value = "easy")]
+    Easy,
+    #[sea_orm(string_value = "medium")]
+    Medium,
+    #[sea_orm(string_value = "hard")]
+    Hard,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "recipe_visibility")]
 pub enum RecipeVisibility {
     #[sea_orm(string_value = "friends")]
@@ -50,3 +100,11 @@ pub enum Unit {
     #[sea_orm(string_value = "pinch")]
     Pinch,
 }
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "unit_system")]
+pub enum UnitSystem {
+    #[sea_orm(string_value = "imperial")]
+    Imperial,
+    #[sea_orm(string_value = "metric")]
+    Metric,
+}
@@ -1,5 +1,6 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+use super::sea_orm_active_enums::UnitSystem;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -11,16 +12,62 @@ pub struct Model {
     pub email: String,
     pub password: Option<String>,
     pub name: String,
+    pub display_name: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub bio: Option<String>,
+    pub avatar: Option<Uuid>,
+    pub picture: Option<String>,
+    pub preferred_units: UnitSystem,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::collections::Entity")]
+    Collections,
+    #[sea_orm(has_many = "super::favorites::Entity")]
+    Favorites,
     #[sea_orm(has_many = "super::ingredients::Entity")]
     Ingredients,
     #[sea_orm(has_many = "super::recipe_share::Entity")]
     RecipeShare,
     #[sea_orm(has_many = "super::recipes::Entity")]
     Recipes,
+    #[sea_orm(has_many = "super::recipe_views::Entity")]
+    RecipeViews,
+    #[sea_orm(has_many = "super::user_aisle_orders::Entity")]
+    UserAisleOrders,
+    #[sea_orm(has_many = "super::user_ingredient_categories::Entity")]
+    UserIngredientCategories,
+    #[sea_orm(has_many = "super::voting_session_participants::Entity")]
+    VotingSessionParticipants,
+    #[sea_orm(has_many = "super::voting_sessions::Entity")]
+    VotingSessions,
+    #[sea_orm(has_many = "super::swipe_session_participants::Entity")]
+    SwipeSessionParticipants,
+    #[sea_orm(has_many = "super::swipe_sessions::Entity")]
+    SwipeSessions,
+    #[sea_orm(has_many = "super::swipes::Entity")]
+    Swipes,
+    #[sea_orm(has_many = "super::planned_meals::Entity")]
+    PlannedMeals,
+    #[sea_orm(has_many = "super::recipe_cooks::Entity")]
+    RecipeCooks,
+    #[sea_orm(has_many = "super::invites::Entity")]
+    Invites,
+    #[sea_orm(has_one = "super::calendar_feeds::Entity")]
+    CalendarFeeds,
+}
+
+impl Related<super::collections::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Collections.def()
+    }
+}
+
+impl Related<super::favorites::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Favorites.def()
+    }
 }
 
 impl Related<super::ingredients::Entity> for Entity {
@@ -41,4 +88,76 @@ impl Related<super::recipes::Entity> for Entity {
     }
 }
 
+impl Related<super::user_aisle_orders::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserAisleOrders.def()
+    }
+}
+
+impl Related<super::user_ingredient_categories::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserIngredientCategories.def()
+    }
+}
+
+impl Related<super::recipe_views::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecipeViews.def()
+    }
+}
+
+impl Related<super::voting_session_participants::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::VotingSessionParticipants.def()
+    }
+}
+
+impl Related<super::voting_sessions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::VotingSessions.def()
+    }
+}
+
+impl Related<super::swipe_session_participants::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SwipeSessionParticipants.def()
+    }
+}
+
+impl Related<super::swipe_sessions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SwipeSessions.def()
+    }
+}
+
+impl Related<super::swipes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Swipes.def()
+    }
+}
+
+impl Related<super::planned_meals::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PlannedMeals.def()
+    }
+}
+
+impl Related<super::calendar_feeds::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CalendarFeeds.def()
+    }
+}
+
+impl Related<super::recipe_cooks::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecipeCooks.def()
+    }
+}
+
+impl Related<super::invites::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Invites.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
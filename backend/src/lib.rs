@@ -2,22 +2,28 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use common::recipe::{DuplicateRecipe, DuplicateRecipes};
 use hyper::StatusCode;
 use serde::{Deserialize, Serialize};
 
 pub mod api;
 pub mod app;
 pub mod auth_backend;
+pub mod calendar;
 pub mod entities;
+pub mod mailer;
+pub mod pdf;
 pub mod redis_store;
 pub mod storage;
 
+#[derive(Debug)]
 pub enum ApiError {
     RecordNotFound,
     DatabaseError(sea_orm::DbErr),
     UnknownError(String),
     ConflictError(String),
     StatusCode(StatusCode, String),
+    DuplicateRecipes(Vec<DuplicateRecipe>),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -39,6 +45,16 @@ impl IntoResponse for ApiError {
             ApiError::RecordNotFound => (StatusCode::NOT_FOUND, r#"Record not found"#.to_string()),
             ApiError::ConflictError(err) => (StatusCode::CONFLICT, err),
             ApiError::StatusCode(status_code, err) => (status_code, err),
+            ApiError::DuplicateRecipes(candidates) => {
+                return (
+                    StatusCode::CONFLICT,
+                    Json(DuplicateRecipes {
+                        error: "The recipe probably already exists".to_string(),
+                        candidates,
+                    }),
+                )
+                    .into_response()
+            }
         };
 
         (
@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use super::{Mail, Mailer};
+
+/// Writes every mail to a file in a directory instead of sending it. Used for development and
+/// tests, where the mails can be read back from the directory
+#[derive(Debug, Clone)]
+pub struct FileMailer {
+    dir: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, anyhow::Error> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+}
+
+#[async_trait::async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, mail: &Mail) -> Result<(), anyhow::Error> {
+        let path = self.dir.join(format!("{}.eml", uuid::Uuid::new_v4()));
+        let content = format!(
+            "To: {}\nSubject: {}\n\n{}\n",
+            mail.to, mail.subject, mail.body
+        );
+        tokio::fs::write(&path, content).await?;
+        log::info!("Wrote mail to {} in {}", mail.to, path.display());
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+pub mod file;
+pub mod smtp;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait::async_trait]
+pub trait Mailer {
+    async fn send(&self, mail: &Mail) -> Result<(), anyhow::Error>;
+}
+
+/// Sends mail over SMTP if `SMTP_HOST` is set, or else writes it to the directory in `MAIL_DIR`.
+/// One of them has to be set, so mail is never written to a file by accident in production
+pub fn from_env() -> Result<Arc<dyn Mailer + Send + Sync>, anyhow::Error> {
+    if let Some(mailer) = smtp::SmtpMailer::from_env()? {
+        return Ok(Arc::new(mailer));
+    }
+
+    match dotenv::var("MAIL_DIR") {
+        Ok(dir) if !dir.is_empty() => Ok(Arc::new(file::FileMailer::new(dir)?)),
+        _ => Err(anyhow::anyhow!(
+            "Either SMTP_HOST or MAIL_DIR must be set to send mail"
+        )),
+    }
+}
@@ -0,0 +1,335 @@
+use anyhow::{anyhow, bail};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_native_tls::{native_tls, TlsConnector, TlsStream};
+
+use super::{Mail, Mailer};
+
+/// Sends mail through an SMTP server, over implicit TLS by default. `SMTP_TLS` can be `starttls`
+/// for submission servers on port 587, or `false` for a local server without credentials
+#[derive(Debug, Clone)]
+pub struct SmtpMailer {
+    host: String,
+    port: u16,
+    security: Security,
+    credentials: Option<(String, String)>,
+    from: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Security {
+    Tls,
+    StartTls,
+    None,
+}
+
+impl SmtpMailer {
+    pub fn from_env() -> Result<Option<Self>, anyhow::Error> {
+        let var = |key: &str| dotenv::var(key).ok().filter(|it| !it.is_empty());
+
+        let Some(host) = var("SMTP_HOST") else {
+            return Ok(None);
+        };
+
+        let security = match var("SMTP_TLS").as_deref() {
+            None | Some("true") => Security::Tls,
+            Some("starttls") => Security::StartTls,
+            Some("false") => Security::None,
+            Some(other) => bail!("SMTP_TLS must be true, starttls or false, not {other}"),
+        };
+        let port = match var("SMTP_PORT") {
+            Some(port) => port.parse()?,
+            None => match security {
+                Security::Tls => 465,
+                Security::StartTls => 587,
+                Security::None => 25,
+            },
+        };
+        let credentials = var("SMTP_USERNAME").zip(var("SMTP_PASSWORD"));
+        let from = var("SMTP_FROM").ok_or_else(|| anyhow!("SMTP_FROM is not set"))?;
+
+        // The credentials would be readable by anyone between the server and us
+        if credentials.is_some() && security == Security::None {
+            bail!("SMTP_USERNAME and SMTP_PASSWORD can't be used when SMTP_TLS is false");
+        }
+
+        Ok(Some(Self {
+            host,
+            port,
+            security,
+            credentials,
+            from,
+        }))
+    }
+
+    async fn tls<S>(&self, stream: S) -> Result<TlsStream<S>, anyhow::Error>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let connector = TlsConnector::from(native_tls::TlsConnector::new()?);
+        Ok(connector.connect(&self.host, stream).await?)
+    }
+
+    // Sends the mail once the server has greeted us, and we have greeted it back
+    async fn deliver<S>(&self, stream: &mut BufReader<S>, mail: &Mail) -> Result<(), anyhow::Error>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        if let Some((username, password)) = &self.credentials {
+            let auth = STANDARD.encode(format!("\0{username}\0{password}"));
+            command(stream, &format!("AUTH PLAIN {auth}"), 235).await?;
+        }
+
+        command(stream, &format!("MAIL FROM:<{}>", self.from), 250).await?;
+        command(stream, &format!("RCPT TO:<{}>", mail.to), 250).await?;
+        command(stream, "DATA", 354).await?;
+        command(stream, &self.message(mail), 250).await?;
+        command(stream, "QUIT", 221).await?;
+        Ok(())
+    }
+
+    // The message with headers, ending with the line with a single dot that ends the data
+    fn message(&self, mail: &Mail) -> String {
+        let body = mail
+            .body
+            .lines()
+            // A line starting with a dot gets another one, so it is not taken as the end
+            .map(|line| match line.starts_with('.') {
+                true => format!(".{line}"),
+                false => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\r\n");
+
+        format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMIME-Version: 1.0\r\n\
+             Content-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\
+             \r\n{body}\r\n.",
+            self.from,
+            mail.to,
+            encode_header(&mail.subject),
+            chrono::Utc::now().to_rfc2822(),
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, mail: &Mail) -> Result<(), anyhow::Error> {
+        // Anything with a line break could add commands or headers of its own
+        if [&mail.to, &mail.subject]
+            .iter()
+            .any(|it| it.contains(['\r', '\n']))
+        {
+            bail!("Mail to {} has a line break in a header", mail.to);
+        }
+
+        let stream = TcpStream::connect((self.host.as_str(), self.port)).await?;
+        match self.security {
+            Security::Tls => {
+                let mut stream = BufReader::new(self.tls(stream).await?);
+                greet(&mut stream).await?;
+                self.deliver(&mut stream, mail).await
+            }
+            Security::StartTls => {
+                let mut stream = BufReader::new(stream);
+                let extensions = greet(&mut stream).await?;
+                if !extensions
+                    .iter()
+                    .any(|it| it.eq_ignore_ascii_case("STARTTLS"))
+                {
+                    bail!("SMTP server doesn't support STARTTLS");
+                }
+                command(&mut stream, "STARTTLS", 220).await?;
+
+                // Anything the server sent before the handshake is thrown away, and we start over
+                // with a greeting like the server had just greeted us
+                let mut stream = BufReader::new(self.tls(stream.into_inner()).await?);
+                command(&mut stream, "EHLO foodie", 250).await?;
+                self.deliver(&mut stream, mail).await
+            }
+            Security::None => {
+                let mut stream = BufReader::new(stream);
+                greet(&mut stream).await?;
+                self.deliver(&mut stream, mail).await
+            }
+        }
+    }
+}
+
+// Waits for the greeting of the server and greets it back. Returns the extensions it supports
+async fn greet<S>(stream: &mut BufReader<S>) -> Result<Vec<String>, anyhow::Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    reply(stream, 220).await?;
+    command(stream, "EHLO foodie", 250).await
+}
+
+// Headers can only contain ASCII, so anything else is sent as base64 encoded UTF-8
+fn encode_header(value: &str) -> String {
+    match value.is_ascii() {
+        true => value.to_string(),
+        false => format!("=?utf-8?b?{}?=", STANDARD.encode(value)),
+    }
+}
+
+async fn command<S>(
+    stream: &mut BufReader<S>,
+    line: &str,
+    code: u16,
+) -> Result<Vec<String>, anyhow::Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream.write_all(format!("{line}\r\n").as_bytes()).await?;
+    stream.flush().await?;
+    reply(stream, code).await
+}
+
+// Reads a reply from the server, which can span many lines, and checks that it has the code.
+// Returns the text of each line
+async fn reply<S>(stream: &mut BufReader<S>, code: u16) -> Result<Vec<String>, anyhow::Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut lines = vec![];
+    loop {
+        let mut line = String::new();
+        if stream.read_line(&mut line).await? == 0 {
+            bail!("SMTP server closed the connection");
+        }
+
+        if !line.starts_with(&code.to_string()) {
+            bail!("Unexpected reply from SMTP server: {}", line.trim_end());
+        }
+        lines.push(line.get(4..).unwrap_or_default().trim_end().to_string());
+
+        // The last line of a reply has a space after the code instead of a dash
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return Ok(lines);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{duplex, AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    use super::*;
+
+    fn mailer() -> SmtpMailer {
+        SmtpMailer {
+            host: "localhost".to_string(),
+            port: 25,
+            security: Security::Tls,
+            credentials: Some(("foo".to_string(), "bar".to_string())),
+            from: "foodie@example.com".to_string(),
+        }
+    }
+
+    fn mail(subject: &str, body: &str) -> Mail {
+        Mail {
+            to: "bar@bar.com".to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_message_dot_stuffing() {
+        let message = mailer().message(&mail("Hi", ".hidden\nline\n..two\n."));
+
+        assert!(message.contains("Subject: Hi\r\n"));
+        assert!(message.ends_with("\r\n\r\n..hidden\r\nline\r\n...two\r\n..\r\n."));
+    }
+
+    #[test]
+    fn test_message_encodes_subject() {
+        let message = mailer().message(&mail("Bli med på Foodie", "Hei"));
+
+        let encoded = STANDARD.encode("Bli med på Foodie");
+        assert!(message.contains(&format!("Subject: =?utf-8?b?{encoded}?=\r\n")));
+    }
+
+    #[tokio::test]
+    async fn test_reply_multiple_lines() {
+        let (client, mut server) = duplex(1024);
+        server
+            .write_all(b"250-foodie.example.com\r\n250-STARTTLS\r\n250 AUTH PLAIN\r\n")
+            .await
+            .unwrap();
+
+        let lines = reply(&mut BufReader::new(client), 250).await.unwrap();
+        assert_eq!(vec!["foodie.example.com", "STARTTLS", "AUTH PLAIN"], lines);
+    }
+
+    #[tokio::test]
+    async fn test_reply_errors() {
+        let (client, mut server) = duplex(1024);
+        let mut client = BufReader::new(client);
+        server
+            .write_all(b"250-foodie.example.com\r\n550 No such user\r\n")
+            .await
+            .unwrap();
+        assert!(reply(&mut client, 250).await.is_err());
+
+        drop(server);
+        assert!(reply(&mut client, 250).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_deliver() {
+        let (client, server) = duplex(4096);
+
+        // Replies to every command like a server would, and returns the commands it got
+        let server = tokio::spawn(async move {
+            let mut server = BufReader::new(server);
+            let mut commands = vec![];
+            loop {
+                let mut line = String::new();
+                if server.read_line(&mut line).await.unwrap() == 0 {
+                    return commands;
+                }
+
+                let reply = match line.split(' ').next().unwrap().trim_end() {
+                    "AUTH" => "235 Authenticated",
+                    "DATA" => "354 Go ahead",
+                    "QUIT" => "221 Bye",
+                    "MAIL" | "RCPT" | "." => "250 Ok",
+                    _ => {
+                        // Part of the message, which is only replied to after the last line
+                        commands.push(line);
+                        continue;
+                    }
+                };
+                commands.push(line);
+                server
+                    .write_all(format!("{reply}\r\n").as_bytes())
+                    .await
+                    .unwrap();
+            }
+        });
+
+        let mut client = BufReader::new(client);
+        mailer()
+            .deliver(&mut client, &mail("Hi", "Hello"))
+            .await
+            .unwrap();
+        drop(client);
+
+        let commands = server.await.unwrap();
+        let commands = commands.iter().map(|it| it.trim_end()).collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "AUTH PLAIN AGZvbwBiYXI=",
+                "MAIL FROM:<foodie@example.com>",
+                "RCPT TO:<bar@bar.com>",
+                "DATA",
+            ],
+            commands[..4]
+        );
+        assert_eq!(vec!["Hello", ".", "QUIT"], commands[commands.len() - 3..]);
+    }
+}
@@ -0,0 +1,378 @@
+use common::{
+    recipe::{format_yield, group_ingredients, Recipe, RecipeIngredient, Unit},
+    shopping_list::ShoppingList,
+};
+use image::{codecs::jpeg::JpegEncoder, DynamicImage};
+use printpdf::{
+    path::PaintMode, BuiltinFont, Color, ColorBits, ColorSpace, Image, ImageFilter, ImageTransform,
+    ImageXObject, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Pt,
+    Px, Rect, Rgb,
+};
+use rust_decimal::Decimal;
+
+// A4 in millimeters
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+const MAX_IMAGE_HEIGHT: f32 = 100.0;
+const JPEG_QUALITY: u8 = 85;
+
+// The average width of a character in Helvetica relative to the font size. The builtin fonts
+// don't come with their metrics, so lines are wrapped from this instead of measuring the text
+const AVERAGE_CHAR_WIDTH: f32 = 0.5;
+
+/// Renders a recipe for printing, with the image, the ingredients grouped like on the recipe page
+/// and numbered steps. Scale the ingredients with `scale_recipe` before rendering to print it for
+/// another number of servings
+pub fn recipe_pdf(recipe: &Recipe, image: Option<&DynamicImage>) -> Result<Vec<u8>, anyhow::Error> {
+    let mut writer = PdfWriter::new(&recipe.name)?;
+
+    writer.text(&recipe.name, 22.0, Style::Bold, 0.0);
+
+    let mut facts = vec![format_yield(recipe.servings, recipe.yield_unit.as_deref())];
+    if let Some(total_time) = recipe.total_time {
+        facts.push(total_time.to_string());
+    }
+    facts.extend(recipe.cuisine.clone());
+    facts.extend(recipe.difficulty.map(|it| it.to_string()));
+    writer.text(&facts.join(" · "), 10.0, Style::Muted, 0.0);
+
+    if let Some(description) = &recipe.description {
+        writer.space(2.0);
+        writer.text(description, 11.0, Style::Regular, 0.0);
+    }
+
+    if let Some(image) = image {
+        writer.space(4.0);
+        writer.image(image)?;
+    }
+
+    writer.space(6.0);
+    writer.text("Ingredients", 16.0, Style::Bold, 0.0);
+    for (group, ingredients) in group_ingredients(&recipe.ingredients) {
+        if let Some(group) = group {
+            writer.space(2.0);
+            writer.text(&group, 12.0, Style::Bold, 0.0);
+        }
+        for ingredient in ingredients {
+            writer.text(
+                &format!("• {}", ingredient_line(&ingredient)),
+                11.0,
+                Style::Regular,
+                2.0,
+            );
+        }
+    }
+
+    if let Some(instructions) = recipe.instructions.as_ref().filter(|it| !it.is_empty()) {
+        writer.space(6.0);
+        writer.text("Steps", 16.0, Style::Bold, 0.0);
+        for (idx, step) in instructions.iter().enumerate() {
+            writer.space(1.0);
+            writer.numbered(idx + 1, step, 11.0);
+        }
+    }
+
+    let source = match (&recipe.source_name, &recipe.source_url) {
+        (Some(name), Some(url)) => Some(format!("Source: {name} ({url})")),
+        (Some(it), None) | (None, Some(it)) => Some(format!("Source: {it}")),
+        (None, None) => None,
+    };
+    if let Some(source) = source {
+        writer.space(6.0);
+        writer.text(&source, 9.0, Style::Muted, 0.0);
+    }
+
+    writer.finish()
+}
+
+/// Renders a shopping list with a box to tick off for every item, grouped by category
+pub fn shopping_list_pdf(shopping_list: &ShoppingList) -> Result<Vec<u8>, anyhow::Error> {
+    let mut writer = PdfWriter::new("Shopping list")?;
+
+    writer.text("Shopping list", 22.0, Style::Bold, 0.0);
+    if shopping_list.sections.is_empty() {
+        writer.text("Nothing to buy", 11.0, Style::Muted, 0.0);
+    }
+
+    for section in &shopping_list.sections {
+        writer.space(4.0);
+        writer.text(&section.category.to_string(), 14.0, Style::Bold, 0.0);
+        for item in &section.items {
+            let line = [
+                format_amount(item.amount),
+                format_unit(item.unit),
+                item.name.clone(),
+            ]
+            .into_iter()
+            .filter(|it| !it.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+            writer.checkbox(&line, 11.0);
+        }
+    }
+
+    writer.finish()
+}
+
+/// Scales the ingredients of a recipe from the servings it is written for to `servings`
+pub fn scale_recipe(recipe: Recipe, servings: i32) -> Recipe {
+    if servings == recipe.servings || recipe.servings <= 0 {
+        return recipe;
+    }
+
+    let ingredients = recipe
+        .ingredients
+        .into_iter()
+        .map(|ingredient| RecipeIngredient {
+            amount: ingredient.amount.map(|amount| {
+                (amount * Decimal::from(servings) / Decimal::from(recipe.servings))
+                    .round_dp(2)
+                    .normalize()
+            }),
+            ..ingredient
+        })
+        .collect();
+
+    Recipe {
+        servings,
+        ingredients,
+        ..recipe
+    }
+}
+
+fn ingredient_line(ingredient: &RecipeIngredient) -> String {
+    let mut line = [
+        format_amount(ingredient.amount),
+        format_unit(ingredient.unit),
+        ingredient.ingredient_name.clone(),
+    ]
+    .into_iter()
+    .filter(|it| !it.is_empty())
+    .collect::<Vec<_>>()
+    .join(" ");
+
+    if let Some(note) = &ingredient.note {
+        line.push_str(&format!(", {note}"));
+    }
+    if ingredient.optional {
+        line.push_str(" (optional)");
+    }
+    line
+}
+
+fn format_amount(amount: Option<Decimal>) -> String {
+    amount
+        .map(|it| it.normalize().to_string())
+        .unwrap_or_default()
+}
+
+fn format_unit(unit: Option<Unit>) -> String {
+    unit.map(|it| it.to_string()).unwrap_or_default()
+}
+
+#[derive(Clone, Copy)]
+enum Style {
+    Regular,
+    Bold,
+    Muted,
+}
+
+// Lays out content from the top of the page and down, and starts a new page when it is full
+struct PdfWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    // Distance from the bottom of the page to the top of the next line
+    y: f32,
+}
+
+impl PdfWriter {
+    fn new(title: &str) -> Result<Self, anyhow::Error> {
+        let (doc, page, layer) =
+            PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Content");
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        Ok(Self {
+            doc,
+            layer,
+            regular,
+            bold,
+            y: PAGE_HEIGHT - MARGIN,
+        })
+    }
+
+    fn finish(self) -> Result<Vec<u8>, anyhow::Error> {
+        Ok(self.doc.save_to_bytes()?)
+    }
+
+    fn space(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    // Makes sure there is room for `height` more on the page, and starts a new one if there isn't
+    fn reserve(&mut self, height: f32) {
+        if self.y - height >= MARGIN {
+            return;
+        }
+        let (page, layer) = self
+            .doc
+            .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Content");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn text(&mut self, text: &str, size: f32, style: Style, indent: f32) {
+        for paragraph in text.lines() {
+            for line in wrap(paragraph, size, CONTENT_WIDTH - indent) {
+                self.line(&line, size, style, MARGIN + indent);
+            }
+        }
+    }
+
+    fn numbered(&mut self, number: usize, text: &str, size: f32) {
+        let indent = 8.0;
+        let lines = wrap(text, size, CONTENT_WIDTH - indent);
+        for (idx, line) in lines.iter().enumerate() {
+            if idx == 0 {
+                self.reserve(line_height(size));
+                self.set_color(Style::Bold);
+                self.layer.use_text(
+                    format!("{number}."),
+                    size,
+                    Mm(MARGIN),
+                    Mm(self.y - size_in_mm(size)),
+                    &self.bold,
+                );
+            }
+            self.line(line, size, Style::Regular, MARGIN + indent);
+        }
+    }
+
+    fn checkbox(&mut self, text: &str, size: f32) {
+        let indent = 7.0;
+        let lines = wrap(text, size, CONTENT_WIDTH - indent);
+        for (idx, line) in lines.iter().enumerate() {
+            if idx == 0 {
+                self.reserve(line_height(size));
+                let side = size_in_mm(size) * 0.8;
+                let bottom = self.y - size_in_mm(size);
+                self.set_color(Style::Regular);
+                self.layer.set_outline_thickness(0.5);
+                self.layer.add_rect(
+                    Rect::new(Mm(MARGIN), Mm(bottom), Mm(MARGIN + side), Mm(bottom + side))
+                        .with_mode(PaintMode::Stroke),
+                );
+            }
+            self.line(line, size, Style::Regular, MARGIN + indent);
+        }
+    }
+
+    fn line(&mut self, text: &str, size: f32, style: Style, x: f32) {
+        self.reserve(line_height(size));
+        self.set_color(style);
+        let font = match style {
+            Style::Bold => &self.bold,
+            Style::Regular | Style::Muted => &self.regular,
+        };
+        self.layer
+            .use_text(text, size, Mm(x), Mm(self.y - size_in_mm(size)), font);
+        self.y -= line_height(size);
+    }
+
+    // Fits the image to the width of the page, and scales it down further if it gets too tall
+    fn image(&mut self, image: &DynamicImage) -> Result<(), anyhow::Error> {
+        let (width, height) = (image.width(), image.height());
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+
+        let mm_per_px = (CONTENT_WIDTH / width as f32).min(MAX_IMAGE_HEIGHT / height as f32);
+        let image_height = height as f32 * mm_per_px;
+        self.reserve(image_height);
+
+        // Photos are embedded as JPEG, since raw pixels would make the PDF several megabytes. This
+        // also flattens any transparency, which printpdf doesn't support anyway
+        let mut jpeg = vec![];
+        JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY).encode_image(&image.to_rgb8())?;
+
+        let image = Image::from(ImageXObject {
+            width: Px(width as usize),
+            height: Px(height as usize),
+            color_space: ColorSpace::Rgb,
+            bits_per_component: ColorBits::Bit8,
+            interpolate: true,
+            image_data: jpeg,
+            image_filter: Some(ImageFilter::DCT),
+            smask: None,
+            clipping_bbox: None,
+        });
+
+        // An image is placed by its size in pixels and its dpi, so the dpi decides how big it is
+        image.add_to_layer(
+            self.layer.clone(),
+            ImageTransform {
+                translate_x: Some(Mm(MARGIN + (CONTENT_WIDTH - width as f32 * mm_per_px) / 2.0)),
+                translate_y: Some(Mm(self.y - image_height)),
+                dpi: Some(25.4 / mm_per_px),
+                ..Default::default()
+            },
+        );
+        self.y -= image_height;
+
+        Ok(())
+    }
+
+    fn set_color(&self, style: Style) {
+        let gray = match style {
+            Style::Regular | Style::Bold => 0.0,
+            Style::Muted => 0.4,
+        };
+        let color = Color::Rgb(Rgb::new(gray, gray, gray, None));
+        self.layer.set_fill_color(color.clone());
+        self.layer.set_outline_color(color);
+    }
+}
+
+fn size_in_mm(size: f32) -> f32 {
+    Mm::from(Pt(size)).0
+}
+
+fn line_height(size: f32) -> f32 {
+    size_in_mm(size) * 1.4
+}
+
+// Breaks the text into lines at spaces. Words that are longer than a line are split
+fn wrap(text: &str, size: f32, width: f32) -> Vec<String> {
+    let max_chars = ((width / (size_in_mm(size) * AVERAGE_CHAR_WIDTH)) as usize).max(1);
+
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word = word.chars().collect::<Vec<_>>();
+        while word.len() > max_chars {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            lines.push(word.drain(..max_chars).collect());
+        }
+        let word = word.into_iter().collect::<String>();
+
+        if line.is_empty() {
+            line = word;
+        } else if line.chars().count() + 1 + word.chars().count() <= max_chars {
+            line.push(' ');
+            line.push_str(&word);
+        } else {
+            lines.push(std::mem::replace(&mut line, word));
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
@@ -1,6 +1,7 @@
 use std::fmt::Debug;
 
 use async_trait::async_trait;
+use axum_login::tower_sessions::MemoryStore;
 use redis::{aio::MultiplexedConnection, AsyncCommands, ExistenceCheck, SetExpiry};
 use time::OffsetDateTime;
 use tower_sessions_core::{
@@ -8,6 +9,15 @@ use tower_sessions_core::{
     session_store, SessionStore,
 };
 
+// Where axum-login keeps the id of the logged in user in the session
+const AUTH_DATA_KEY: &str = "axum-login.data";
+
+/// A session store that knows which sessions belongs to a user, so they can be ended all at once
+#[async_trait]
+pub trait UserSessionStore {
+    async fn delete_user_sessions(&self, user_id: i32) -> Result<(), anyhow::Error>;
+}
+
 /// A Redis session store.
 #[derive(Debug, Clone)]
 pub struct RedisStore {
@@ -44,8 +54,56 @@ impl RedisStore {
             .await
             .unwrap();
 
+        if let Some(user_id) = session_user_id(record) {
+            self.add_user_session(user_id, record).await;
+        }
+
         Ok(res)
     }
+
+    // Keeps a set of the sessions of every user. Sessions that have expired are left in the set, as
+    // deleting them again when the user's sessions are deleted does nothing
+    async fn add_user_session(&self, user_id: i64, record: &Record) {
+        let _: () = self
+            .conn
+            .clone()
+            .sadd(user_sessions_key(user_id), record.id.to_string())
+            .await
+            .unwrap();
+    }
+}
+
+fn session_user_id(record: &Record) -> Option<i64> {
+    record.data.get(AUTH_DATA_KEY)?.get("user_id")?.as_i64()
+}
+
+fn user_sessions_key(user_id: i64) -> String {
+    format!("user-sessions:{user_id}")
+}
+
+#[async_trait]
+impl UserSessionStore for RedisStore {
+    async fn delete_user_sessions(&self, user_id: i32) -> Result<(), anyhow::Error> {
+        let key = user_sessions_key(user_id.into());
+        let mut conn = self.conn.clone();
+
+        let sessions: Vec<String> = conn.smembers(&key).await?;
+        if !sessions.is_empty() {
+            let _: () = conn.del(sessions).await?;
+        }
+        let _: () = conn.del(key).await?;
+
+        Ok(())
+    }
+}
+
+// The memory store can't list its sessions. The sessions of a deleted user are still logged out,
+// since the user can't be found when the session is loaded
+#[async_trait]
+impl UserSessionStore for MemoryStore {
+    async fn delete_user_sessions(&self, _user_id: i32) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
 }
 
 #[async_trait]
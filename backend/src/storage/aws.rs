@@ -1,7 +1,9 @@
 use std::time::Duration;
 
 use aws_config::BehaviorVersion;
-use aws_sdk_s3::{config::Credentials, presigning::PresigningConfig, Client as S3Client};
+use aws_sdk_s3::{
+    config::Credentials, presigning::PresigningConfig, primitives::ByteStream, Client as S3Client,
+};
 use hyper::Method;
 
 use super::FoodieStorage;
@@ -84,4 +86,26 @@ impl FoodieStorage for FoodieAws {
             .await?;
         Ok(())
     }
+
+    async fn get(&self, file: Uuid) -> Result<Vec<u8>, anyhow::Error> {
+        let object = self
+            .client
+            .get_object()
+            .bucket("aws")
+            .key(file)
+            .send()
+            .await?;
+        Ok(object.body.collect().await?.to_vec())
+    }
+
+    async fn put(&self, file: Uuid, body: Vec<u8>) -> Result<(), anyhow::Error> {
+        self.client
+            .put_object()
+            .bucket("aws")
+            .key(file)
+            .body(ByteStream::from(body))
+            .send()
+            .await?;
+        Ok(())
+    }
 }
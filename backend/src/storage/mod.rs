@@ -9,6 +9,10 @@ pub trait FoodieStorage {
 
     async fn delete(&self, file: Uuid) -> Result<(), anyhow::Error>;
 
+    async fn get(&self, file: Uuid) -> Result<Vec<u8>, anyhow::Error>;
+
+    async fn put(&self, file: Uuid, body: Vec<u8>) -> Result<(), anyhow::Error>;
+
     // async fn save_file<T, U>(&self, file: &str, body: T) -> Result<(), anyhow::Error>
     // where
     //     T: Stream<Item = Result<Bytes, U>> + Send + Unpin,
@@ -0,0 +1,195 @@
+use backend::entities::{friendships, sea_orm_active_enums::FriendshipStatus};
+use common::{
+    collection::{Collection, CreateCollection, RecipeBookmarks},
+    recipe::{CreateRecipe, Recipe, RecipeVisibility},
+    user::{CreateUser, UserLogin},
+};
+use reqwest::StatusCode;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+use sqlx::PgPool;
+
+use crate::{ingredient::recipe_with_ingredients, TestApp};
+
+#[sqlx::test(migrations = false)]
+async fn test_favorites(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let pizza = app
+        .create_recipe(&CreateRecipe {
+            name: "Pizza".to_string(),
+            ..recipe_with_ingredients(&["Flour"])
+        })
+        .await?;
+    let toast = app
+        .create_recipe(&CreateRecipe {
+            name: "Toast".to_string(),
+            ..recipe_with_ingredients(&["Flour"])
+        })
+        .await?;
+
+    // Adding a favorite twice is fine
+    app.put(format!("/api/recipes/{}/favorite", pizza.id), &())
+        .await?;
+    let bookmarks = app
+        .put(format!("/api/recipes/{}/favorite", pizza.id), &())
+        .await?
+        .json::<RecipeBookmarks>()
+        .await?;
+    assert!(bookmarks.favorite);
+
+    app.put(format!("/api/recipes/{}/favorite", toast.id), &())
+        .await?;
+
+    let favorites = app
+        .get("/api/favorites")
+        .await?
+        .json::<Vec<Recipe>>()
+        .await?;
+    assert_eq!(
+        vec!["Toast", "Pizza"],
+        favorites
+            .iter()
+            .map(|it| it.name.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    let bookmarks = app
+        .delete(format!("/api/recipes/{}/favorite", pizza.id))
+        .await?
+        .json::<RecipeBookmarks>()
+        .await?;
+    assert!(!bookmarks.favorite);
+
+    let response = app.put("/api/recipes/9999/favorite", &()).await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_collections(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let pizza = app
+        .create_recipe(&CreateRecipe {
+            name: "Pizza".to_string(),
+            ..recipe_with_ingredients(&["Flour"])
+        })
+        .await?;
+
+    let weeknight = app
+        .post(
+            "/api/collections",
+            Some(&CreateCollection {
+                name: " Weeknight ".to_string(),
+                description: None,
+                visibility: RecipeVisibility::Friends,
+            }),
+        )
+        .await?
+        .json::<Collection>()
+        .await?;
+    assert_eq!("Weeknight", weeknight.name);
+
+    let secret = app
+        .post(
+            "/api/collections",
+            Some(&CreateCollection {
+                name: "Secret".to_string(),
+                description: None,
+                visibility: RecipeVisibility::Private,
+            }),
+        )
+        .await?
+        .json::<Collection>()
+        .await?;
+
+    let duplicate = app
+        .post(
+            "/api/collections",
+            Some(&CreateCollection {
+                name: "Weeknight".to_string(),
+                ..Default::default()
+            }),
+        )
+        .await?;
+    assert_eq!(StatusCode::CONFLICT, duplicate.status());
+
+    let empty = app
+        .post("/api/collections", Some(&CreateCollection::default()))
+        .await?;
+    assert_eq!(StatusCode::BAD_REQUEST, empty.status());
+
+    for collection in [&weeknight, &secret] {
+        app.put(
+            format!("/api/collections/{}/recipes/{}", collection.id, pizza.id),
+            &(),
+        )
+        .await?;
+    }
+
+    let bookmarks = app
+        .get(format!("/api/recipes/{}/bookmarks", pizza.id))
+        .await?
+        .json::<RecipeBookmarks>()
+        .await?;
+    assert_eq!(vec![weeknight.id, secret.id], bookmarks.collection_ids);
+
+    let friend = app
+        .create_user(&CreateUser {
+            name: "bar".to_string(),
+            email: "bar@bar.com".to_string(),
+            password: "foo".to_string(),
+        })
+        .await?;
+
+    friendships::Entity::insert(friendships::ActiveModel {
+        status: Set(FriendshipStatus::Accepted),
+        requester_id: Set(app.user.id),
+        recipient_id: Set(friend.id),
+        ..Default::default()
+    })
+    .exec(&app.pool)
+    .await?;
+
+    app.login(&UserLogin {
+        email: "bar@bar.com".to_string(),
+        password: "foo".to_string(),
+    })
+    .await;
+
+    // Friends only see the shared collection, and can't change it
+    let collections = app
+        .get("/api/collections")
+        .await?
+        .json::<Vec<Collection>>()
+        .await?;
+    assert_eq!(
+        vec![(weeknight.id, vec![pizza.id])],
+        collections
+            .into_iter()
+            .map(|it| (it.id, it.recipe_ids))
+            .collect::<Vec<_>>()
+    );
+
+    let recipes = app
+        .get(format!("/api/collections/{}/recipes", weeknight.id))
+        .await?
+        .json::<Vec<Recipe>>()
+        .await?;
+    assert_eq!(
+        vec![pizza.id],
+        recipes.iter().map(|it| it.id).collect::<Vec<_>>()
+    );
+
+    let response = app.get(format!("/api/collections/{}", secret.id)).await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    let response = app
+        .delete(format!(
+            "/api/collections/{}/recipes/{}",
+            weeknight.id, pizza.id
+        ))
+        .await?;
+    assert_eq!(StatusCode::FORBIDDEN, response.status());
+
+    Ok(())
+}
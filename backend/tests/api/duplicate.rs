@@ -0,0 +1,211 @@
+use backend::entities::{friendships, sea_orm_active_enums::FriendshipStatus};
+use chrono::{TimeDelta, Utc};
+use common::{
+    meal_plan::{CreatePlannedMeal, PlannedMeal},
+    recipe::{CreateRecipe, DuplicateRecipes, MergeRecipe, Recipe, RecipeVisibility},
+    user::{CreateUser, UserLogin},
+};
+use reqwest::StatusCode;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+use sqlx::PgPool;
+
+use crate::{ingredient::recipe_with_ingredients, TestApp, TEST_EMAIL, TEST_PASSWORD};
+
+fn taco(name: &str, ingredients: &[&str]) -> CreateRecipe {
+    CreateRecipe {
+        name: name.to_string(),
+        ..recipe_with_ingredients(ingredients)
+    }
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_duplicate_recipes(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let ingredients = ["Tortilla", "Minced meat", "Salsa", "Cheese"];
+
+    let recipe = app
+        .post("/api/recipes", Some(&taco("Taco fredag", &ingredients)))
+        .await?
+        .json::<Recipe>()
+        .await?;
+
+    let response = app
+        .post("/api/recipes", Some(&taco("Tacofredag", &ingredients)))
+        .await?;
+    assert_eq!(StatusCode::CONFLICT, response.status());
+    let duplicates = response.json::<DuplicateRecipes>().await?;
+    assert_eq!(
+        vec![recipe.id],
+        duplicates
+            .candidates
+            .iter()
+            .map(|it| it.id)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(1.0, duplicates.candidates[0].ingredient_overlap);
+
+    // A similar name is not enough when the ingredients are different
+    let response = app
+        .post(
+            "/api/recipes",
+            Some(&taco("Taco salad", &["Lettuce", "Corn"])),
+        )
+        .await?;
+    assert_eq!(StatusCode::OK, response.status());
+
+    let response = app
+        .post(
+            "/api/recipes?allow_duplicate=true",
+            Some(&taco("Taco fredag", &ingredients)),
+        )
+        .await?;
+    assert_eq!(StatusCode::OK, response.status());
+    let duplicate = response.json::<Recipe>().await?;
+
+    app.put(format!("/api/recipes/{}/favorite", duplicate.id), &())
+        .await?;
+
+    let merged = app
+        .post(
+            format!("/api/recipes/{}/merge", duplicate.id),
+            Some(&MergeRecipe { into_id: recipe.id }),
+        )
+        .await?
+        .json::<Recipe>()
+        .await?;
+    assert_eq!(recipe.id, merged.id);
+
+    let response = app.get(format!("/api/recipes/{}", duplicate.id)).await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    // The favorite is moved to the kept recipe
+    let favorites = app
+        .get("/api/favorites")
+        .await?
+        .json::<Vec<Recipe>>()
+        .await?;
+    assert_eq!(
+        vec![recipe.id],
+        favorites.iter().map(|it| it.id).collect::<Vec<_>>()
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_merge_recipe_into_itself(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    let recipe = app
+        .post("/api/recipes", Some(&taco("Taco fredag", &["Tortilla"])))
+        .await?
+        .json::<Recipe>()
+        .await?;
+
+    let response = app
+        .post(
+            format!("/api/recipes/{}/merge", recipe.id),
+            Some(&MergeRecipe { into_id: recipe.id }),
+        )
+        .await?;
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    let response = app
+        .post(
+            "/api/recipes/9999/merge",
+            Some(&MergeRecipe { into_id: recipe.id }),
+        )
+        .await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_merge_into_recipe_friends_cant_see(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let ingredients = ["Tortilla", "Minced meat", "Salsa", "Cheese"];
+
+    let shared = app
+        .create_recipe(&taco("Taco fredag", &ingredients))
+        .await?;
+    let private = app
+        .post(
+            "/api/recipes?allow_duplicate=true",
+            Some(&CreateRecipe {
+                visibility: RecipeVisibility::Private,
+                ..taco("Tacofredag", &ingredients)
+            }),
+        )
+        .await?
+        .json::<Recipe>()
+        .await?;
+
+    let bar = app
+        .create_user(&CreateUser {
+            name: "bar".to_string(),
+            email: "bar@bar.com".to_string(),
+            password: "foo".to_string(),
+        })
+        .await?;
+    friendships::Entity::insert(friendships::ActiveModel {
+        requester_id: Set(app.user.id),
+        recipient_id: Set(bar.id),
+        status: Set(FriendshipStatus::Accepted),
+        ..Default::default()
+    })
+    .exec(&app.pool)
+    .await?;
+
+    app.login(&UserLogin {
+        email: "bar@bar.com".to_string(),
+        password: "foo".to_string(),
+    })
+    .await;
+    app.put(format!("/api/recipes/{}/favorite", shared.id), &())
+        .await?;
+    app.post(
+        "/api/meal-plan",
+        Some(&CreatePlannedMeal {
+            recipe_id: shared.id,
+            eat_at: (Utc::now() + TimeDelta::days(1)).fixed_offset(),
+        }),
+    )
+    .await?;
+
+    app.login(&UserLogin {
+        email: TEST_EMAIL.to_string(),
+        password: TEST_PASSWORD.to_string(),
+    })
+    .await;
+    let response = app
+        .post(
+            format!("/api/recipes/{}/merge", shared.id),
+            Some(&MergeRecipe {
+                into_id: private.id,
+            }),
+        )
+        .await?;
+    assert_eq!(StatusCode::OK, response.status());
+
+    // The friend can't see the kept recipe, so their favorite and planned meal are gone
+    app.login(&UserLogin {
+        email: "bar@bar.com".to_string(),
+        password: "foo".to_string(),
+    })
+    .await;
+    let favorites = app
+        .get("/api/favorites")
+        .await?
+        .json::<Vec<Recipe>>()
+        .await?;
+    assert!(favorites.is_empty());
+    let meals = app
+        .get("/api/meal-plan")
+        .await?
+        .json::<Vec<PlannedMeal>>()
+        .await?;
+    assert!(meals.is_empty());
+
+    Ok(())
+}
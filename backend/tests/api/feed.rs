@@ -0,0 +1,172 @@
+use backend::entities::recipe_cooks;
+use common::{
+    feed::{CookRecipe, FeedItem, FeedKind},
+    recipe::{CreateRecipe, Recipe, RecipeVisibility},
+    user::UserLogin,
+};
+use reqwest::StatusCode;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+use sqlx::PgPool;
+
+use crate::{ingredient::recipe_with_ingredients, TestApp, TEST_EMAIL, TEST_PASSWORD};
+
+fn recipe(name: &str, visibility: RecipeVisibility) -> CreateRecipe {
+    CreateRecipe {
+        name: name.to_string(),
+        visibility,
+        ..recipe_with_ingredients(&["Flour"])
+    }
+}
+
+async fn feed(app: &TestApp, query: &[(&str, String)]) -> Result<Vec<FeedItem>, anyhow::Error> {
+    Ok(app
+        .client
+        .get(format!("{}/api/feed", app.address))
+        .query(query)
+        .send()
+        .await?
+        .json::<Vec<FeedItem>>()
+        .await?)
+}
+
+fn kinds(feed: &[FeedItem]) -> Vec<(FeedKind, &str)> {
+    feed.iter()
+        .map(|it| (it.kind, it.recipe_name.as_str()))
+        .collect()
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_feed(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let foo = app.user.id;
+
+    // The user's own activity is not in their feed
+    app.post(
+        "/api/recipes",
+        Some(&recipe("Soup", RecipeVisibility::Friends)),
+    )
+    .await?;
+
+    app.create_user_named("bar").await?;
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+    app.post::<(), _>(format!("/api/friends/new/{foo}"), None)
+        .await?;
+
+    let pizza = app
+        .post(
+            "/api/recipes",
+            Some(&recipe("Pizza", RecipeVisibility::Friends)),
+        )
+        .await?
+        .json::<Recipe>()
+        .await?;
+    let secret = app
+        .post(
+            "/api/recipes",
+            Some(&recipe("Secret", RecipeVisibility::Private)),
+        )
+        .await?
+        .json::<Recipe>()
+        .await?;
+
+    app.put(
+        format!("/api/recipes/{}", pizza.id),
+        &recipe("Pizza", RecipeVisibility::Friends),
+    )
+    .await?;
+
+    for (recipe_id, rating) in [(pizza.id, Some(5)), (secret.id, None)] {
+        let res = app
+            .post(
+                format!("/api/recipes/{recipe_id}/cooked"),
+                Some(&CookRecipe { rating }),
+            )
+            .await?;
+        assert_eq!(StatusCode::CREATED, res.status());
+    }
+
+    let res = app
+        .post(
+            format!("/api/recipes/{}/cooked", pizza.id),
+            Some(&CookRecipe { rating: Some(6) }),
+        )
+        .await?;
+    assert_eq!(StatusCode::BAD_REQUEST, res.status());
+
+    app.login(&UserLogin {
+        email: TEST_EMAIL.to_string(),
+        password: TEST_PASSWORD.to_string(),
+    })
+    .await;
+
+    // Nothing until they are friends
+    assert!(feed(&app, &[]).await?.is_empty());
+
+    let bar = pizza.user_id;
+    app.post::<(), _>(format!("/api/friends/accept/{bar}"), None)
+        .await?;
+
+    // The private recipe is not in the feed, even though it was cooked
+    let items = feed(&app, &[]).await?;
+    assert_eq!(
+        vec![
+            (FeedKind::RecipeCooked, "Pizza"),
+            (FeedKind::RecipeUpdated, "Pizza"),
+            (FeedKind::RecipeCreated, "Pizza"),
+        ],
+        kinds(&items)
+    );
+    assert_eq!(Some(5), items[0].rating);
+    assert_eq!("bar", items[0].user_name);
+
+    let res = app
+        .post(
+            format!("/api/recipes/{}/cooked", secret.id),
+            Some(&CookRecipe::default()),
+        )
+        .await?;
+    assert_eq!(StatusCode::NOT_FOUND, res.status());
+
+    // The next page is everything before the last item
+    let first = feed(&app, &[("limit", "2".to_string())]).await?;
+    assert_eq!(2, first.len());
+    let second = feed(&app, &page_after(&first[1], 2)).await?;
+    assert_eq!(vec![(FeedKind::RecipeCreated, "Pizza")], kinds(&second));
+
+    // Activity at the same time as the end of a page is not skipped
+    let created_at = items[2].at;
+    for _ in 0..3 {
+        recipe_cooks::Entity::insert(recipe_cooks::ActiveModel {
+            user_id: Set(pizza.user_id),
+            recipe_id: Set(pizza.id),
+            cooked_at: Set(created_at),
+            ..Default::default()
+        })
+        .exec(&app.pool)
+        .await?;
+    }
+
+    let all = feed(&app, &[]).await?;
+    assert_eq!(6, all.len());
+
+    let mut pages = feed(&app, &[("limit", "2".to_string())]).await?;
+    loop {
+        let page = feed(&app, &page_after(pages.last().unwrap(), 2)).await?;
+        if page.is_empty() {
+            break;
+        }
+        pages.extend(page);
+    }
+    assert_eq!(all, pages);
+
+    Ok(())
+}
+
+fn page_after(last: &FeedItem, limit: u64) -> Vec<(&'static str, String)> {
+    vec![
+        ("limit", limit.to_string()),
+        ("before", last.at.to_rfc3339()),
+        ("before_kind", format!("{:?}", last.kind)),
+        ("before_id", last.id.to_string()),
+    ]
+}
@@ -1,10 +1,19 @@
 use backend::entities::{friendships, sea_orm_active_enums::FriendshipStatus};
-use common::user::{CreateUser, UserLogin, UserWithRelation};
+use common::{
+    friendship::FriendSuggestion,
+    recipe::{CreateRecipe, Recipe, RecipeVisibility},
+    user::{CreateUser, UserLogin, UserWithRelation},
+    websocket::FoodieMessageType,
+};
 use hyper::StatusCode;
-use sea_orm::{EntityTrait, PaginatorTrait};
+use sea_orm::{sea_query::Expr, EntityTrait, PaginatorTrait, Set};
 use sqlx::PgPool;
 
-use crate::TestApp;
+use crate::{
+    ingredient::{create_global_ingredient, recipe_with_ingredients},
+    voting::next_message,
+    TestApp, TEST_EMAIL, TEST_PASSWORD,
+};
 
 #[sqlx::test(migrations = false)]
 async fn test_send_friendrequest(pool: PgPool) -> Result<(), anyhow::Error> {
@@ -149,3 +158,477 @@ async fn test_friendrequest_get_pending_requests(pool: PgPool) -> Result<(), any
 
     Ok(())
 }
+
+async fn find_friendship(app: &TestApp) -> Result<Option<friendships::Model>, anyhow::Error> {
+    Ok(friendships::Entity::find().one(&app.pool).await?)
+}
+
+async fn search_users(app: &TestApp) -> Result<Vec<UserWithRelation>, anyhow::Error> {
+    Ok(app
+        .get("/api/users?search=")
+        .await?
+        .json::<Vec<UserWithRelation>>()
+        .await?)
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_friendrequest_again_after_rejection(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let other = app.create_user_named("bar").await?;
+
+    app.post::<(), _>(format!("/api/friends/new/{}", other.id), None)
+        .await?;
+
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+    let res = app
+        .post::<(), _>(format!("/api/friends/reject/{}", app.user.id), None)
+        .await?;
+    assert_eq!(StatusCode::OK, res.status());
+
+    let friendship = find_friendship(&app).await?.unwrap();
+    assert_eq!(FriendshipStatus::Rejected, friendship.status);
+    assert!(friendship.responded_at.is_some());
+
+    // The requester has to wait before they can ask again
+    app.login_as(TEST_EMAIL, TEST_PASSWORD).await;
+    let res = app
+        .post::<(), _>(format!("/api/friends/new/{}", other.id), None)
+        .await?;
+    assert_eq!(StatusCode::TOO_MANY_REQUESTS, res.status());
+
+    friendships::Entity::update_many()
+        .col_expr(
+            friendships::Column::RespondedAt,
+            Expr::value(chrono::Utc::now() - chrono::Duration::days(8)),
+        )
+        .exec(&app.pool)
+        .await?;
+
+    let res = app
+        .post::<(), _>(format!("/api/friends/new/{}", other.id), None)
+        .await?;
+    assert_eq!(StatusCode::OK, res.status());
+
+    let friendship = find_friendship(&app).await?.unwrap();
+    assert_eq!(FriendshipStatus::Pending, friendship.status);
+    assert_eq!(None, friendship.responded_at);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_friendrequest_rejecter_changes_their_mind(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let other = app.create_user_named("bar").await?;
+
+    app.post::<(), _>(format!("/api/friends/new/{}", other.id), None)
+        .await?;
+
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+    app.post::<(), _>(format!("/api/friends/reject/{}", app.user.id), None)
+        .await?;
+
+    // The one who rejected can accept it later
+    let res = app
+        .post::<(), _>(format!("/api/friends/accept/{}", app.user.id), None)
+        .await?;
+    assert_eq!(StatusCode::OK, res.status());
+    assert_eq!(
+        FriendshipStatus::Accepted,
+        find_friendship(&app).await?.unwrap().status
+    );
+
+    // An accepted friendship can't be rejected
+    let res = app
+        .post::<(), _>(format!("/api/friends/reject/{}", app.user.id), None)
+        .await?;
+    assert_eq!(StatusCode::BAD_REQUEST, res.status());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_friendrequest_by_rejecter(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let other = app.create_user_named("bar").await?;
+
+    app.post::<(), _>(format!("/api/friends/new/{}", other.id), None)
+        .await?;
+
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+    app.post::<(), _>(format!("/api/friends/reject/{}", app.user.id), None)
+        .await?;
+
+    // The one who rejected can send a request without waiting, which makes them the requester
+    let res = app
+        .post::<(), _>(format!("/api/friends/new/{}", app.user.id), None)
+        .await?;
+    assert_eq!(StatusCode::OK, res.status());
+
+    let friendship = find_friendship(&app).await?.unwrap();
+    assert_eq!(FriendshipStatus::Pending, friendship.status);
+    assert_eq!(other.id, friendship.requester_id);
+    assert_eq!(app.user.id, friendship.recipient_id);
+
+    app.login_as(TEST_EMAIL, TEST_PASSWORD).await;
+    let res = app
+        .post::<(), _>(format!("/api/friends/accept/{}", other.id), None)
+        .await?;
+    assert_eq!(StatusCode::OK, res.status());
+    assert_eq!(
+        FriendshipStatus::Accepted,
+        find_friendship(&app).await?.unwrap().status
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_block_and_unblock_friend(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let other = app.create_user_named("bar").await?;
+
+    app.post(
+        "/api/recipes",
+        Some(&CreateRecipe {
+            visibility: RecipeVisibility::Friends,
+            ..recipe_with_ingredients(&["Tomato"])
+        }),
+    )
+    .await?;
+    app.post::<(), _>(format!("/api/friends/new/{}", other.id), None)
+        .await?;
+
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+    app.post::<(), _>(format!("/api/friends/accept/{}", app.user.id), None)
+        .await?;
+
+    let recipes = app.get("/api/recipes").await?.json::<Vec<Recipe>>().await?;
+    assert_eq!(1, recipes.len());
+
+    // The recipient of the request can block, and becomes the one who can unblock
+    let res = app
+        .post::<(), _>(format!("/api/friends/block/{}", app.user.id), None)
+        .await?;
+    assert_eq!(StatusCode::OK, res.status());
+
+    let friendship = find_friendship(&app).await?.unwrap();
+    assert_eq!(FriendshipStatus::Blocked, friendship.status);
+    assert_eq!(other.id, friendship.requester_id);
+
+    let recipes = app.get("/api/recipes").await?.json::<Vec<Recipe>>().await?;
+    assert!(recipes.is_empty());
+    assert!(search_users(&app).await?.is_empty());
+
+    let blocked = app
+        .get("/api/friends/blocked")
+        .await?
+        .json::<Vec<UserWithRelation>>()
+        .await?;
+    assert_eq!(
+        vec![app.user.id],
+        blocked.iter().map(|it| it.id).collect::<Vec<_>>()
+    );
+
+    // The blocked user can't find them, unblock or send a new request
+    app.login_as(TEST_EMAIL, TEST_PASSWORD).await;
+    assert!(search_users(&app).await?.is_empty());
+
+    for action in ["unblock", "new", "accept"] {
+        let res = app
+            .post::<(), _>(format!("/api/friends/{action}/{}", other.id), None)
+            .await?;
+        assert_eq!(StatusCode::NOT_FOUND, res.status(), "{action}");
+    }
+    assert_eq!(
+        FriendshipStatus::Blocked,
+        find_friendship(&app).await?.unwrap().status
+    );
+
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+    let res = app
+        .post::<(), _>(format!("/api/friends/unblock/{}", app.user.id), None)
+        .await?;
+    assert_eq!(StatusCode::OK, res.status());
+    assert!(find_friendship(&app).await?.is_none());
+
+    let users = search_users(&app).await?;
+    assert_eq!(1, users.len());
+    assert_eq!(None, users[0].status);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_block_stranger(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let other = app.create_user_named("bar").await?;
+
+    let res = app
+        .post::<(), _>(format!("/api/friends/block/{}", other.id), None)
+        .await?;
+    assert_eq!(StatusCode::OK, res.status());
+
+    // The one who blocked has to unblock before sending a request
+    let res = app
+        .post::<(), _>(format!("/api/friends/new/{}", other.id), None)
+        .await?;
+    assert_eq!(StatusCode::BAD_REQUEST, res.status());
+
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+    let res = app
+        .post::<(), _>(format!("/api/friends/new/{}", app.user.id), None)
+        .await?;
+    assert_eq!(StatusCode::NOT_FOUND, res.status());
+
+    let friendship = find_friendship(&app).await?.unwrap();
+    assert_eq!(FriendshipStatus::Blocked, friendship.status);
+    assert_eq!(app.user.id, friendship.requester_id);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_friendrequest_and_block_need_another_user(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    for action in ["new", "block"] {
+        let res = app
+            .post::<(), _>(format!("/api/friends/{action}/{}", app.user.id), None)
+            .await?;
+        assert_eq!(StatusCode::BAD_REQUEST, res.status());
+
+        let res = app
+            .post::<(), _>(format!("/api/friends/{action}/9999"), None)
+            .await?;
+        assert_eq!(StatusCode::NOT_FOUND, res.status());
+    }
+    assert!(find_friendship(&app).await?.is_none());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_cancel_friend_request(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let other = app.create_user_named("bar").await?;
+
+    app.post::<(), _>(format!("/api/friends/new/{}", other.id), None)
+        .await?;
+
+    let outgoing = app
+        .get("/api/friends/outgoing")
+        .await?
+        .json::<Vec<UserWithRelation>>()
+        .await?;
+    assert_eq!(
+        vec![other.id],
+        outgoing.iter().map(|it| it.id).collect::<Vec<_>>()
+    );
+
+    // Only the requester can cancel the request
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+    let outgoing = app
+        .get("/api/friends/outgoing")
+        .await?
+        .json::<Vec<UserWithRelation>>()
+        .await?;
+    assert!(outgoing.is_empty());
+
+    let res = app
+        .post::<(), _>(format!("/api/friends/cancel/{}", app.user.id), None)
+        .await?;
+    assert_eq!(StatusCode::NOT_FOUND, res.status());
+
+    app.login_as(TEST_EMAIL, TEST_PASSWORD).await;
+    let res = app
+        .post::<(), _>(format!("/api/friends/cancel/{}", other.id), None)
+        .await?;
+    assert_eq!(StatusCode::OK, res.status());
+    assert!(find_friendship(&app).await?.is_none());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_remove_friend(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let other = app.create_user_named("bar").await?;
+
+    app.post(
+        "/api/recipes",
+        Some(&CreateRecipe {
+            visibility: RecipeVisibility::Friends,
+            ..recipe_with_ingredients(&["Tomato"])
+        }),
+    )
+    .await?;
+    app.post::<(), _>(format!("/api/friends/new/{}", other.id), None)
+        .await?;
+
+    // A pending request is not a friendship that can be removed
+    let res = app
+        .post::<(), _>(format!("/api/friends/remove/{}", other.id), None)
+        .await?;
+    assert_eq!(StatusCode::NOT_FOUND, res.status());
+
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+    app.post::<(), _>(format!("/api/friends/accept/{}", app.user.id), None)
+        .await?;
+
+    let friends = app
+        .get("/api/friends")
+        .await?
+        .json::<Vec<UserWithRelation>>()
+        .await?;
+    assert_eq!(
+        vec![app.user.id],
+        friends.iter().map(|it| it.id).collect::<Vec<_>>()
+    );
+    let recipes = app.get("/api/recipes").await?.json::<Vec<Recipe>>().await?;
+    assert_eq!(1, recipes.len());
+
+    // The recipient of the request can remove the friend as well
+    let res = app
+        .post::<(), _>(format!("/api/friends/remove/{}", app.user.id), None)
+        .await?;
+    assert_eq!(StatusCode::OK, res.status());
+    assert!(find_friendship(&app).await?.is_none());
+
+    let friends = app
+        .get("/api/friends")
+        .await?
+        .json::<Vec<UserWithRelation>>()
+        .await?;
+    assert!(friends.is_empty());
+    let recipes = app.get("/api/recipes").await?.json::<Vec<Recipe>>().await?;
+    assert!(recipes.is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_friend_events(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let other = app.create_user_named("bar").await?;
+
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+    let mut bar_socket = app.websocket().await?;
+    app.login_as(TEST_EMAIL, TEST_PASSWORD).await;
+    let mut foo_socket = app.websocket().await?;
+
+    app.post::<(), _>(format!("/api/friends/new/{}", other.id), None)
+        .await?;
+    // The notification is sent before the event
+    let FoodieMessageType::Notification(_) = next_message(&mut bar_socket).await? else {
+        panic!("Expected a notification");
+    };
+    let FoodieMessageType::FriendRequest(requester) = next_message(&mut bar_socket).await? else {
+        panic!("Expected a friend request");
+    };
+    assert_eq!(app.user.id, requester.id);
+
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+    app.post::<(), _>(format!("/api/friends/accept/{}", app.user.id), None)
+        .await?;
+    let FoodieMessageType::Notification(_) = next_message(&mut foo_socket).await? else {
+        panic!("Expected a notification");
+    };
+    let FoodieMessageType::FriendRequestAccepted(recipient) = next_message(&mut foo_socket).await?
+    else {
+        panic!("Expected an accepted friend request");
+    };
+    assert_eq!(other.id, recipient.id);
+
+    app.post::<(), _>(format!("/api/friends/remove/{}", app.user.id), None)
+        .await?;
+    let FoodieMessageType::FriendRemoved(friend) = next_message(&mut foo_socket).await? else {
+        panic!("Expected a removed friend");
+    };
+    assert_eq!(other.id, friend.id);
+
+    Ok(())
+}
+
+async fn insert_friendship(
+    app: &TestApp,
+    requester_id: i32,
+    recipient_id: i32,
+    status: FriendshipStatus,
+) -> Result<(), anyhow::Error> {
+    friendships::Entity::insert(friendships::ActiveModel {
+        requester_id: Set(requester_id),
+        recipient_id: Set(recipient_id),
+        status: Set(status),
+        requested_at: Set(chrono::Utc::now().fixed_offset()),
+        responded_at: Set(None),
+    })
+    .exec(&app.pool)
+    .await?;
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_friend_suggestions(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let friend = app.create_user_named("bar").await?;
+    let carol = app.create_user_named("carol").await?;
+    let dave = app.create_user_named("dave").await?;
+    let erin = app.create_user_named("erin").await?;
+    let frank = app.create_user_named("frank").await?;
+    app.create_user_named("gina").await?;
+    app.create_user_named("henry").await?;
+
+    insert_friendship(&app, app.user.id, friend.id, FriendshipStatus::Accepted).await?;
+    for user in [&carol, &dave, &erin] {
+        insert_friendship(&app, friend.id, user.id, FriendshipStatus::Accepted).await?;
+    }
+    insert_friendship(&app, carol.id, dave.id, FriendshipStatus::Accepted).await?;
+    // Blocked and rejected users are never suggested, even with mutual friends
+    insert_friendship(&app, app.user.id, carol.id, FriendshipStatus::Blocked).await?;
+    insert_friendship(&app, erin.id, app.user.id, FriendshipStatus::Rejected).await?;
+
+    let ingredients = ["Tortilla", "Minced meat", "Salsa"];
+    for ingredient in ingredients {
+        create_global_ingredient(&app, ingredient).await?;
+    }
+    app.post("/api/recipes", Some(&recipe_with_ingredients(&ingredients)))
+        .await?;
+
+    app.login_as("frank@frank.com", TEST_PASSWORD).await;
+    app.post(
+        "/api/recipes?allow_duplicate=true",
+        Some(&recipe_with_ingredients(&ingredients)),
+    )
+    .await?;
+
+    // Private recipes are not counted, as that would tell that they exist
+    app.login_as("henry@henry.com", TEST_PASSWORD).await;
+    app.post(
+        "/api/recipes?allow_duplicate=true",
+        Some(&CreateRecipe {
+            visibility: RecipeVisibility::Private,
+            ..recipe_with_ingredients(&ingredients)
+        }),
+    )
+    .await?;
+
+    app.login_as(TEST_EMAIL, TEST_PASSWORD).await;
+    let suggestions = app
+        .get("/api/friends/suggestions")
+        .await?
+        .json::<Vec<FriendSuggestion>>()
+        .await?;
+
+    // Mutual friends count for more than similar recipes, and users with nothing in common are
+    // left out
+    assert_eq!(
+        vec![(dave.id, 1, 0), (frank.id, 0, 1)],
+        suggestions
+            .iter()
+            .map(|it| (it.id, it.mutual_friends, it.similar_recipes))
+            .collect::<Vec<_>>()
+    );
+
+    Ok(())
+}
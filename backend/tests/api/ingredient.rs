@@ -0,0 +1,445 @@
+use backend::entities::{ingredient_aliases, ingredients, recipe_ingredients};
+use common::{
+    ingredient::{
+        CreateIngredient, CreateIngredientAlias, Ingredient, IngredientCategory, MergeIngredient,
+        UpdateIngredientCategory,
+    },
+    recipe::{CreateRecipe, CreateRecipeIngredient, Recipe},
+    user::CreateUser,
+};
+use reqwest::StatusCode;
+use sea_orm::{ActiveValue::NotSet, ColumnTrait, EntityTrait, QueryFilter, Set};
+use sqlx::PgPool;
+
+use crate::{TestApp, TEST_PASSWORD};
+
+pub async fn create_global_ingredient(app: &TestApp, name: &str) -> Result<i32, anyhow::Error> {
+    let ingredient = ingredients::Entity::insert(ingredients::ActiveModel {
+        id: NotSet,
+        name: Set(name.to_string()),
+        user_id: Set(None),
+        normalized_name: Set(name.to_lowercase()),
+        category: NotSet,
+        allergens: NotSet,
+        vegetarian: NotSet,
+        vegan: NotSet,
+    })
+    .exec_with_returning(&app.pool)
+    .await?;
+
+    Ok(ingredient.id)
+}
+
+pub fn recipe_with_ingredients(names: &[&str]) -> CreateRecipe {
+    CreateRecipe {
+        name: "Salad".to_string(),
+        servings: 2,
+        ingredients: names
+            .iter()
+            .map(|name| CreateRecipeIngredient {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_get_ingredients_global_and_own(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let salt_id = create_global_ingredient(&app, "Salt").await?;
+
+    app.post(
+        "/api/recipes",
+        Some(&recipe_with_ingredients(&["Tomato", "Salt"])),
+    )
+    .await?;
+
+    let ingredients = app
+        .get("/api/ingredients")
+        .await?
+        .json::<Vec<Ingredient>>()
+        .await?;
+
+    let tomato = ingredients.iter().find(|i| i.name == "Tomato").unwrap();
+    assert_eq!(2, ingredients.len());
+    assert_eq!(Some(app.user.id), tomato.user_id);
+
+    app.create_user_named("bar").await?;
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+
+    let ingredients = app
+        .get("/api/ingredients")
+        .await?
+        .json::<Vec<Ingredient>>()
+        .await?;
+
+    assert_eq!(1, ingredients.len());
+    assert_eq!(salt_id, ingredients[0].id);
+    assert_eq!(None, ingredients[0].user_id);
+
+    let res = app.get(format!("/api/ingredients/{}", tomato.id)).await?;
+    assert_eq!(StatusCode::NOT_FOUND, res.status());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_ingredient_names_are_case_insensitive(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let salt_id = create_global_ingredient(&app, "Salt").await?;
+
+    let create = |name: &str| CreateIngredient {
+        name: name.to_string(),
+    };
+
+    let first = app
+        .post("/api/ingredients", Some(&create("Tomato")))
+        .await?
+        .json::<Ingredient>()
+        .await?;
+    let second = app
+        .post("/api/ingredients", Some(&create("  tomato ")))
+        .await?
+        .json::<Ingredient>()
+        .await?;
+    let salt = app
+        .post("/api/ingredients", Some(&create("SALT")))
+        .await?
+        .json::<Ingredient>()
+        .await?;
+
+    assert_eq!(first.id, second.id);
+    assert_eq!("Tomato", second.name);
+    assert_eq!(salt_id, salt.id);
+
+    let recipe = app
+        .post(
+            "/api/recipes",
+            Some(&recipe_with_ingredients(&["Tomato", "TOMATO", "salt"])),
+        )
+        .await?
+        .json::<Recipe>()
+        .await?;
+
+    let ingredient_ids = recipe
+        .ingredients
+        .iter()
+        .map(|it| it.ingredient_id)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![first.id, first.id, salt_id], ingredient_ids);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_ingredient_alias_resolves_to_catalog(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let spring_onion_id = create_global_ingredient(&app, "Spring onion").await?;
+
+    let res = app
+        .post(
+            format!("/api/ingredients/{spring_onion_id}/aliases"),
+            Some(&CreateIngredientAlias {
+                name: "Scallion".to_string(),
+            }),
+        )
+        .await?;
+    assert_eq!(StatusCode::OK, res.status());
+
+    let recipe = app
+        .post(
+            "/api/recipes",
+            Some(&recipe_with_ingredients(&["scallion"])),
+        )
+        .await?
+        .json::<Recipe>()
+        .await?;
+
+    assert_eq!(spring_onion_id, recipe.ingredients[0].ingredient_id);
+    assert_eq!("Spring onion", recipe.ingredients[0].ingredient_name);
+
+    app.create_user_named("bar").await?;
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+
+    let res = app
+        .post(
+            format!("/api/ingredients/{spring_onion_id}/aliases"),
+            Some(&CreateIngredientAlias {
+                name: "Green onion".to_string(),
+            }),
+        )
+        .await?;
+    assert_eq!(StatusCode::FORBIDDEN, res.status());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_merge_ingredients(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let tomato_id = create_global_ingredient(&app, "Tomato").await?;
+
+    let first = app
+        .post(
+            "/api/recipes",
+            Some(&recipe_with_ingredients(&["Tomatoes"])),
+        )
+        .await?
+        .json::<Recipe>()
+        .await?;
+    let second = app
+        .post(
+            "/api/recipes?allow_duplicate=true",
+            Some(&recipe_with_ingredients(&["Tomatoes", "Tomato"])),
+        )
+        .await?
+        .json::<Recipe>()
+        .await?;
+
+    let tomatoes_id = first.ingredients[0].ingredient_id;
+    assert_ne!(tomato_id, tomatoes_id);
+
+    let res = app
+        .post(
+            format!("/api/ingredients/{tomatoes_id}/merge"),
+            Some(&MergeIngredient { into_id: tomato_id }),
+        )
+        .await?;
+    assert_eq!(StatusCode::OK, res.status());
+
+    for (recipe_id, expected) in [
+        (first.id, vec![tomato_id]),
+        (second.id, vec![tomato_id, tomato_id]),
+    ] {
+        let ingredient_ids = recipe_ingredients::Entity::find()
+            .filter(recipe_ingredients::Column::RecipeId.eq(recipe_id))
+            .all(&app.pool)
+            .await?
+            .into_iter()
+            .map(|it| it.ingredient_id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(expected, ingredient_ids);
+    }
+
+    let merged = ingredients::Entity::find_by_id(tomatoes_id)
+        .one(&app.pool)
+        .await?;
+    assert_eq!(None, merged);
+
+    let alias = ingredient_aliases::Entity::find()
+        .filter(ingredient_aliases::Column::NormalizedName.eq("tomatoes"))
+        .one(&app.pool)
+        .await?
+        .unwrap();
+    assert_eq!(tomato_id, alias.ingredient_id);
+
+    let other = app
+        .create_user(&CreateUser {
+            name: "baz".to_string(),
+            email: "baz@baz.com".to_string(),
+            password: "foo".to_string(),
+        })
+        .await?;
+    let private = ingredients::Entity::insert(ingredients::ActiveModel {
+        id: NotSet,
+        name: Set("Cherry tomato".to_string()),
+        user_id: Set(Some(other.id)),
+        normalized_name: Set("cherry tomato".to_string()),
+        category: NotSet,
+        allergens: NotSet,
+        vegetarian: NotSet,
+        vegan: NotSet,
+    })
+    .exec_with_returning(&app.pool)
+    .await?;
+    let res = app
+        .post(
+            format!("/api/ingredients/{tomato_id}/merge"),
+            Some(&MergeIngredient {
+                into_id: private.id,
+            }),
+        )
+        .await?;
+    assert_eq!(StatusCode::FORBIDDEN, res.status());
+
+    app.create_user_named("bar").await?;
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+
+    let potato_id = create_global_ingredient(&app, "Potato").await?;
+    let res = app
+        .post(
+            format!("/api/ingredients/{potato_id}/merge"),
+            Some(&MergeIngredient { into_id: tomato_id }),
+        )
+        .await?;
+    assert_eq!(StatusCode::FORBIDDEN, res.status());
+
+    Ok(())
+}
+
+async fn search(app: &TestApp, q: &str) -> Result<Vec<String>, anyhow::Error> {
+    let ingredients = app
+        .get(format!("/api/ingredients/search?q={q}"))
+        .await?
+        .json::<Vec<Ingredient>>()
+        .await?;
+
+    Ok(ingredients.into_iter().map(|it| it.name).collect())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_search_ingredients_prefix_and_typos(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    create_global_ingredient(&app, "Tomato").await?;
+    create_global_ingredient(&app, "Potato").await?;
+    create_global_ingredient(&app, "Garlic").await?;
+
+    assert_eq!(vec!["Tomato"], search(&app, "tom").await?);
+    assert_eq!(vec!["Tomato"], search(&app, "tomatoe").await?);
+    assert_eq!(vec!["Garlic"], search(&app, "garlik").await?);
+    assert!(search(&app, "").await?.is_empty());
+
+    app.post(
+        "/api/recipes",
+        Some(&recipe_with_ingredients(&["Tomato paste"])),
+    )
+    .await?;
+    app.create_user_named("bar").await?;
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+
+    assert_eq!(vec!["Tomato"], search(&app, "tomato").await?);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_search_ingredients_ranked_by_usage(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    create_global_ingredient(&app, "Tomato").await?;
+    create_global_ingredient(&app, "Tomatillo").await?;
+
+    assert_eq!(vec!["Tomato", "Tomatillo"], search(&app, "toma").await?);
+
+    app.post(
+        "/api/recipes",
+        Some(&recipe_with_ingredients(&["Tomatillo"])),
+    )
+    .await?;
+
+    assert_eq!(vec!["Tomatillo", "Tomato"], search(&app, "toma").await?);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_ingredient_category_override(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let milk_id = create_global_ingredient(&app, "Milk").await?;
+
+    let update = |category| UpdateIngredientCategory { category };
+
+    let milk = app
+        .put(
+            format!("/api/ingredients/{milk_id}/default-category"),
+            &update(IngredientCategory::Dairy),
+        )
+        .await?
+        .json::<Ingredient>()
+        .await?;
+    assert_eq!(IngredientCategory::Dairy, milk.category);
+
+    app.create_user_named("bar").await?;
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+
+    let res = app
+        .put(
+            format!("/api/ingredients/{milk_id}/default-category"),
+            &update(IngredientCategory::Beverages),
+        )
+        .await?;
+    assert_eq!(StatusCode::FORBIDDEN, res.status());
+
+    app.put(
+        format!("/api/ingredients/{milk_id}/category"),
+        &update(IngredientCategory::Beverages),
+    )
+    .await?;
+
+    let milk = app
+        .get(format!("/api/ingredients/{milk_id}"))
+        .await?
+        .json::<Ingredient>()
+        .await?;
+    assert_eq!(IngredientCategory::Beverages, milk.category);
+
+    let milk = app
+        .delete(format!("/api/ingredients/{milk_id}/category"))
+        .await?
+        .json::<Ingredient>()
+        .await?;
+    assert_eq!(IngredientCategory::Dairy, milk.category);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_ingredients_ordered_by_aisle(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    for (name, category) in [
+        ("Apple", IngredientCategory::Produce),
+        ("Milk", IngredientCategory::Dairy),
+        ("Flour", IngredientCategory::DryGoods),
+        ("Banana", IngredientCategory::Produce),
+    ] {
+        let id = create_global_ingredient(&app, name).await?;
+        app.put(
+            format!("/api/ingredients/{id}/category"),
+            &UpdateIngredientCategory { category },
+        )
+        .await?;
+    }
+
+    let names = |ingredients: Vec<Ingredient>| {
+        ingredients
+            .into_iter()
+            .map(|it| it.name)
+            .collect::<Vec<_>>()
+    };
+
+    let ingredients = app
+        .get("/api/ingredients")
+        .await?
+        .json::<Vec<Ingredient>>()
+        .await?;
+    assert_eq!(vec!["Apple", "Banana", "Milk", "Flour"], names(ingredients));
+
+    let aisles = app
+        .put(
+            "/api/ingredients/aisles",
+            &vec![IngredientCategory::DryGoods, IngredientCategory::Dairy],
+        )
+        .await?
+        .json::<Vec<IngredientCategory>>()
+        .await?;
+    assert_eq!(
+        [
+            IngredientCategory::DryGoods,
+            IngredientCategory::Dairy,
+            IngredientCategory::Produce
+        ],
+        aisles[..3]
+    );
+
+    let ingredients = app
+        .get("/api/ingredients")
+        .await?
+        .json::<Vec<Ingredient>>()
+        .await?;
+    assert_eq!(vec!["Flour", "Milk", "Apple", "Banana"], names(ingredients));
+
+    Ok(())
+}
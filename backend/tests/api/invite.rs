@@ -0,0 +1,134 @@
+use backend::entities::{friendships, invites, sea_orm_active_enums::FriendshipStatus};
+use chrono::Utc;
+use common::{
+    invite::{CreateInvite, Invite},
+    user::{CreateUser, User},
+};
+use reqwest::StatusCode;
+use sea_orm::{sea_query::Expr, EntityTrait};
+use sqlx::PgPool;
+
+use crate::{TestApp, TEST_EMAIL, TEST_PASSWORD};
+
+fn new_user(email: &str) -> CreateUser {
+    CreateUser {
+        name: "new".to_string(),
+        email: email.to_string(),
+        password: TEST_PASSWORD.to_string(),
+    }
+}
+
+async fn invite(app: &TestApp, email: &str) -> Result<String, anyhow::Error> {
+    let response = app
+        .post(
+            "/api/invites",
+            Some(&CreateInvite {
+                email: email.to_string(),
+            }),
+        )
+        .await?;
+    assert_eq!(StatusCode::CREATED, response.status());
+
+    // The token is at the end of the link in the mail
+    let mail = app
+        .mails()?
+        .into_iter()
+        .find(|it| it.contains(&format!("To: {email}")))
+        .unwrap();
+    let token = mail.split("invite=").nth(1).unwrap().trim().to_string();
+    Ok(token)
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_register_with_invite(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    let token = invite(&app, "new@foo.com").await?;
+    let user = app
+        .post(
+            format!("/api/register?invite={token}"),
+            Some(&new_user("new@foo.com")),
+        )
+        .await?
+        .json::<User>()
+        .await?;
+
+    // The one who registers becomes friends with the one who invited them
+    let friendship = friendships::Entity::find_by_id((app.user.id, user.id))
+        .one(&app.pool)
+        .await?
+        .unwrap();
+    assert_eq!(FriendshipStatus::Accepted, friendship.status);
+
+    let invites = app.get("/api/invites").await?.json::<Vec<Invite>>().await?;
+    assert_eq!(1, invites.len());
+    assert!(invites[0].accepted);
+
+    // The invite can only be used once
+    let response = app
+        .post(
+            format!("/api/register?invite={token}"),
+            Some(&new_user("new@foo.com")),
+        )
+        .await?;
+    assert_eq!(StatusCode::GONE, response.status());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_invalid_invites(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    for (email, status) in [
+        ("not an email", StatusCode::BAD_REQUEST),
+        ("new@foo.com\r\nBcc: spam@foo.com", StatusCode::BAD_REQUEST),
+        (TEST_EMAIL, StatusCode::CONFLICT),
+    ] {
+        let response = app
+            .post(
+                "/api/invites",
+                Some(&CreateInvite {
+                    email: email.to_string(),
+                }),
+            )
+            .await?;
+        assert_eq!(status, response.status(), "{email}");
+    }
+    assert!(app.mails()?.is_empty());
+
+    let response = app
+        .post(
+            "/api/register?invite=unknown",
+            Some(&new_user("new@foo.com")),
+        )
+        .await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    // The invite is only for the email it was sent to
+    let token = invite(&app, "new@foo.com").await?;
+    let response = app
+        .post(
+            format!("/api/register?invite={token}"),
+            Some(&new_user("other@foo.com")),
+        )
+        .await?;
+    assert_eq!(StatusCode::FORBIDDEN, response.status());
+
+    invites::Entity::update_many()
+        .col_expr(
+            invites::Column::ExpiresAt,
+            Expr::value(Utc::now() - chrono::Duration::minutes(1)),
+        )
+        .exec(&app.pool)
+        .await?;
+    let response = app
+        .post(
+            format!("/api/register?invite={token}"),
+            Some(&new_user("new@foo.com")),
+        )
+        .await?;
+    assert_eq!(StatusCode::GONE, response.status());
+
+    Ok(())
+}
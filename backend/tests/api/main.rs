@@ -1,23 +1,50 @@
+mod collection;
+mod duplicate;
+mod feed;
 mod friends;
+mod ingredient;
+mod invite;
+mod meal_plan;
+mod notification;
+mod pdf;
+mod profile;
 mod recipe;
+mod shopping_list;
+mod suggestion;
+mod swipe;
+mod takeout;
 mod users;
+mod voting;
 
 use axum_login::tower_sessions::MemoryStore;
-use common::user::{CreateUser, User, UserLogin};
+use common::{
+    recipe::{CreateRecipe, Recipe},
+    user::{CreateUser, User, UserLogin},
+};
 use migration::MigratorTrait;
 use sea_orm::{DatabaseConnection, SqlxPostgresConnector};
 use serde::Serialize;
 use sqlx::PgPool;
-use std::{fmt::Display, future::IntoFuture};
-
-use backend::app::App;
-use reqwest::{IntoUrl, Response};
+use std::{fmt::Display, future::IntoFuture, path::PathBuf, sync::Arc};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    tungstenite::{client::IntoClientRequest, http::header::COOKIE},
+    MaybeTlsStream, WebSocketStream,
+};
+
+use backend::{app::App, mailer::file::FileMailer};
+use reqwest::{
+    cookie::{CookieStore, Jar},
+    IntoUrl, Response,
+};
 
 struct TestApp {
     pub client: reqwest::Client,
     pub address: String,
     pub user: User,
     pool: DatabaseConnection,
+    cookies: Arc<Jar>,
+    mail_dir: PathBuf,
 }
 
 const TEST_EMAIL: &str = "foo@foo.com";
@@ -33,16 +60,27 @@ impl TestApp {
         let address = format!("http://{}", listener.local_addr()?);
         let connection = SqlxPostgresConnector::from_sqlx_postgres_pool(pool);
 
-        let app = App::new(connection.clone(), MemoryStore::default()).await?;
+        // Every app gets its own directory for mail, so the tests can read what they sent
+        let mail_dir =
+            std::env::temp_dir().join(format!("foodie-mail-{}", listener.local_addr()?.port()));
+        let _ = std::fs::remove_dir_all(&mail_dir);
+        let mailer = Arc::new(FileMailer::new(&mail_dir)?);
+
+        let app = App::new(connection.clone(), MemoryStore::default(), mailer).await?;
         let server = axum::serve(listener, app.router.into_make_service());
         tokio::spawn(server.into_future());
 
         migration::Migrator::up(&connection, None).await?;
 
-        let client = reqwest::Client::builder().cookie_store(true).build()?;
+        let cookies = Arc::new(Jar::default());
+        let client = reqwest::Client::builder()
+            .cookie_provider(cookies.clone())
+            .build()?;
         // HACK: Set an env variable that I read in `register` API.
         // Not opening up so that anyone can just register a user
         std::env::set_var("FOODIE_TEST", "1");
+        // The default test user is an admin. Log in as another user to test as a regular user
+        std::env::set_var("ADMIN_MAILS", TEST_EMAIL);
 
         let user = client
             .post(format!("{}/api/register", address))
@@ -61,6 +99,8 @@ impl TestApp {
             client,
             user,
             pool: connection,
+            cookies,
+            mail_dir,
         };
 
         _self
@@ -120,6 +160,27 @@ impl TestApp {
         Ok(res)
     }
 
+    // Connects to the websocket as the user that is currently logged in
+    pub async fn websocket(
+        &self,
+    ) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, anyhow::Error> {
+        let url = format!("{}/api/ws", self.address);
+        let mut request = url.replace("http", "ws").into_client_request()?;
+        if let Some(cookie) = self.cookies.cookies(&url.parse()?) {
+            request.headers_mut().insert(COOKIE, cookie);
+        }
+
+        let (stream, _) = tokio_tungstenite::connect_async(request).await?;
+        Ok(stream)
+    }
+
+    // The mails that have been sent, as written by the file mailer
+    pub fn mails(&self) -> Result<Vec<String>, anyhow::Error> {
+        std::fs::read_dir(&self.mail_dir)?
+            .map(|entry| Ok(std::fs::read_to_string(entry?.path())?))
+            .collect()
+    }
+
     async fn login(&self, input: &UserLogin) {
         self.post("/api/login", Some(input)).await.unwrap();
     }
@@ -132,4 +193,31 @@ impl TestApp {
             .await?;
         Ok(user)
     }
+
+    async fn login_as(&self, email: &str, password: &str) {
+        self.login(&UserLogin {
+            email: email.to_string(),
+            password: password.to_string(),
+        })
+        .await;
+    }
+
+    // Registers `name` with `{name}@{name}.com` as email and the same password as the test user
+    async fn create_user_named(&self, name: &str) -> Result<User, anyhow::Error> {
+        self.create_user(&CreateUser {
+            name: name.to_string(),
+            email: format!("{name}@{name}.com"),
+            password: TEST_PASSWORD.to_string(),
+        })
+        .await
+    }
+
+    async fn create_recipe(&self, input: &CreateRecipe) -> Result<Recipe, anyhow::Error> {
+        let recipe = self
+            .post("/api/recipes", Some(input))
+            .await?
+            .json::<Recipe>()
+            .await?;
+        Ok(recipe)
+    }
 }
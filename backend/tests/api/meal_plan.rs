@@ -0,0 +1,166 @@
+use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
+use common::{
+    meal_plan::{CalendarFeed, CreatePlannedMeal, PlannedMeal},
+    recipe::{CreateRecipe, Duration, RecipeVisibility},
+    user::{CreateUser, UserLogin},
+};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+
+use crate::{ingredient::recipe_with_ingredients, TestApp};
+
+async fn plan(
+    app: &TestApp,
+    recipe_id: i32,
+    eat_at: DateTime<FixedOffset>,
+) -> Result<reqwest::Response, anyhow::Error> {
+    app.post(
+        "/api/meal-plan",
+        Some(&CreatePlannedMeal { recipe_id, eat_at }),
+    )
+    .await
+}
+
+// Fetches the feed without the cookies of the test client, like a calendar app would
+async fn calendar(app: &TestApp, url: &str) -> Result<reqwest::Response, anyhow::Error> {
+    let (_, path) = url.split_once("/api/").unwrap();
+    Ok(reqwest::get(format!("{}/api/{path}", app.address)).await?)
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_meal_plan(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let recipe = app
+        .create_recipe(&CreateRecipe {
+            name: "Pizza".to_string(),
+            ..recipe_with_ingredients(&["Flour"])
+        })
+        .await?;
+
+    let tomorrow = (Utc::now() + TimeDelta::days(1)).fixed_offset();
+    let next_week = (Utc::now() + TimeDelta::days(7)).fixed_offset();
+    let planned = plan(&app, recipe.id, next_week)
+        .await?
+        .json::<PlannedMeal>()
+        .await?;
+    plan(&app, recipe.id, tomorrow).await?;
+
+    let response = plan(&app, 9999, tomorrow).await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    let meals = app
+        .get("/api/meal-plan")
+        .await?
+        .json::<Vec<PlannedMeal>>()
+        .await?;
+    // The earliest meal comes first, even though it was planned last
+    assert_eq!(2, meals.len());
+    assert_eq!(planned.id, meals[1].id);
+    assert_eq!("Pizza", meals[1].name);
+
+    let response = app.delete(format!("/api/meal-plan/{}", planned.id)).await?;
+    assert_eq!(StatusCode::OK, response.status());
+    let response = app.delete(format!("/api/meal-plan/{}", planned.id)).await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    let meals = app
+        .get("/api/meal-plan")
+        .await?
+        .json::<Vec<PlannedMeal>>()
+        .await?;
+    assert_eq!(1, meals.len());
+
+    // Others can't remove meals from the plan
+    app.create_user(&CreateUser {
+        name: "bar".to_string(),
+        email: "bar@bar.com".to_string(),
+        password: "foo".to_string(),
+    })
+    .await?;
+    app.login(&UserLogin {
+        email: "bar@bar.com".to_string(),
+        password: "foo".to_string(),
+    })
+    .await;
+    let response = app
+        .delete(format!("/api/meal-plan/{}", meals[0].id))
+        .await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_meal_plan_calendar(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let pizza = app
+        .create_recipe(&CreateRecipe {
+            name: "Pizza".to_string(),
+            prep_time: Some(Duration::from_minutes(30)),
+            resting_time: Some(Duration::from_hours(2)),
+            ..recipe_with_ingredients(&["Flour"])
+        })
+        .await?;
+    let salad = app
+        .create_recipe(&CreateRecipe {
+            name: "Salad, with dressing".to_string(),
+            prep_time: Some(Duration::from_minutes(15)),
+            visibility: RecipeVisibility::Private,
+            ..recipe_with_ingredients(&["Lettuce"])
+        })
+        .await?;
+
+    let eat_at = DateTime::parse_from_rfc3339("2030-05-17T18:00:00+02:00")?;
+    plan(&app, pizza.id, eat_at).await?;
+    plan(&app, salad.id, eat_at).await?;
+
+    let feed = app
+        .get("/api/meal-plan/calendar")
+        .await?
+        .json::<CalendarFeed>()
+        .await?;
+    assert!(feed.url.ends_with(".ics"));
+
+    // The url stays the same until it is reset
+    let same_feed = app
+        .get("/api/meal-plan/calendar")
+        .await?
+        .json::<CalendarFeed>()
+        .await?;
+    assert_eq!(feed, same_feed);
+
+    let response = calendar(&app, &feed.url).await?;
+    assert_eq!(StatusCode::OK, response.status());
+    let ics = response.text().await?;
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(ics.contains("SUMMARY:Pizza\r\n"));
+    assert!(ics.contains("SUMMARY:Salad\\, with dressing\r\n"));
+    // Cooking starts the total time before it is ready to eat
+    assert!(ics.contains("DTSTART:20300517T133000Z\r\nDTEND:20300517T160000Z\r\n"));
+    assert!(ics.contains("DTSTART:20300517T154500Z\r\nDTEND:20300517T160000Z\r\n"));
+    let frontend_url = dotenv::var("FRONTEND_URL")?;
+    assert!(ics.contains(&format!("URL:{frontend_url}/recipes/{}\r\n", pizza.id)));
+    // Only the pizza has to rest long enough for a prep reminder
+    assert_eq!(1, ics.matches("BEGIN:VALARM").count());
+    assert!(ics.contains("SUMMARY:Prep Pizza\r\n"));
+
+    let ics = calendar(&app, &format!("{}?reminders=false", feed.url))
+        .await?
+        .text()
+        .await?;
+    assert_eq!(0, ics.matches("BEGIN:VALARM").count());
+    assert_eq!(2, ics.matches("BEGIN:VEVENT").count());
+
+    let new_feed = app
+        .post("/api/meal-plan/calendar/reset", None::<&()>)
+        .await?
+        .json::<CalendarFeed>()
+        .await?;
+    assert_ne!(feed, new_feed);
+    let response = calendar(&app, &feed.url).await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+    let response = calendar(&app, &new_feed.url).await?;
+    assert_eq!(StatusCode::OK, response.status());
+
+    Ok(())
+}
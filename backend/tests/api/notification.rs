@@ -0,0 +1,119 @@
+use common::{
+    notification::{Notification, NotificationKind, ReadNotifications, UnreadNotifications},
+    recipe::{CreateRecipe, Recipe, RecipeVisibility},
+    user::{CreateUser, UserLogin},
+    websocket::FoodieMessageType,
+};
+use sqlx::PgPool;
+
+use crate::{
+    ingredient::recipe_with_ingredients, voting::next_message, TestApp, TEST_EMAIL, TEST_PASSWORD,
+};
+
+async fn unread(app: &TestApp) -> Result<u64, anyhow::Error> {
+    Ok(app
+        .get("/api/notifications/unread")
+        .await?
+        .json::<UnreadNotifications>()
+        .await?
+        .count)
+}
+
+async fn notifications(app: &TestApp) -> Result<Vec<Notification>, anyhow::Error> {
+    Ok(app
+        .get("/api/notifications")
+        .await?
+        .json::<Vec<Notification>>()
+        .await?)
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_notifications(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let other = app
+        .create_user(&CreateUser {
+            name: "bar".to_string(),
+            email: "bar@bar.com".to_string(),
+            password: "foo".to_string(),
+        })
+        .await?;
+    let mut foo_socket = app.websocket().await?;
+
+    // The friend request is stored, even though the other user is not connected
+    app.post::<(), _>(format!("/api/friends/new/{}", other.id), None)
+        .await?;
+
+    app.login(&UserLogin {
+        email: "bar@bar.com".to_string(),
+        password: "foo".to_string(),
+    })
+    .await;
+
+    assert_eq!(1, unread(&app).await?);
+    let request = &notifications(&app).await?[0];
+    assert_eq!(NotificationKind::FriendRequest, request.kind);
+    assert_eq!(Some(app.user.id), request.actor_id);
+    assert_eq!(Some("foo".to_string()), request.actor_name);
+    assert!(!request.read);
+
+    app.post::<(), _>(format!("/api/friends/accept/{}", app.user.id), None)
+        .await?;
+
+    // The requester is connected, so they get it right away
+    let FoodieMessageType::Notification(accepted) = next_message(&mut foo_socket).await? else {
+        panic!("Expected a notification");
+    };
+    assert_eq!(NotificationKind::FriendRequestAccepted, accepted.kind);
+    assert_eq!(Some(other.id), accepted.actor_id);
+
+    for visibility in [RecipeVisibility::Private, RecipeVisibility::Friends] {
+        app.post(
+            "/api/recipes?allow_duplicate=true",
+            Some(&CreateRecipe {
+                visibility,
+                ..recipe_with_ingredients(&["Tomato"])
+            }),
+        )
+        .await?;
+    }
+
+    app.login(&UserLogin {
+        email: TEST_EMAIL.to_string(),
+        password: TEST_PASSWORD.to_string(),
+    })
+    .await;
+
+    // Friends are only told about the recipes they can see
+    let recipes = app.get("/api/recipes").await?.json::<Vec<Recipe>>().await?;
+    let notifications = notifications(&app).await?;
+    assert_eq!(
+        vec![
+            NotificationKind::FriendRecipe,
+            NotificationKind::FriendRequestAccepted
+        ],
+        notifications.iter().map(|it| it.kind).collect::<Vec<_>>()
+    );
+    assert_eq!(Some(recipes[0].id), notifications[0].recipe_id);
+    assert_eq!(2, unread(&app).await?);
+
+    let unread_after = app
+        .post(
+            "/api/notifications/read",
+            Some(&ReadNotifications {
+                ids: vec![notifications[1].id],
+            }),
+        )
+        .await?
+        .json::<UnreadNotifications>()
+        .await?;
+    assert_eq!(1, unread_after.count);
+
+    app.post(
+        "/api/notifications/read",
+        Some(&ReadNotifications::default()),
+    )
+    .await?;
+    assert_eq!(0, unread(&app).await?);
+
+    Ok(())
+}
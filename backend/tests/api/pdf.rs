@@ -0,0 +1,96 @@
+use common::{
+    recipe::{CreateRecipe, CreateRecipeIngredient, Unit},
+    shopping_list::CreateShoppingList,
+};
+use reqwest::{header, StatusCode};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+use crate::TestApp;
+
+fn pdf_text(bytes: &[u8]) -> Result<String, anyhow::Error> {
+    let document = lopdf::Document::load_mem(bytes)?;
+    let pages = document.get_pages().into_keys().collect::<Vec<_>>();
+    Ok(document.extract_text(&pages)?)
+}
+
+fn pancakes() -> CreateRecipe {
+    CreateRecipe {
+        name: "Pancakes".to_string(),
+        servings: 4,
+        instructions: Some(vec!["Whisk everything".to_string(), "Fry".to_string()]),
+        ingredients: vec![CreateRecipeIngredient {
+            name: "Flour".to_string(),
+            amount: Some(Decimal::from(300)),
+            unit: Some(Unit::Gram),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_recipe_pdf(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let recipe = app.create_recipe(&pancakes()).await?;
+
+    let response = app
+        .get(format!("/api/recipes/{}/pdf?servings=6", recipe.id))
+        .await?;
+    assert_eq!(StatusCode::OK, response.status());
+    assert_eq!(
+        Some("application/pdf"),
+        response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|it| it.to_str().ok())
+    );
+
+    let text = pdf_text(&response.bytes().await?)?;
+    assert!(text.contains("Pancakes"));
+    assert!(text.contains("6 servings"));
+    assert!(text.contains("450 Gram Flour"));
+    assert!(text.contains("Whisk everything"));
+
+    let response = app
+        .get(format!("/api/recipes/{}/pdf?servings=0", recipe.id))
+        .await?;
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    let response = app.get("/api/recipes/9999/pdf").await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_shopping_list_pdf(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let recipe = app.create_recipe(&pancakes()).await?;
+
+    let response = app
+        .post(
+            "/api/shopping-list/pdf",
+            Some(&CreateShoppingList {
+                recipe_ids: vec![recipe.id, recipe.id],
+            }),
+        )
+        .await?;
+    assert_eq!(StatusCode::OK, response.status());
+
+    let text = pdf_text(&response.bytes().await?)?;
+    assert!(text.contains("Shopping list"));
+    assert!(text.contains("300 Gram Flour"));
+
+    let response = app
+        .post(
+            "/api/shopping-list/pdf",
+            Some(&CreateShoppingList {
+                recipe_ids: vec![9999],
+            }),
+        )
+        .await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use common::{
+    recipe::{CreateRecipe, Recipe, RecipeVisibility},
+    user::{
+        CreateUser, FriendProfile, Profile, UnitSystem, UpdateAvatar, UpdateProfile, User,
+        UserLogin,
+    },
+};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{ingredient::recipe_with_ingredients, TestApp, TEST_EMAIL, TEST_NAME, TEST_PASSWORD};
+
+#[sqlx::test(migrations = false)]
+async fn test_update_profile(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    let profile = app.get("/api/me/profile").await?.json::<Profile>().await?;
+    assert_eq!(TEST_NAME, profile.name);
+    assert_eq!(None, profile.display_name);
+    assert_eq!(UnitSystem::Metric, profile.preferred_units);
+
+    let profile = app
+        .put(
+            "/api/me/profile",
+            &UpdateProfile {
+                display_name: Some(" Taco lover ".to_string()),
+                bio: Some("".to_string()),
+                preferred_units: UnitSystem::Imperial,
+            },
+        )
+        .await?
+        .json::<Profile>()
+        .await?;
+    assert_eq!(Some("Taco lover".to_string()), profile.display_name);
+    assert_eq!(None, profile.bio);
+    assert_eq!(UnitSystem::Imperial, profile.preferred_units);
+
+    // The display name is shown instead of the name
+    let me = app.get("/api/me").await?.json::<User>().await?;
+    assert_eq!("Taco lover", me.name);
+
+    let response = app
+        .put(
+            "/api/me/profile",
+            &UpdateProfile {
+                bio: Some("a".repeat(501)),
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_avatar(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    let avatar = Uuid::new_v4();
+    let profile = app
+        .put("/api/me/avatar", &UpdateAvatar { id: avatar })
+        .await?
+        .json::<Profile>()
+        .await?;
+    assert!(profile.avatar.unwrap().contains(&avatar.to_string()));
+
+    // An image of a recipe can't be the avatar, since it would be deleted when it is replaced
+    let img = Uuid::new_v4();
+    app.post(
+        "/api/recipes",
+        Some(&CreateRecipe {
+            img: Some(img),
+            ..recipe_with_ingredients(&["Tortilla"])
+        }),
+    )
+    .await?;
+    let response = app.put("/api/me/avatar", &UpdateAvatar { id: img }).await?;
+    assert_eq!(StatusCode::CONFLICT, response.status());
+
+    let profile = app
+        .delete("/api/me/avatar")
+        .await?
+        .json::<Profile>()
+        .await?;
+    assert_eq!(None, profile.avatar);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_friend_profile(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    let shared = app
+        .post(
+            "/api/recipes",
+            Some(&CreateRecipe {
+                name: "Tacos".to_string(),
+                visibility: RecipeVisibility::Friends,
+                ..recipe_with_ingredients(&["Tortilla"])
+            }),
+        )
+        .await?
+        .json::<Recipe>()
+        .await?;
+    app.post(
+        "/api/recipes",
+        Some(&CreateRecipe {
+            name: "Secret sauce".to_string(),
+            visibility: RecipeVisibility::Private,
+            ..recipe_with_ingredients(&["Salsa"])
+        }),
+    )
+    .await?;
+    app.put(
+        "/api/me/profile",
+        &UpdateProfile {
+            bio: Some("I like tacos".to_string()),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let other = app
+        .create_user(&CreateUser {
+            name: "bar".to_string(),
+            email: "bar@bar.com".to_string(),
+            password: TEST_PASSWORD.to_string(),
+        })
+        .await?;
+    app.post::<(), _>(format!("/api/friends/new/{}", other.id), None)
+        .await?;
+
+    app.login(&UserLogin {
+        email: "bar@bar.com".to_string(),
+        password: TEST_PASSWORD.to_string(),
+    })
+    .await;
+
+    // Only friends can see the profile
+    let response = app
+        .get(format!("/api/users/{}/profile", app.user.id))
+        .await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    app.post::<(), _>(format!("/api/friends/accept/{}", app.user.id), None)
+        .await?;
+
+    let friend = app
+        .get(format!("/api/users/{}/profile", app.user.id))
+        .await?
+        .json::<FriendProfile>()
+        .await?;
+    assert_eq!(Some("I like tacos".to_string()), friend.profile.bio);
+    assert_eq!(
+        vec![shared.id],
+        friend.recipes.iter().map(|it| it.id).collect::<Vec<_>>()
+    );
+
+    // Private recipes are still shown on your own profile
+    app.login(&UserLogin {
+        email: TEST_EMAIL.to_string(),
+        password: TEST_PASSWORD.to_string(),
+    })
+    .await;
+    let own = app
+        .get(format!("/api/users/{}/profile", app.user.id))
+        .await?
+        .json::<FriendProfile>()
+        .await?;
+    assert_eq!(2, own.recipes.len());
+
+    Ok(())
+}
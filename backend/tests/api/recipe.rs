@@ -1,17 +1,25 @@
 use backend::entities::{
-    friendships, ingredients, recipe_ingredients, recipes, sea_orm_active_enums::FriendshipStatus,
+    friendships, ingredients, recipe_ingredients, recipes,
+    sea_orm_active_enums::{self, FriendshipStatus},
 };
-use chrono::NaiveTime;
 use common::{
-    recipe::{CreateRecipe, CreateRecipeIngredient, Recipe, RecipeVisibility, Unit},
+    ingredient::{Allergen, Diet, UpdateIngredientDietary},
+    recipe::{
+        group_ingredients, CreateRecipe, CreateRecipeIngredient, Duration, Recipe,
+        RecipeDifficulty, RecipeVisibility, Unit,
+    },
     user::{CreateUser, UserLogin},
 };
+use itertools::Itertools;
 use reqwest::StatusCode;
 use rust_decimal::Decimal;
 use sea_orm::{ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter};
 use sqlx::PgPool;
 
-use crate::TestApp;
+use crate::{
+    ingredient::{create_global_ingredient, recipe_with_ingredients},
+    TestApp,
+};
 
 async fn get_pizza_recipe() -> Result<CreateRecipe, anyhow::Error> {
     let ingredients = [
@@ -19,16 +27,19 @@ async fn get_pizza_recipe() -> Result<CreateRecipe, anyhow::Error> {
             name: "Flour".to_string(),
             unit: Some(Unit::Kilogram),
             amount: Some(Decimal::from(1)),
+            ..Default::default()
         },
         CreateRecipeIngredient {
             name: "Yiest".to_string(),
             unit: Some(Unit::Gram),
             amount: Some(Decimal::from(20)),
+            ..Default::default()
         },
         CreateRecipeIngredient {
             name: "Water".to_string(),
             unit: Some(Unit::Deciliter),
             amount: Some(Decimal::from(6)),
+            ..Default::default()
         },
     ];
 
@@ -38,10 +49,13 @@ async fn get_pizza_recipe() -> Result<CreateRecipe, anyhow::Error> {
         img: None,
         instructions: None,
         ingredients: ingredients.to_vec(),
-        baking_time: NaiveTime::from_hms_opt(0, 20, 0),
-        prep_time: NaiveTime::from_hms_opt(4, 0, 0),
+        baking_time: Some(Duration::from_minutes(20)),
+        prep_time: Some(Duration::from_hours(4)),
+        cooking_time: None,
+        resting_time: None,
         servings: 4,
         visibility: RecipeVisibility::Friends,
+        ..Default::default()
     })
 }
 
@@ -51,16 +65,19 @@ async fn get_pancake_recipe() -> Result<CreateRecipe, anyhow::Error> {
             name: "Flour".to_string(),
             unit: Some(Unit::Kilogram),
             amount: Some(Decimal::from(1)),
+            ..Default::default()
         },
         CreateRecipeIngredient {
             name: "Milk".to_string(),
             unit: Some(Unit::Cup),
             amount: Some(Decimal::from(1)),
+            ..Default::default()
         },
         CreateRecipeIngredient {
             name: "Egg".to_string(),
             unit: None,
             amount: Some(Decimal::from(1)),
+            ..Default::default()
         },
     ];
 
@@ -70,10 +87,13 @@ async fn get_pancake_recipe() -> Result<CreateRecipe, anyhow::Error> {
         img: None,
         instructions: None,
         ingredients: ingredients.to_vec(),
-        baking_time: NaiveTime::from_hms_opt(0, 10, 0),
-        prep_time: NaiveTime::from_hms_opt(1, 0, 0),
+        baking_time: Some(Duration::from_minutes(10)),
+        prep_time: Some(Duration::from_hours(1)),
+        cooking_time: None,
+        resting_time: None,
         servings: 4,
         visibility: RecipeVisibility::Friends,
+        ..Default::default()
     })
 }
 
@@ -83,16 +103,19 @@ async fn get_toast_recipe() -> Result<CreateRecipe, anyhow::Error> {
             name: "Bread".to_string(),
             unit: None,
             amount: Some(Decimal::from(2)),
+            ..Default::default()
         },
         CreateRecipeIngredient {
             name: "Cheese".to_string(),
             unit: Some(Unit::Gram),
             amount: Some(Decimal::from(100)),
+            ..Default::default()
         },
         CreateRecipeIngredient {
             name: "Butter".to_string(),
             unit: None,
             amount: None,
+            ..Default::default()
         },
     ];
 
@@ -102,10 +125,13 @@ async fn get_toast_recipe() -> Result<CreateRecipe, anyhow::Error> {
         img: None,
         instructions: None,
         ingredients: ingredients.to_vec(),
-        baking_time: NaiveTime::from_hms_opt(0, 11, 0),
-        prep_time: NaiveTime::from_hms_opt(0, 10, 0),
+        baking_time: Some(Duration::from_minutes(11)),
+        prep_time: Some(Duration::from_minutes(10)),
+        cooking_time: None,
+        resting_time: None,
         servings: 2,
         visibility: RecipeVisibility::Friends,
+        ..Default::default()
     })
 }
 
@@ -435,3 +461,311 @@ async fn test_get_shared_recipes_private_no_access(pool: PgPool) -> Result<(), a
     assert_eq!(0, recipes.len());
     Ok(())
 }
+
+#[sqlx::test(migrations = false)]
+async fn test_recipe_dietary_flags(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    for (name, allergens, vegetarian, vegan) in [
+        ("Flour", vec![Allergen::Gluten], true, true),
+        ("Butter", vec![Allergen::Lactose], true, false),
+        ("Lettuce", vec![], false, true),
+    ] {
+        let id = create_global_ingredient(&app, name).await?;
+        app.put(
+            format!("/api/ingredients/{id}/dietary"),
+            &UpdateIngredientDietary {
+                allergens,
+                vegetarian,
+                vegan,
+            },
+        )
+        .await?;
+    }
+
+    let bread = app
+        .post(
+            "/api/recipes",
+            Some(&CreateRecipe {
+                name: "Bread".to_string(),
+                ..recipe_with_ingredients(&["Flour", "Butter"])
+            }),
+        )
+        .await?
+        .json::<Recipe>()
+        .await?;
+
+    assert_eq!(vec![Allergen::Gluten, Allergen::Lactose], bread.allergens);
+    assert_eq!(vec![Diet::Vegetarian], bread.diets);
+
+    let salad = app
+        .post("/api/recipes", Some(&recipe_with_ingredients(&["Lettuce"])))
+        .await?
+        .json::<Recipe>()
+        .await?;
+
+    assert!(salad.allergens.is_empty());
+    assert_eq!(vec![Diet::Vegetarian, Diet::Vegan], salad.diets);
+
+    // Without ingredients the recipe is not known to follow any diet
+    recipes::Entity::insert(recipes::ActiveModel {
+        user_id: Set(app.user.id),
+        name: Set("Water".to_string()),
+        servings: Set(1),
+        visibility: Set(sea_orm_active_enums::RecipeVisibility::Private),
+        ..Default::default()
+    })
+    .exec(&app.pool)
+    .await?;
+
+    let names = |recipes: Vec<Recipe>| {
+        recipes
+            .into_iter()
+            .map(|it| it.name)
+            .sorted()
+            .collect::<Vec<_>>()
+    };
+
+    for (query, expected) in [
+        ("", vec!["Bread", "Salad", "Water"]),
+        ("?exclude=Gluten", vec!["Salad", "Water"]),
+        ("?exclude=Nuts,Shellfish", vec!["Bread", "Salad", "Water"]),
+        ("?diet=Vegetarian", vec!["Bread", "Salad"]),
+        ("?diet=Vegan&exclude=Lactose", vec!["Salad"]),
+    ] {
+        let recipes = app
+            .get(format!("/api/recipes{query}"))
+            .await?
+            .json::<Vec<Recipe>>()
+            .await?;
+        assert_eq!(expected, names(recipes), "{query}");
+    }
+
+    let res = app.get("/api/recipes?exclude=Chocolate").await?;
+    assert_eq!(StatusCode::BAD_REQUEST, res.status());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_recipe_ingredient_groups(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    let line = |name: &str, group: Option<&str>, note: Option<&str>, optional: bool| {
+        CreateRecipeIngredient {
+            name: name.to_string(),
+            note: note.map(|it| it.to_string()),
+            optional,
+            group: group.map(|it| it.to_string()),
+            ..Default::default()
+        }
+    };
+
+    let recipe = CreateRecipe {
+        name: "Lasagna".to_string(),
+        ingredients: vec![
+            line(
+                "Onion",
+                Some("For the sauce"),
+                Some("finely chopped"),
+                false,
+            ),
+            line("Tomato", Some("For the sauce"), None, false),
+            line("Flour", Some("For the dough"), None, false),
+            line("Onion", Some("For the dough"), Some(" "), false),
+            line("Basil", None, None, true),
+        ],
+        ..Default::default()
+    };
+
+    let created = app
+        .post("/api/recipes", Some(&recipe))
+        .await?
+        .json::<Recipe>()
+        .await?;
+
+    let recipe = app
+        .get(format!("/api/recipes/{}", created.id))
+        .await?
+        .json::<Recipe>()
+        .await?;
+
+    let lines = recipe
+        .ingredients
+        .iter()
+        .map(|it| {
+            (
+                it.position,
+                it.ingredient_name.as_str(),
+                it.note.as_deref(),
+                it.optional,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![
+            (0, "Onion", Some("finely chopped"), false),
+            (1, "Tomato", None, false),
+            (2, "Flour", None, false),
+            (3, "Onion", None, false),
+            (4, "Basil", None, true),
+        ],
+        lines
+    );
+    assert_eq!(
+        recipe.ingredients[0].ingredient_id,
+        recipe.ingredients[3].ingredient_id
+    );
+
+    let groups = group_ingredients(&recipe.ingredients)
+        .into_iter()
+        .map(|(group, items)| (group, items.len()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![
+            (Some("For the sauce".to_string()), 2),
+            (Some("For the dough".to_string()), 2),
+            (None, 1),
+        ],
+        groups
+    );
+
+    let recipes = app.get("/api/recipes").await?.json::<Vec<Recipe>>().await?;
+    assert_eq!(recipe.ingredients, recipes[0].ingredients);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_recipe_durations(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    // A sourdough takes more than a day, which couldn't be represented as a time of day
+    let sourdough = CreateRecipe {
+        name: "Sourdough".to_string(),
+        prep_time: Some(Duration::from_minutes(30)),
+        cooking_time: None,
+        baking_time: Some(Duration::from_minutes(45)),
+        resting_time: Some(Duration::from_hours(26)),
+        ..get_pizza_recipe().await?
+    };
+    let recipe = app
+        .post("/api/recipes", Some(&sourdough))
+        .await?
+        .json::<Recipe>()
+        .await?;
+
+    assert_eq!(Some(Duration::from_hours(26)), recipe.resting_time);
+    assert_eq!(
+        Some(Duration::from_minutes(26 * 60 + 75)),
+        recipe.total_time
+    );
+    assert_eq!(
+        "1 d 3 h 15 min",
+        recipe.total_time.unwrap().to_string().as_str()
+    );
+
+    let model = recipes::Entity::find_by_id(recipe.id)
+        .one(&app.pool)
+        .await?
+        .unwrap();
+    assert_eq!(Some(26 * 60), model.resting_minutes);
+
+    let toast = get_toast_recipe().await?;
+    let recipe = app
+        .post("/api/recipes", Some(&toast))
+        .await?
+        .json::<Recipe>()
+        .await?;
+    assert_eq!(Some(Duration::from_minutes(21)), recipe.total_time);
+
+    let no_times = CreateRecipe {
+        name: "Pizza without times".to_string(),
+        prep_time: None,
+        baking_time: None,
+        ..get_pizza_recipe().await?
+    };
+    let recipe = app
+        .post("/api/recipes", Some(&no_times))
+        .await?
+        .json::<Recipe>()
+        .await?;
+    assert_eq!(None, recipe.total_time);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_recipe_metadata(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    let cookies = CreateRecipe {
+        name: "Cookies".to_string(),
+        servings: 24,
+        yield_unit: Some("cookies".to_string()),
+        source_url: Some("https://example.com/cookies".to_string()),
+        source_name: Some("Grandma".to_string()),
+        cuisine: Some("American".to_string()),
+        difficulty: Some(RecipeDifficulty::Easy),
+        ..get_pancake_recipe().await?
+    };
+    let recipe = app
+        .post("/api/recipes", Some(&cookies))
+        .await?
+        .json::<Recipe>()
+        .await?;
+
+    assert_eq!(Some("cookies"), recipe.yield_unit.as_deref());
+    assert_eq!(Some("Grandma"), recipe.source_name.as_deref());
+    assert_eq!(Some(RecipeDifficulty::Easy), recipe.difficulty);
+
+    let pizza = CreateRecipe {
+        cuisine: Some("Italian".to_string()),
+        difficulty: Some(RecipeDifficulty::Medium),
+        ..get_pizza_recipe().await?
+    };
+    app.post("/api/recipes", Some(&pizza)).await?;
+    app.post("/api/recipes", Some(&get_toast_recipe().await?))
+        .await?;
+
+    let italian = app
+        .get("/api/recipes?cuisine=italian")
+        .await?
+        .json::<Vec<Recipe>>()
+        .await?;
+    assert_eq!(
+        vec!["My pizza"],
+        italian
+            .iter()
+            .map(|it| it.name.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    let easy = app
+        .get("/api/recipes?difficulty=Easy")
+        .await?
+        .json::<Vec<Recipe>>()
+        .await?;
+    assert_eq!(
+        vec!["Cookies"],
+        easy.iter().map(|it| it.name.as_str()).collect::<Vec<_>>()
+    );
+
+    let cuisines = app
+        .get("/api/recipes/cuisines")
+        .await?
+        .json::<Vec<String>>()
+        .await?;
+    assert_eq!(vec!["American", "Italian"], cuisines);
+
+    let invalid_source = CreateRecipe {
+        source_url: Some("javascript:alert(1)".to_string()),
+        ..get_toast_recipe().await?
+    };
+    let response = app.post("/api/recipes", Some(&invalid_source)).await?;
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use common::{
+    ingredient::{IngredientCategory, UpdateIngredientCategory},
+    recipe::{CreateRecipe, CreateRecipeIngredient, Recipe, Unit},
+    shopping_list::{CreateShoppingList, ShoppingList},
+};
+use reqwest::StatusCode;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+
+use crate::{ingredient::create_global_ingredient, TestApp, TEST_PASSWORD};
+
+fn line(name: &str, amount: i64, unit: Unit) -> CreateRecipeIngredient {
+    CreateRecipeIngredient {
+        name: name.to_string(),
+        amount: Some(Decimal::from(amount)),
+        unit: Some(unit),
+        ..Default::default()
+    }
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_shopping_list_grouped_by_aisle(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    for (name, category) in [
+        ("Flour", IngredientCategory::DryGoods),
+        ("Milk", IngredientCategory::Dairy),
+    ] {
+        let id = create_global_ingredient(&app, name).await?;
+        app.put(
+            format!("/api/ingredients/{id}/default-category"),
+            &UpdateIngredientCategory { category },
+        )
+        .await?;
+    }
+
+    let mut recipe_ids = vec![];
+    for ingredients in [
+        vec![
+            line("Flour", 500, Unit::Gram),
+            line("Milk", 3, Unit::Deciliter),
+        ],
+        vec![line("Flour", 200, Unit::Gram), line("Milk", 1, Unit::Cup)],
+    ] {
+        let recipe = app
+            .post(
+                "/api/recipes?allow_duplicate=true",
+                Some(&CreateRecipe {
+                    name: "Pancakes".to_string(),
+                    servings: 4,
+                    ingredients,
+                    ..Default::default()
+                }),
+            )
+            .await?
+            .json::<Recipe>()
+            .await?;
+        recipe_ids.push(recipe.id);
+    }
+
+    app.put("/api/ingredients/aisles", &vec![IngredientCategory::Dairy])
+        .await?;
+
+    let shopping_list = app
+        .post(
+            "/api/shopping-list",
+            Some(&CreateShoppingList {
+                recipe_ids: recipe_ids.clone(),
+            }),
+        )
+        .await?
+        .json::<ShoppingList>()
+        .await?;
+
+    let categories = shopping_list
+        .sections
+        .iter()
+        .map(|it| it.category)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![IngredientCategory::Dairy, IngredientCategory::DryGoods],
+        categories
+    );
+
+    let amounts = shopping_list
+        .sections
+        .iter()
+        .flat_map(|it| &it.items)
+        .map(|it| (it.name.as_str(), it.unit, it.amount))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            ("Milk", Some(Unit::Cup), Some(Decimal::from(1))),
+            ("Milk", Some(Unit::Deciliter), Some(Decimal::from(3))),
+            ("Flour", Some(Unit::Gram), Some(Decimal::from(700))),
+        ],
+        amounts
+    );
+
+    app.create_user_named("bar").await?;
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+
+    let res = app
+        .post(
+            "/api/shopping-list",
+            Some(&CreateShoppingList { recipe_ids }),
+        )
+        .await?;
+    assert_eq!(StatusCode::NOT_FOUND, res.status());
+
+    Ok(())
+}
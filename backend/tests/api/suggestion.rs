@@ -0,0 +1,123 @@
+use common::{
+    feed::CookRecipe,
+    recipe::{CreateRecipe, Duration, RecipeSuggestions},
+};
+use itertools::Itertools;
+use sqlx::PgPool;
+
+use crate::{ingredient::recipe_with_ingredients, TestApp};
+
+async fn suggest(app: &TestApp, query: &str) -> Result<RecipeSuggestions, anyhow::Error> {
+    let suggestions = app
+        .get(format!("/api/recipes/suggestions?{query}"))
+        .await?
+        .json::<RecipeSuggestions>()
+        .await?;
+    Ok(suggestions)
+}
+
+fn names(suggestions: &RecipeSuggestions) -> Vec<&str> {
+    suggestions
+        .recipes
+        .iter()
+        .map(|it| it.name.as_str())
+        .collect()
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_suggestions(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    for (name, minutes, cuisine) in [
+        ("Pizza", 90, "Italian"),
+        ("Pasta", 20, "Italian"),
+        ("Taco", 30, "Mexican"),
+        ("Toast", 5, "British"),
+    ] {
+        app.create_recipe(&CreateRecipe {
+            name: name.to_string(),
+            prep_time: Some(Duration::from_minutes(minutes)),
+            cuisine: Some(cuisine.to_string()),
+            ..recipe_with_ingredients(&["Flour"])
+        })
+        .await?;
+    }
+    app.create_recipe(&CreateRecipe {
+        name: "Salad".to_string(),
+        ..recipe_with_ingredients(&["Lettuce"])
+    })
+    .await?;
+
+    let suggestions = suggest(&app, "").await?;
+    assert_eq!(1, suggestions.recipes.len());
+
+    // The same seed gives the same pick
+    let first = suggest(&app, "count=3&seed=42").await?;
+    let second = suggest(&app, "count=3&seed=42").await?;
+    assert_eq!(42, first.seed);
+    assert_eq!(names(&first), names(&second));
+    assert_eq!(3, names(&first).iter().unique().count());
+
+    // Can't pick more recipes than there are
+    let suggestions = suggest(&app, "count=10").await?;
+    assert_eq!(5, suggestions.recipes.len());
+
+    // Recipes without any times are left out when filtering on time
+    let mut suggestions = suggest(&app, "count=10&max_time=30").await?;
+    suggestions.recipes.sort_by_key(|it| it.id);
+    assert_eq!(vec!["Pasta", "Taco", "Toast"], names(&suggestions));
+
+    let mut suggestions = suggest(&app, "count=10&cuisine=italian&max_time=60").await?;
+    suggestions.recipes.sort_by_key(|it| it.id);
+    assert_eq!(vec!["Pasta"], names(&suggestions));
+
+    let suggestions = suggest(&app, "count=10&cuisine=French").await?;
+    assert!(suggestions.recipes.is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_suggestions_are_weighted(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let mut recipes = vec![];
+    for name in ["Pizza", "Pasta", "Taco", "Toast"] {
+        let recipe = app
+            .create_recipe(&CreateRecipe {
+                name: name.to_string(),
+                ..recipe_with_ingredients(&["Flour"])
+            })
+            .await?;
+        recipes.push(recipe);
+    }
+
+    app.put(format!("/api/recipes/{}/favorite", recipes[0].id), &())
+        .await?;
+    // Looking at a recipe makes it less likely to be suggested for a while
+    app.get(format!("/api/recipes/{}", recipes[1].id)).await?;
+    // A recipe rated above 3 on average is more likely to be suggested
+    for rating in [5, 3] {
+        app.post(
+            format!("/api/recipes/{}/cooked", recipes[2].id),
+            Some(&CookRecipe {
+                rating: Some(rating),
+            }),
+        )
+        .await?;
+    }
+
+    let mut picked = vec![0; recipes.len()];
+    for seed in 0..40 {
+        let suggestions = suggest(&app, &format!("seed={seed}")).await?;
+        let index = recipes
+            .iter()
+            .position(|it| it.id == suggestions.recipes[0].id)
+            .unwrap();
+        picked[index] += 1;
+    }
+
+    assert!(picked[0] > picked[2] && picked[0] > picked[3]);
+    assert!(picked[1] < picked[2] && picked[1] < picked[3]);
+    assert!(picked[2] > picked[3]);
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use backend::entities::{friendships, sea_orm_active_enums::FriendshipStatus};
+use common::{
+    recipe::{CreateRecipe, Recipe, RecipeVisibility},
+    swipe::{CreateSwipeSession, Swipe, SwipeMatch, SwipeSession},
+    websocket::FoodieMessageType,
+};
+use futures_util::StreamExt;
+use reqwest::StatusCode;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+use sqlx::PgPool;
+
+use crate::{ingredient::recipe_with_ingredients, TestApp, TEST_EMAIL, TEST_PASSWORD};
+
+async fn swipe(
+    app: &TestApp,
+    session_id: i32,
+    recipe_id: i32,
+    liked: bool,
+) -> Result<Option<SwipeMatch>, anyhow::Error> {
+    let swipe_match = app
+        .put(
+            format!("/api/swipe/{session_id}/recipes/{recipe_id}"),
+            &Swipe { liked },
+        )
+        .await?
+        .json::<Option<SwipeMatch>>()
+        .await?;
+    Ok(swipe_match)
+}
+
+async fn deck(app: &TestApp, session_id: i32) -> Result<Vec<String>, anyhow::Error> {
+    let recipes = app
+        .get(format!("/api/swipe/{session_id}/deck"))
+        .await?
+        .json::<Vec<Recipe>>()
+        .await?;
+    Ok(recipes.into_iter().map(|it| it.name).collect())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_swipe_session(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let pizza = app
+        .create_recipe(&CreateRecipe {
+            name: "Pizza".to_string(),
+            visibility: RecipeVisibility::Friends,
+            ..recipe_with_ingredients(&["Flour"])
+        })
+        .await?;
+    let taco = app
+        .create_recipe(&CreateRecipe {
+            name: "Taco".to_string(),
+            visibility: RecipeVisibility::Friends,
+            ..recipe_with_ingredients(&["Flour"])
+        })
+        .await?;
+    app.create_recipe(&CreateRecipe {
+        name: "Secret".to_string(),
+        visibility: RecipeVisibility::Private,
+        ..recipe_with_ingredients(&["Flour"])
+    })
+    .await?;
+
+    let bar = app.create_user_named("bar").await?;
+    friendships::Entity::insert(friendships::ActiveModel {
+        requester_id: Set(app.user.id),
+        recipient_id: Set(bar.id),
+        status: Set(FriendshipStatus::Accepted),
+        ..Default::default()
+    })
+    .exec(&app.pool)
+    .await?;
+
+    let session = app
+        .post(
+            "/api/swipe",
+            Some(&CreateSwipeSession {
+                name: "Tuesday".to_string(),
+                participant_ids: vec![bar.id],
+            }),
+        )
+        .await?
+        .json::<SwipeSession>()
+        .await?;
+    assert_eq!(2, session.participants.len());
+
+    // Private recipes can never be a match, so they are not in the deck
+    let mut names = deck(&app, session.id).await?;
+    names.sort();
+    assert_eq!(vec!["Pizza", "Taco"], names);
+
+    let response = app
+        .put(
+            format!("/api/swipe/{}/recipes/9999", session.id),
+            &Swipe { liked: true },
+        )
+        .await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    assert_eq!(None, swipe(&app, session.id, pizza.id, true).await?);
+    assert_eq!(None, swipe(&app, session.id, taco.id, false).await?);
+    assert!(deck(&app, session.id).await?.is_empty());
+
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+    let mut socket = app.websocket().await?;
+
+    // The recipe the other one said yes to comes first
+    assert_eq!(vec!["Pizza", "Taco"], deck(&app, session.id).await?);
+
+    assert_eq!(None, swipe(&app, session.id, taco.id, true).await?);
+    let swipe_match = swipe(&app, session.id, pizza.id, true).await?;
+    assert_eq!(Some(pizza.id), swipe_match.map(|it| it.recipe_id));
+
+    let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+        .await?
+        .unwrap()?;
+    let FoodieMessageType::SwipeMatch(swipe_match) = message.to_text()?.parse()? else {
+        panic!("Expected a match");
+    };
+    assert_eq!("Pizza", swipe_match.name);
+
+    app.login_as(TEST_EMAIL, TEST_PASSWORD).await;
+    let session = app
+        .get(format!("/api/swipe/{}", session.id))
+        .await?
+        .json::<SwipeSession>()
+        .await?;
+    assert_eq!(
+        vec!["Pizza"],
+        session
+            .matches
+            .iter()
+            .map(|it| it.name.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_swipe_session_validation(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    let stranger = app.create_user_named("bar").await?;
+
+    let response = app
+        .post("/api/swipe", Some(&CreateSwipeSession::default()))
+        .await?;
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    let response = app
+        .post(
+            "/api/swipe",
+            Some(&CreateSwipeSession {
+                participant_ids: vec![stranger.id],
+                ..Default::default()
+            }),
+        )
+        .await?;
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    Ok(())
+}
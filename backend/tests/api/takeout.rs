@@ -0,0 +1,180 @@
+use std::io::{Cursor, Write};
+
+use backend::entities::{friendships, sea_orm_active_enums::FriendshipStatus};
+use common::{
+    ingredient::{Allergen, Ingredient, UpdateIngredientDietary},
+    recipe::{CreateRecipe, CreateRecipeIngredient, Recipe, Unit},
+    takeout::{TakeoutFriendship, TakeoutImport, TakeoutManifest, TakeoutRecipe},
+};
+use reqwest::StatusCode;
+use rust_decimal::Decimal;
+use sea_orm::{EntityTrait, Set};
+use sqlx::PgPool;
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+use backend::api::takeout::MAX_TAKEOUT_SIZE;
+
+use crate::{ingredient::create_global_ingredient, TestApp, TEST_PASSWORD};
+
+async fn import(app: &TestApp, archive: Vec<u8>) -> Result<reqwest::Response, anyhow::Error> {
+    Ok(app
+        .client
+        .post(format!("{}/api/takeout", app.address))
+        .body(archive)
+        .send()
+        .await?)
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_takeout(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let flour = create_global_ingredient(&app, "Flour").await?;
+
+    let recipe = app
+        .post(
+            "/api/recipes",
+            Some(&CreateRecipe {
+                name: "Pizza".to_string(),
+                servings: 4,
+                instructions: Some(vec!["Bake it".to_string()]),
+                ingredients: vec![
+                    CreateRecipeIngredient {
+                        name: "Flour".to_string(),
+                        unit: Some(Unit::Gram),
+                        amount: Some(Decimal::new(500, 0)),
+                        ..Default::default()
+                    },
+                    CreateRecipeIngredient {
+                        name: "Secret sauce".to_string(),
+                        note: Some("Lots of it".to_string()),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }),
+        )
+        .await?
+        .json::<Recipe>()
+        .await?;
+    let sauce = recipe.ingredients[1].ingredient_id;
+
+    app.put(
+        format!("/api/ingredients/{sauce}/dietary"),
+        &UpdateIngredientDietary {
+            allergens: vec![Allergen::Sesame],
+            vegetarian: true,
+            vegan: false,
+        },
+    )
+    .await?;
+
+    // Only the block made by the user is exported, not that someone else blocked them
+    for (name, blocked_by_user) in [("carol", true), ("dave", false)] {
+        let other = app.create_user_named(name).await?;
+        let (requester_id, recipient_id) = match blocked_by_user {
+            true => (app.user.id, other.id),
+            false => (other.id, app.user.id),
+        };
+        friendships::Entity::insert(friendships::ActiveModel {
+            requester_id: Set(requester_id),
+            recipient_id: Set(recipient_id),
+            status: Set(FriendshipStatus::Blocked),
+            requested_at: Set(chrono::Utc::now().fixed_offset()),
+            responded_at: Set(None),
+        })
+        .exec(&app.pool)
+        .await?;
+    }
+
+    let response = app.get("/api/takeout").await?;
+    assert_eq!(StatusCode::OK, response.status());
+    let archive = response.bytes().await?.to_vec();
+
+    let mut zip = ZipArchive::new(Cursor::new(archive.clone()))?;
+    let manifest: TakeoutManifest = serde_json::from_reader(zip.by_name("manifest.json")?)?;
+    assert_eq!("foo@foo.com", manifest.email);
+    let recipes: Vec<TakeoutRecipe> = serde_json::from_reader(zip.by_name("recipes.json")?)?;
+    assert_eq!(
+        vec![recipe.id],
+        recipes.iter().map(|it| it.id).collect::<Vec<_>>()
+    );
+    let friendships: Vec<TakeoutFriendship> =
+        serde_json::from_reader(zip.by_name("friendships.json")?)?;
+    assert_eq!(
+        vec!["carol@carol.com"],
+        friendships.iter().map(|it| &it.email).collect::<Vec<_>>()
+    );
+
+    app.create_user_named("bar").await?;
+    app.login_as("bar@bar.com", TEST_PASSWORD).await;
+
+    let imported = import(&app, archive).await?.json::<TakeoutImport>().await?;
+    assert_eq!(1, imported.recipes);
+    assert_eq!(2, imported.ingredients);
+
+    // The other user is not friends with the first one, so they only see the imported recipe
+    let recipes = app.get("/api/recipes").await?.json::<Vec<Recipe>>().await?;
+    assert_eq!(1, recipes.len());
+    let imported = &recipes[0];
+    assert_ne!(recipe.id, imported.id);
+    assert_ne!(recipe.user_id, imported.user_id);
+    assert_eq!("Pizza", imported.name);
+    assert_eq!(4, imported.servings);
+    assert_eq!(recipe.instructions, imported.instructions);
+    assert_eq!(recipe.ingredients.len(), imported.ingredients.len());
+    assert_eq!(Some(Decimal::new(500, 0)), imported.ingredients[0].amount);
+    assert_eq!(Some("Lots of it".to_string()), imported.ingredients[1].note);
+
+    // The catalog ingredient is reused, while the private one is recreated for the other user
+    assert_eq!(flour, imported.ingredients[0].ingredient_id);
+    let imported_sauce = imported.ingredients[1].ingredient_id;
+    assert_ne!(sauce, imported_sauce);
+
+    let ingredient = app
+        .get(format!("/api/ingredients/{imported_sauce}"))
+        .await?
+        .json::<Ingredient>()
+        .await?;
+    assert_eq!(Some(imported.user_id), ingredient.user_id);
+    assert_eq!(vec![Allergen::Sesame], ingredient.allergens);
+    assert!(ingredient.vegetarian);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_takeout_invalid_archive(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    let response = import(&app, b"not a zip".to_vec()).await?;
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    let recipes = app.get("/api/recipes").await?.json::<Vec<Recipe>>().await?;
+    assert!(recipes.is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_takeout_too_big_unpacked(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    // Zeros compress to next to nothing, so the archive is small until it is unpacked
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file(
+        format!("images/{}", uuid::Uuid::new_v4()),
+        SimpleFileOptions::default(),
+    )?;
+    let chunk = vec![0; 1024 * 1024];
+    for _ in 0..=MAX_TAKEOUT_SIZE / chunk.len() {
+        zip.write_all(&chunk)?;
+    }
+    let archive = zip.finish()?.into_inner();
+    assert!(archive.len() < MAX_TAKEOUT_SIZE / 100);
+
+    let response = import(&app, archive).await?;
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+    assert!(response.text().await?.contains("unpacked"));
+
+    Ok(())
+}
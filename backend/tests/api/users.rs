@@ -1,10 +1,17 @@
+use backend::entities::{
+    friendships, ingredients, recipe_ingredients, recipes, sea_orm_active_enums::RecipeVisibility,
+    users,
+};
 use common::{
     friendship::FriendshipStatus,
-    user::{CreateUser, UserWithRelation},
+    recipe::Recipe,
+    user::{CreateUser, UserLogin, UserWithRelation},
 };
+use reqwest::StatusCode;
+use sea_orm::{EntityTrait, Set};
 use sqlx::PgPool;
 
-use crate::TestApp;
+use crate::{ingredient::recipe_with_ingredients, TestApp, TEST_EMAIL, TEST_PASSWORD};
 
 #[sqlx::test(migrations = false)]
 async fn test_get_users_status(pool: PgPool) -> Result<(), anyhow::Error> {
@@ -34,3 +41,101 @@ async fn test_get_users_status(pool: PgPool) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[sqlx::test(migrations = false)]
+async fn test_delete_account(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+
+    // Another session of the same user, e.g. on another device
+    let other_device = reqwest::Client::builder().cookie_store(true).build()?;
+    other_device
+        .post(format!("{}/api/login", app.address))
+        .json(&UserLogin {
+            email: TEST_EMAIL.to_string(),
+            password: TEST_PASSWORD.to_string(),
+        })
+        .send()
+        .await?;
+
+    let recipe = app
+        .post(
+            "/api/recipes",
+            Some(&recipe_with_ingredients(&["Secret sauce", "Chili paste"])),
+        )
+        .await?
+        .json::<Recipe>()
+        .await?;
+    let secret_sauce = recipe.ingredients[0].ingredient_id;
+    let chili_paste = recipe.ingredients[1].ingredient_id;
+
+    let friend = app
+        .create_user(&CreateUser {
+            name: "bar".to_string(),
+            email: "bar@bar.com".to_string(),
+            password: "foo".to_string(),
+        })
+        .await?;
+    app.post::<(), _>(format!("/api/friends/new/{}", friend.id), None)
+        .await?;
+
+    // A recipe of another user that uses one of the private ingredients
+    let other_recipe = recipes::Entity::insert(recipes::ActiveModel {
+        user_id: Set(friend.id),
+        name: Set("Chili".to_string()),
+        servings: Set(2),
+        visibility: Set(RecipeVisibility::Friends),
+        ..Default::default()
+    })
+    .exec_with_returning(&app.pool)
+    .await?;
+    recipe_ingredients::Entity::insert(recipe_ingredients::ActiveModel {
+        recipe_id: Set(other_recipe.id),
+        ingredient_id: Set(chili_paste),
+        position: Set(0),
+        ..Default::default()
+    })
+    .exec(&app.pool)
+    .await?;
+
+    let response = app.delete("/api/me").await?;
+    assert_eq!(StatusCode::NO_CONTENT, response.status());
+
+    let response = app.get("/api/me").await?;
+    assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+    let response = other_device
+        .get(format!("{}/api/me", app.address))
+        .send()
+        .await?;
+    assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+
+    assert!(users::Entity::find_by_id(app.user.id)
+        .one(&app.pool)
+        .await?
+        .is_none());
+    assert!(recipes::Entity::find_by_id(recipe.id)
+        .one(&app.pool)
+        .await?
+        .is_none());
+    assert!(friendships::Entity::find().all(&app.pool).await?.is_empty());
+
+    // The ingredient only the deleted user used is gone, while the one used by others is kept
+    assert!(ingredients::Entity::find_by_id(secret_sauce)
+        .one(&app.pool)
+        .await?
+        .is_none());
+    let kept = ingredients::Entity::find_by_id(chili_paste)
+        .one(&app.pool)
+        .await?
+        .unwrap();
+    assert_eq!(None, kept.user_id);
+
+    app.login(&UserLogin {
+        email: TEST_EMAIL.to_string(),
+        password: TEST_PASSWORD.to_string(),
+    })
+    .await;
+    let response = app.get("/api/me").await?;
+    assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+
+    Ok(())
+}
@@ -0,0 +1,337 @@
+use std::time::Duration;
+
+use backend::entities::{friendships, sea_orm_active_enums::FriendshipStatus, voting_sessions};
+use common::{
+    recipe::CreateRecipe,
+    user::{CreateUser, UserLogin},
+    voting::{CreateVotingSession, VotingSession},
+    websocket::FoodieMessageType,
+};
+use futures_util::{SinkExt, StreamExt};
+use reqwest::StatusCode;
+use sea_orm::{ActiveValue::Set, EntityTrait, QuerySelect, TransactionTrait};
+use sqlx::PgPool;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use crate::{ingredient::recipe_with_ingredients, TestApp, TEST_EMAIL, TEST_PASSWORD};
+
+pub type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn vote(socket: &mut Socket, session_id: i32, recipe_id: i32) -> Result<(), anyhow::Error> {
+    let message = serde_json::to_string(&FoodieMessageType::Vote {
+        session_id,
+        recipe_id,
+    })?;
+    socket.send(Message::text(message)).await?;
+    Ok(())
+}
+
+pub async fn next_message(socket: &mut Socket) -> Result<FoodieMessageType, anyhow::Error> {
+    let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+        .await?
+        .ok_or(anyhow::anyhow!("The websocket was closed"))??;
+    Ok(message.to_text()?.parse()?)
+}
+
+fn votes(session: &VotingSession) -> Vec<(&str, i32)> {
+    session
+        .candidates
+        .iter()
+        .map(|it| (it.name.as_str(), it.votes))
+        .collect()
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_voting_session(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let pizza = app
+        .create_recipe(&CreateRecipe {
+            name: "Pizza".to_string(),
+            ..recipe_with_ingredients(&["Flour"])
+        })
+        .await?;
+    let taco = app
+        .create_recipe(&CreateRecipe {
+            name: "Taco".to_string(),
+            ..recipe_with_ingredients(&["Flour"])
+        })
+        .await?;
+
+    let bar = app
+        .create_user(&CreateUser {
+            name: "bar".to_string(),
+            email: "bar@bar.com".to_string(),
+            password: "foo".to_string(),
+        })
+        .await?;
+    friendships::Entity::insert(friendships::ActiveModel {
+        requester_id: Set(app.user.id),
+        recipient_id: Set(bar.id),
+        status: Set(FriendshipStatus::Accepted),
+        ..Default::default()
+    })
+    .exec(&app.pool)
+    .await?;
+
+    app.login(&UserLogin {
+        email: "bar@bar.com".to_string(),
+        password: "foo".to_string(),
+    })
+    .await;
+    let mut bar_socket = app.websocket().await?;
+
+    app.login(&UserLogin {
+        email: TEST_EMAIL.to_string(),
+        password: TEST_PASSWORD.to_string(),
+    })
+    .await;
+    let mut foo_socket = app.websocket().await?;
+
+    let session = app
+        .post(
+            "/api/voting",
+            Some(&CreateVotingSession {
+                name: "Friday".to_string(),
+                recipe_ids: vec![pizza.id, taco.id],
+                participant_ids: vec![bar.id],
+            }),
+        )
+        .await?
+        .json::<VotingSession>()
+        .await?;
+    assert_eq!(vec![("Pizza", 0), ("Taco", 0)], votes(&session));
+    assert_eq!(2, session.participants.len());
+
+    // The invite is stored as a notification as well
+    let FoodieMessageType::Notification(notification) = next_message(&mut bar_socket).await? else {
+        panic!("Expected a notification");
+    };
+    assert_eq!(Some(session.id), notification.voting_session_id);
+
+    let FoodieMessageType::VotingSessionInvite(invite) = next_message(&mut bar_socket).await?
+    else {
+        panic!("Expected an invite");
+    };
+    assert_eq!(session.id, invite.id);
+
+    // Everyone gets the new count of votes, but only sees their own vote
+    vote(&mut bar_socket, session.id, pizza.id).await?;
+    for (socket, own_vote) in [(&mut bar_socket, Some(pizza.id)), (&mut foo_socket, None)] {
+        let FoodieMessageType::VotingSessionUpdate(update) = next_message(socket).await? else {
+            panic!("Expected an update");
+        };
+        assert_eq!(vec![("Pizza", 1), ("Taco", 0)], votes(&update));
+        assert_eq!(own_vote, update.vote);
+    }
+
+    // Voting again replaces the earlier vote
+    vote(&mut foo_socket, session.id, taco.id).await?;
+    next_message(&mut foo_socket).await?;
+    vote(&mut foo_socket, session.id, pizza.id).await?;
+    next_message(&mut foo_socket).await?;
+
+    let session = app
+        .get(format!("/api/voting/{}", session.id))
+        .await?
+        .json::<VotingSession>()
+        .await?;
+    assert_eq!(vec![("Pizza", 2), ("Taco", 0)], votes(&session));
+    assert_eq!(Some(pizza.id), session.vote);
+
+    let closed = app
+        .post(format!("/api/voting/{}/close", session.id), None::<&()>)
+        .await?
+        .json::<VotingSession>()
+        .await?;
+    assert!(closed.closed_at.is_some());
+    assert_eq!(Some(pizza.id), closed.winner_recipe_id);
+
+    for socket in [&mut bar_socket, &mut foo_socket] {
+        let mut message = next_message(socket).await?;
+        // Skip the updates from the last votes
+        while let FoodieMessageType::VotingSessionUpdate(_) = message {
+            message = next_message(socket).await?;
+        }
+        let FoodieMessageType::VotingSessionClosed(result) = message else {
+            panic!("Expected the result");
+        };
+        assert_eq!(Some(pizza.id), result.winner_recipe_id);
+    }
+
+    // Votes after the vote is closed don't count, and only the voter is told
+    vote(&mut foo_socket, session.id, taco.id).await?;
+    let FoodieMessageType::VoteFailed { .. } = next_message(&mut foo_socket).await? else {
+        panic!("Expected the vote to fail");
+    };
+    let response = app
+        .post(format!("/api/voting/{}/close", session.id), None::<&()>)
+        .await?;
+    assert_eq!(StatusCode::CONFLICT, response.status());
+
+    let sessions = app
+        .get("/api/voting")
+        .await?
+        .json::<Vec<VotingSession>>()
+        .await?;
+    assert_eq!(1, sessions.len());
+    assert_eq!(vec![("Pizza", 2), ("Taco", 0)], votes(&sessions[0]));
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_voting_session_validation(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let pizza = app
+        .create_recipe(&CreateRecipe {
+            name: "Pizza".to_string(),
+            ..recipe_with_ingredients(&["Flour"])
+        })
+        .await?;
+    let taco = app
+        .create_recipe(&CreateRecipe {
+            name: "Taco".to_string(),
+            ..recipe_with_ingredients(&["Flour"])
+        })
+        .await?;
+
+    let stranger = app
+        .create_user(&CreateUser {
+            name: "bar".to_string(),
+            email: "bar@bar.com".to_string(),
+            password: "foo".to_string(),
+        })
+        .await?;
+
+    let response = app
+        .post(
+            "/api/voting",
+            Some(&CreateVotingSession {
+                recipe_ids: vec![pizza.id, pizza.id],
+                ..Default::default()
+            }),
+        )
+        .await?;
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    let response = app
+        .post(
+            "/api/voting",
+            Some(&CreateVotingSession {
+                recipe_ids: vec![pizza.id, taco.id],
+                participant_ids: vec![stranger.id],
+                ..Default::default()
+            }),
+        )
+        .await?;
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    let response = app
+        .post(
+            "/api/voting",
+            Some(&CreateVotingSession {
+                recipe_ids: vec![pizza.id, 9999],
+                ..Default::default()
+            }),
+        )
+        .await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    let session = app
+        .post(
+            "/api/voting",
+            Some(&CreateVotingSession {
+                recipe_ids: vec![pizza.id, taco.id],
+                ..Default::default()
+            }),
+        )
+        .await?
+        .json::<VotingSession>()
+        .await?;
+    assert_eq!("What's for dinner?", session.name);
+
+    // Only the ones taking part can see the session
+    app.login(&UserLogin {
+        email: "bar@bar.com".to_string(),
+        password: "foo".to_string(),
+    })
+    .await;
+    let response = app.get(format!("/api/voting/{}", session.id)).await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+    let response = app
+        .post(format!("/api/voting/{}/close", session.id), None::<&()>)
+        .await?;
+    assert_eq!(StatusCode::NOT_FOUND, response.status());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn test_vote_while_closing(pool: PgPool) -> Result<(), anyhow::Error> {
+    let app = TestApp::new(pool.clone()).await?;
+    let mut recipe_ids = vec![];
+    for name in ["Pizza", "Taco"] {
+        let recipe = app
+            .create_recipe(&CreateRecipe {
+                name: name.to_string(),
+                ..recipe_with_ingredients(&["Flour"])
+            })
+            .await?;
+        recipe_ids.push(recipe.id);
+    }
+    let session = app
+        .post(
+            "/api/voting",
+            Some(&CreateVotingSession {
+                recipe_ids: recipe_ids.clone(),
+                ..Default::default()
+            }),
+        )
+        .await?
+        .json::<VotingSession>()
+        .await?;
+    let mut socket = app.websocket().await?;
+
+    vote(&mut socket, session.id, 9999).await?;
+    let FoodieMessageType::VoteFailed { error, .. } = next_message(&mut socket).await? else {
+        panic!("Expected the vote to fail");
+    };
+    assert_eq!("The recipe is not part of the vote", error);
+
+    // Holds the lock that closing the vote takes, and closes it in the same transaction
+    let tx = app.pool.begin().await?;
+    voting_sessions::Entity::find_by_id(session.id)
+        .lock_exclusive()
+        .one(&tx)
+        .await?;
+    voting_sessions::Entity::update(voting_sessions::ActiveModel {
+        id: Set(session.id),
+        closed_at: Set(Some(chrono::Utc::now().into())),
+        winner_recipe_id: Set(None),
+        ..Default::default()
+    })
+    .exec(&tx)
+    .await?;
+
+    // The vote waits until the closing is committed, and is then refused
+    vote(&mut socket, session.id, recipe_ids[0]).await?;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    tx.commit().await?;
+
+    let FoodieMessageType::VoteFailed { session_id, error } = next_message(&mut socket).await?
+    else {
+        panic!("Expected the vote to fail");
+    };
+    assert_eq!(session.id, session_id);
+    assert_eq!("The vote is already closed", error);
+
+    let session = app
+        .get(format!("/api/voting/{}", session.id))
+        .await?
+        .json::<VotingSession>()
+        .await?;
+    assert_eq!(vec![("Pizza", 0), ("Taco", 0)], votes(&session));
+
+    Ok(())
+}
@@ -0,0 +1,464 @@
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+
+use crate::recipe::{CreateRecipeIngredient, Unit};
+
+// Spellings of the units in English and Norwegian, matched without case and trailing dots
+const UNITS: &[(&str, Unit)] = &[
+    ("mg", Unit::Milligram),
+    ("milligram", Unit::Milligram),
+    ("milligrams", Unit::Milligram),
+    ("g", Unit::Gram),
+    ("gr", Unit::Gram),
+    ("gram", Unit::Gram),
+    ("grams", Unit::Gram),
+    ("gramm", Unit::Gram),
+    ("hg", Unit::Hectogram),
+    ("hectogram", Unit::Hectogram),
+    ("hectograms", Unit::Hectogram),
+    ("hektogram", Unit::Hectogram),
+    ("kg", Unit::Kilogram),
+    ("kilo", Unit::Kilogram),
+    ("kilos", Unit::Kilogram),
+    ("kilogram", Unit::Kilogram),
+    ("kilograms", Unit::Kilogram),
+    ("ml", Unit::Milliliter),
+    ("milliliter", Unit::Milliliter),
+    ("milliliters", Unit::Milliliter),
+    ("millilitre", Unit::Milliliter),
+    ("millilitres", Unit::Milliliter),
+    ("dl", Unit::Deciliter),
+    ("deciliter", Unit::Deciliter),
+    ("deciliters", Unit::Deciliter),
+    ("decilitre", Unit::Deciliter),
+    ("decilitres", Unit::Deciliter),
+    ("desiliter", Unit::Deciliter),
+    ("l", Unit::Liter),
+    ("liter", Unit::Liter),
+    ("liters", Unit::Liter),
+    ("litre", Unit::Liter),
+    ("litres", Unit::Liter),
+    ("tsp", Unit::Teaspoon),
+    ("teaspoon", Unit::Teaspoon),
+    ("teaspoons", Unit::Teaspoon),
+    ("ts", Unit::Teaspoon),
+    ("tsk", Unit::Teaspoon),
+    ("teskje", Unit::Teaspoon),
+    ("teskjeer", Unit::Teaspoon),
+    ("tbsp", Unit::Tablespoon),
+    ("tablespoon", Unit::Tablespoon),
+    ("tablespoons", Unit::Tablespoon),
+    ("ss", Unit::Tablespoon),
+    ("spsk", Unit::Tablespoon),
+    ("spiseskje", Unit::Tablespoon),
+    ("spiseskjeer", Unit::Tablespoon),
+    ("cup", Unit::Cup),
+    ("cups", Unit::Cup),
+    ("kopp", Unit::Cup),
+    ("kopper", Unit::Cup),
+    ("clove", Unit::Clove),
+    ("cloves", Unit::Clove),
+    ("fedd", Unit::Clove),
+    ("pinch", Unit::Pinch),
+    ("pinches", Unit::Pinch),
+    ("klype", Unit::Pinch),
+    ("klyper", Unit::Pinch),
+];
+
+const UNICODE_FRACTIONS: &[(char, i64, i64)] = &[
+    ('½', 1, 2),
+    ('⅓', 1, 3),
+    ('⅔', 2, 3),
+    ('¼', 1, 4),
+    ('¾', 3, 4),
+    ('⅕', 1, 5),
+    ('⅛', 1, 8),
+];
+
+const OPTIONAL_MARKERS: &[&str] = &["(optional)", "(valgfritt)", "optional", "valgfritt"];
+const TO_TASTE: &[&str] = &["to taste", "etter smak"];
+
+/// Parses a line like "2 dl milk", "1 1/2 cup sugar", "3 cloves garlic, minced" or "salt to taste"
+/// into an ingredient. Everything after the first comma is used as the note. For ranges like
+/// "2-3 eggs" the lowest amount is used, and the range is kept in the note.
+///
+/// Returns `None` if the line doesn't contain an ingredient name.
+pub fn parse_ingredient_line(line: &str) -> Option<CreateRecipeIngredient> {
+    let line = line.trim().trim_start_matches(['-', '*', '•', '·']).trim();
+
+    let (line, mut optional) = strip_optional(line);
+
+    let (amount, rest, range) = match parse_quantity(&line) {
+        Some((low, rest)) => match parse_range(rest) {
+            Some((high, rest)) => (Some(low), rest, Some(format!("{low}-{high}"))),
+            None => (Some(low), rest, None),
+        },
+        None => (None, line.as_str(), None),
+    };
+
+    let (unit, rest) = match parse_unit(rest) {
+        Some((unit, rest)) => (Some(unit), rest),
+        None => (None, rest),
+    };
+
+    let rest = rest.trim_start();
+    let rest = ["of ", "av "]
+        .iter()
+        .find_map(|it| rest.strip_prefix(it))
+        .unwrap_or(rest);
+
+    let (name, note) = match rest.split_once(',') {
+        Some((name, note)) => (name.trim(), Some(note.trim())),
+        None => (rest.trim(), None),
+    };
+
+    let (name, to_taste) = strip_to_taste(name);
+
+    let note = match note {
+        Some(note) if OPTIONAL_MARKERS.contains(&note.to_lowercase().as_str()) => {
+            optional = true;
+            None
+        }
+        note => note.filter(|it| !it.is_empty()),
+    };
+
+    let note = [range.as_deref(), to_taste, note]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(CreateRecipeIngredient {
+        name: name.to_string(),
+        unit,
+        amount,
+        note: (!note.is_empty()).then_some(note),
+        optional,
+        group: None,
+    })
+}
+
+/// Parses a pasted block of ingredients, one per line. A line ending with a colon, like
+/// "For the sauce:", starts a group that is used for the following lines.
+pub fn parse_ingredient_lines(text: &str) -> Vec<CreateRecipeIngredient> {
+    let mut group = None;
+    let mut ingredients = vec![];
+
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(header) = line.strip_suffix(':') {
+            group = Some(header.trim().to_string()).filter(|it| !it.is_empty());
+            continue;
+        }
+
+        if let Some(ingredient) = parse_ingredient_line(line) {
+            ingredients.push(CreateRecipeIngredient {
+                group: group.clone(),
+                ..ingredient
+            });
+        }
+    }
+
+    ingredients
+}
+
+fn strip_optional(line: &str) -> (String, bool) {
+    for marker in &OPTIONAL_MARKERS[..2] {
+        if let Some(index) = find_ignore_ascii_case(line, marker) {
+            let mut line = line.to_string();
+            line.replace_range(index..index + marker.len(), "");
+            return (line.split_whitespace().collect::<Vec<_>>().join(" "), true);
+        }
+    }
+    (line.to_string(), false)
+}
+
+fn strip_to_taste(name: &str) -> (&str, Option<&'static str>) {
+    for suffix in TO_TASTE {
+        let Some(index) = name.len().checked_sub(suffix.len()) else {
+            continue;
+        };
+        if name.as_bytes()[index..].eq_ignore_ascii_case(suffix.as_bytes()) {
+            return (name[..index].trim(), Some(suffix));
+        }
+    }
+    (name, None)
+}
+
+// The byte offset of an ASCII `needle` in `haystack`. The offset is used on `haystack` itself, as
+// lowercasing may change the length of characters like "İ"
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .char_indices()
+        .map(|(index, _)| index)
+        .find(|index| {
+            haystack.as_bytes()[*index..]
+                .get(..needle.len())
+                .is_some_and(|it| it.eq_ignore_ascii_case(needle.as_bytes()))
+        })
+}
+
+// A whole number, a decimal number, a fraction or a mixed number like "1 1/2" or "1½"
+fn parse_quantity(s: &str) -> Option<(Decimal, &str)> {
+    let (whole, rest, is_fraction) = parse_number(s)?;
+    if is_fraction {
+        return Some((whole, rest));
+    }
+
+    let trimmed = rest.trim_start();
+    if trimmed.len() != rest.len() {
+        if let Some((fraction, rest, true)) = parse_number(trimmed) {
+            return Some((whole + fraction, rest));
+        }
+    }
+
+    Some((whole, rest))
+}
+
+// The upper part of a range, like "-3" in "2-3" or "to 3" in "2 to 3"
+fn parse_range(s: &str) -> Option<(Decimal, &str)> {
+    let trimmed = s.trim_start();
+    let rest = ["-", "–", "—", "to ", "til "]
+        .iter()
+        .find_map(|it| trimmed.strip_prefix(it))?;
+    parse_quantity(rest.trim_start())
+}
+
+fn parse_number(s: &str) -> Option<(Decimal, &str, bool)> {
+    if let Some((fraction, rest)) = parse_unicode_fraction(s) {
+        return Some((fraction, rest, true));
+    }
+
+    let end = s
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | ',' | '/')))
+        .unwrap_or(s.len());
+    let number = s[..end].trim_end_matches(['.', ',', '/']);
+    let rest = &s[number.len()..];
+    if number.is_empty() {
+        return None;
+    }
+
+    let (value, is_fraction) = match number.split_once('/') {
+        Some((numerator, denominator)) => {
+            let denominator = parse_decimal(denominator)?;
+            if denominator.is_zero() {
+                return None;
+            }
+            (parse_decimal(numerator)? / denominator, true)
+        }
+        None => (parse_decimal(number)?, false),
+    };
+
+    match parse_unicode_fraction(rest) {
+        Some((fraction, rest)) if !is_fraction => Some((value + fraction, rest, false)),
+        _ => Some((value.round_dp(3).normalize(), rest, is_fraction)),
+    }
+}
+
+fn parse_unicode_fraction(s: &str) -> Option<(Decimal, &str)> {
+    let c = s.chars().next()?;
+    let (_, numerator, denominator) = UNICODE_FRACTIONS.iter().find(|it| it.0 == c)?;
+    let value = (Decimal::from(*numerator) / Decimal::from(*denominator))
+        .round_dp(3)
+        .normalize();
+    Some((value, &s[c.len_utf8()..]))
+}
+
+// "1,5" is how decimals are written in Norwegian
+fn parse_decimal(s: &str) -> Option<Decimal> {
+    Decimal::from_str(&s.replace(',', ".")).ok()
+}
+
+fn parse_unit(s: &str) -> Option<(Unit, &str)> {
+    let trimmed = s.trim_start();
+    let end = trimmed
+        .find(|c: char| !(c.is_alphabetic() || c == '.'))
+        .unwrap_or(trimmed.len());
+    let word = trimmed[..end].trim_end_matches('.').to_lowercase();
+
+    let (_, unit) = UNITS.iter().find(|it| it.0 == word)?;
+    Some((*unit, &trimmed[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ingredient(
+        name: &str,
+        amount: Option<&str>,
+        unit: Option<Unit>,
+        note: Option<&str>,
+    ) -> CreateRecipeIngredient {
+        CreateRecipeIngredient {
+            name: name.to_string(),
+            unit,
+            amount: amount.map(|it| Decimal::from_str(it).unwrap()),
+            note: note.map(|it| it.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_amount_and_unit() {
+        for (line, expected) in [
+            (
+                "2 dl milk",
+                ingredient("milk", Some("2"), Some(Unit::Deciliter), None),
+            ),
+            (
+                "1/2 cup sugar",
+                ingredient("sugar", Some("0.5"), Some(Unit::Cup), None),
+            ),
+            (
+                "1 1/2 cups flour",
+                ingredient("flour", Some("1.5"), Some(Unit::Cup), None),
+            ),
+            (
+                "1½ tbsp. butter",
+                ingredient("butter", Some("1.5"), Some(Unit::Tablespoon), None),
+            ),
+            (
+                "200 g of cheese",
+                ingredient("cheese", Some("200"), Some(Unit::Gram), None),
+            ),
+            ("- 3 eggs", ingredient("eggs", Some("3"), None, None)),
+            ("Pepper", ingredient("Pepper", None, None, None)),
+        ] {
+            assert_eq!(Some(expected), parse_ingredient_line(line), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_parse_note_and_to_taste() {
+        assert_eq!(
+            Some(ingredient(
+                "garlic",
+                Some("3"),
+                Some(Unit::Clove),
+                Some("minced")
+            )),
+            parse_ingredient_line("3 cloves garlic, minced")
+        );
+        assert_eq!(
+            Some(ingredient("salt", None, None, Some("to taste"))),
+            parse_ingredient_line("salt to taste")
+        );
+        assert_eq!(
+            Some(ingredient("Salt", None, None, Some("to taste"))),
+            parse_ingredient_line("Salt To Taste")
+        );
+    }
+
+    #[test]
+    fn test_parse_range() {
+        for line in ["2-3 eggs", "2 to 3 eggs", "2–3 eggs"] {
+            assert_eq!(
+                Some(ingredient("eggs", Some("2"), None, Some("2-3"))),
+                parse_ingredient_line(line),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_norwegian() {
+        for (line, expected) in [
+            (
+                "1,5 ss smør",
+                ingredient("smør", Some("1.5"), Some(Unit::Tablespoon), None),
+            ),
+            (
+                "2 fedd hvitløk",
+                ingredient("hvitløk", Some("2"), Some(Unit::Clove), None),
+            ),
+            (
+                "1 klype salt",
+                ingredient("salt", Some("1"), Some(Unit::Pinch), None),
+            ),
+            (
+                "3 dl av melk",
+                ingredient("melk", Some("3"), Some(Unit::Deciliter), None),
+            ),
+            (
+                "pepper etter smak",
+                ingredient("pepper", None, None, Some("etter smak")),
+            ),
+        ] {
+            assert_eq!(Some(expected), parse_ingredient_line(line), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_parse_optional() {
+        for line in [
+            "1 dl cream (optional)",
+            "1 dl cream, optional",
+            "1 dl cream (Valgfritt)",
+        ] {
+            assert_eq!(
+                Some(CreateRecipeIngredient {
+                    optional: true,
+                    ..ingredient("cream", Some("1"), Some(Unit::Deciliter), None)
+                }),
+                parse_ingredient_line(line),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_non_ascii() {
+        for (line, name) in [
+            ("İ (optional)", "İ"),
+            ("İİ (optional) ø", "İİ ø"),
+            ("ẞ (optional) x", "ẞ x"),
+        ] {
+            assert_eq!(
+                Some(CreateRecipeIngredient {
+                    optional: true,
+                    ..ingredient(name, None, None, None)
+                }),
+                parse_ingredient_line(line),
+                "{line}"
+            );
+        }
+
+        assert_eq!(
+            Some(ingredient("İ", None, None, Some("to taste"))),
+            parse_ingredient_line("İ to taste")
+        );
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        assert_eq!(None, parse_ingredient_line(""));
+        assert_eq!(None, parse_ingredient_line("2 dl"));
+        assert_eq!(None, parse_ingredient_line("(optional)"));
+    }
+
+    #[test]
+    fn test_parse_groups() {
+        let ingredients = parse_ingredient_lines(
+            "2 eggs\n\nFor the sauce:\n2 dl cream\n1 tsp salt\n\nTopping:\n100 g cheese",
+        );
+
+        assert_eq!(
+            vec![
+                (None, "eggs"),
+                (Some("For the sauce"), "cream"),
+                (Some("For the sauce"), "salt"),
+                (Some("Topping"), "cheese"),
+            ],
+            ingredients
+                .iter()
+                .map(|it| (it.group.as_deref(), it.name.as_str()))
+                .collect::<Vec<_>>()
+        );
+    }
+}
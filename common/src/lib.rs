@@ -1,3 +1,4 @@
+pub mod ingredient_parser;
 mod models;
 
 pub use models::*;
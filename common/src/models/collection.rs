@@ -0,0 +1,34 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::recipe::RecipeVisibility;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CreateCollection {
+    pub name: String,
+    pub description: Option<String>,
+    /// Whether friends can see the collection. Shared with friends by default, like recipes
+    #[serde(default)]
+    pub visibility: RecipeVisibility,
+}
+
+/// A named set of recipes, like "Weeknight" or "Christmas". The recipes can be both the owner's
+/// own and the ones shared by friends
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Collection {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub visibility: RecipeVisibility,
+    pub updated_at: DateTime<FixedOffset>,
+    /// Only the recipes the requesting user has access to
+    pub recipe_ids: Vec<i32>,
+}
+
+/// If a recipe is a favorite of the user, and which of the user's own collections it is in
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct RecipeBookmarks {
+    pub favorite: bool,
+    pub collection_ids: Vec<i32>,
+}
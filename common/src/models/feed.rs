@@ -0,0 +1,33 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+/// Activity at the same time is ordered by the kind, in the reverse order of the variants
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FeedKind {
+    RecipeCreated,
+    RecipeUpdated,
+    /// The friend cooked the recipe, and maybe rated it
+    RecipeCooked,
+}
+
+/// Something a friend did with a recipe the user has access to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeedItem {
+    pub kind: FeedKind,
+    /// The recipe for created and updated recipes, and the cook for cooked ones. Together with
+    /// `kind` and `at` it is where the next page starts
+    pub id: i32,
+    pub user_id: i32,
+    pub user_name: String,
+    pub recipe_id: i32,
+    pub recipe_name: String,
+    /// From 1 to 5, only for cooked recipes
+    pub rating: Option<i32>,
+    pub at: DateTime<FixedOffset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CookRecipe {
+    /// From 1 to 5
+    pub rating: Option<i32>,
+}
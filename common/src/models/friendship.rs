@@ -14,3 +14,16 @@ pub enum FriendshipStatus {
     Rejected,
     Blocked,
 }
+
+/// Someone the user might know, with what they have in common
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FriendSuggestion {
+    pub id: i32,
+    pub name: String,
+    pub email: String,
+    pub mutual_friends: i64,
+    /// Voting and swipe sessions they have both been a part of
+    pub shared_sessions: i64,
+    /// Recipes of the suggested user that share ingredients with the recipes of the user
+    pub similar_recipes: i64,
+}
@@ -1,4 +1,7 @@
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CreateIngredient {
@@ -9,4 +12,154 @@ pub struct CreateIngredient {
 pub struct Ingredient {
     pub id: i32,
     pub name: String,
+    /// The owner of a private ingredient. `None` means it is part of the global catalog
+    pub user_id: Option<i32>,
+    /// The category as seen by the current user, which is their own override if they have one
+    pub category: IngredientCategory,
+    pub allergens: Vec<Allergen>,
+    pub vegetarian: bool,
+    pub vegan: bool,
+}
+
+/// Where in the store an ingredient is found. The order of the variants is the default aisle order
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Copy, Eq, PartialEq, Hash, Default, EnumIter, Display,
+)]
+pub enum IngredientCategory {
+    Produce,
+    Dairy,
+    Meat,
+    Seafood,
+    Bakery,
+    #[strum(to_string = "Dry goods")]
+    DryGoods,
+    Spices,
+    Frozen,
+    Beverages,
+    #[default]
+    Other,
+}
+
+impl FromStr for IngredientCategory {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "Produce" => Ok(Self::Produce),
+            "Dairy" => Ok(Self::Dairy),
+            "Meat" => Ok(Self::Meat),
+            "Seafood" => Ok(Self::Seafood),
+            "Bakery" => Ok(Self::Bakery),
+            "Dry goods" => Ok(Self::DryGoods),
+            "Spices" => Ok(Self::Spices),
+            "Frozen" => Ok(Self::Frozen),
+            "Beverages" => Ok(Self::Beverages),
+            "Other" => Ok(Self::Other),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    Copy,
+    Eq,
+    PartialEq,
+    Hash,
+    PartialOrd,
+    Ord,
+    EnumIter,
+    Display,
+)]
+pub enum Allergen {
+    Gluten,
+    Lactose,
+    Egg,
+    Nuts,
+    Peanuts,
+    Soy,
+    Fish,
+    Shellfish,
+    Sesame,
+}
+
+impl FromStr for Allergen {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "Gluten" => Ok(Self::Gluten),
+            "Lactose" => Ok(Self::Lactose),
+            "Egg" => Ok(Self::Egg),
+            "Nuts" => Ok(Self::Nuts),
+            "Peanuts" => Ok(Self::Peanuts),
+            "Soy" => Ok(Self::Soy),
+            "Fish" => Ok(Self::Fish),
+            "Shellfish" => Ok(Self::Shellfish),
+            "Sesame" => Ok(Self::Sesame),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, Eq, PartialEq, EnumIter, Display)]
+pub enum Diet {
+    Vegetarian,
+    Vegan,
+}
+
+impl FromStr for Diet {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "Vegetarian" => Ok(Self::Vegetarian),
+            "Vegan" => Ok(Self::Vegan),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateIngredientDietary {
+    pub allergens: Vec<Allergen>,
+    pub vegetarian: bool,
+    /// Vegan ingredients are always vegetarian as well
+    pub vegan: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateIngredientCategory {
+    pub category: IngredientCategory,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateIngredientAlias {
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IngredientAlias {
+    pub id: i32,
+    pub ingredient_id: i32,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MergeIngredient {
+    /// The ingredient that should be kept. Every recipe using the merged ingredient is rewritten
+    /// to use this one instead
+    pub into_id: i32,
+}
+
+/// Normalizes an ingredient name so that "Tomato", "tomato" and " tomato  " are considered the
+/// same ingredient.
+pub fn normalize_ingredient_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
 }
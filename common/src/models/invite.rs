@@ -0,0 +1,18 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CreateInvite {
+    pub email: String,
+}
+
+/// An invite the user has sent, with a link that lets the invited register
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Invite {
+    pub id: i32,
+    pub email: String,
+    pub created_at: DateTime<FixedOffset>,
+    pub expires_at: DateTime<FixedOffset>,
+    /// If the invited has registered with it. It can only be used once
+    pub accepted: bool,
+}
@@ -0,0 +1,28 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::recipe::Duration;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CreatePlannedMeal {
+    pub recipe_id: i32,
+    /// When the meal should be ready to eat
+    pub eat_at: DateTime<FixedOffset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PlannedMeal {
+    pub id: i32,
+    pub recipe_id: i32,
+    pub name: String,
+    /// When the meal should be ready to eat
+    pub eat_at: DateTime<FixedOffset>,
+    /// The total time of the recipe, so cooking has to start this long before `eat_at`
+    pub total_time: Option<Duration>,
+}
+
+/// A subscribable iCalendar feed of the meal plan. Anyone with the url can read the feed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CalendarFeed {
+    pub url: String,
+}
@@ -1,5 +1,14 @@
+pub mod collection;
+pub mod feed;
 pub mod friendship;
 pub mod ingredient;
+pub mod invite;
+pub mod meal_plan;
+pub mod notification;
 pub mod recipe;
+pub mod shopping_list;
+pub mod swipe;
+pub mod takeout;
 pub mod user;
+pub mod voting;
 pub mod websocket;
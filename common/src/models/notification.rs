@@ -0,0 +1,40 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationKind {
+    /// Someone sent the user a friend request
+    FriendRequest,
+    /// Someone accepted the friend request of the user
+    FriendRequestAccepted,
+    /// A friend created a recipe, which is in `recipe_id`
+    FriendRecipe,
+    /// A friend invited the user to vote on dinner, in the session in `voting_session_id`
+    VotingSessionInvite,
+}
+
+/// Something that happened while the user might not have been there to see it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+    pub id: i32,
+    pub kind: NotificationKind,
+    /// The user who caused it
+    pub actor_id: Option<i32>,
+    pub actor_name: Option<String>,
+    pub recipe_id: Option<i32>,
+    pub voting_session_id: Option<i32>,
+    pub created_at: DateTime<FixedOffset>,
+    pub read: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UnreadNotifications {
+    pub count: u64,
+}
+
+/// The notifications to mark as read. All of them are marked when no ids are given
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ReadNotifications {
+    #[serde(default)]
+    pub ids: Vec<i32>,
+}
@@ -1,11 +1,13 @@
-use std::str::FromStr;
+use std::{fmt, iter::Sum, ops::Add, str::FromStr};
 
-use chrono::{DateTime, FixedOffset, NaiveTime};
+use chrono::{DateTime, FixedOffset};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter};
 use uuid::Uuid;
 
+use crate::ingredient::{Allergen, Diet};
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct CreateRecipe {
     pub name: String,
@@ -13,10 +15,29 @@ pub struct CreateRecipe {
     pub instructions: Option<Vec<String>>,
     pub img: Option<Uuid>,
     pub servings: i32,
-    pub prep_time: Option<NaiveTime>,
-    pub baking_time: Option<NaiveTime>,
+    pub prep_time: Option<Duration>,
+    /// Active time at the stove, e.g. frying or stirring
+    #[serde(default)]
+    pub cooking_time: Option<Duration>,
+    pub baking_time: Option<Duration>,
+    /// Time where nothing has to be done, e.g. marinating, proofing or letting it rest
+    #[serde(default)]
+    pub resting_time: Option<Duration>,
     pub ingredients: Vec<CreateRecipeIngredient>,
     pub visibility: RecipeVisibility,
+    /// Where the recipe comes from, e.g. a link to a blog post
+    #[serde(default)]
+    pub source_url: Option<String>,
+    /// Who to credit for the recipe, e.g. the name of a cookbook or a person
+    #[serde(default)]
+    pub source_name: Option<String>,
+    #[serde(default)]
+    pub cuisine: Option<String>,
+    #[serde(default)]
+    pub difficulty: Option<RecipeDifficulty>,
+    /// What `servings` is counting, e.g. "loaf", "cookies" or "20 cm cake tin". Servings if `None`
+    #[serde(default)]
+    pub yield_unit: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default, Display, EnumIter)]
@@ -26,6 +47,26 @@ pub enum RecipeVisibility {
     Private,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Display, EnumIter)]
+pub enum RecipeDifficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl FromStr for RecipeDifficulty {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "Easy" => Ok(Self::Easy),
+            "Medium" => Ok(Self::Medium),
+            "Hard" => Ok(Self::Hard),
+            _ => Err(()),
+        }
+    }
+}
+
 impl FromStr for RecipeVisibility {
     type Err = ();
 
@@ -48,13 +89,138 @@ pub struct Recipe {
     pub img: Option<String>,
     pub servings: i32,
     pub updated_at: DateTime<FixedOffset>,
-    pub prep_time: Option<NaiveTime>,
-    pub baking_time: Option<NaiveTime>,
+    pub prep_time: Option<Duration>,
+    pub cooking_time: Option<Duration>,
+    pub baking_time: Option<Duration>,
+    pub resting_time: Option<Duration>,
+    /// The sum of all the times. `None` if none of them are set
+    pub total_time: Option<Duration>,
     pub ingredients: Vec<RecipeIngredient>,
     pub visibility: RecipeVisibility,
+    /// Derived from the ingredients. Contains every allergen any of the ingredients has
+    pub allergens: Vec<Allergen>,
+    /// Derived from the ingredients. A recipe only follows a diet if all its ingredients do
+    pub diets: Vec<Diet>,
+    /// Where the recipe comes from, e.g. a link to a blog post
+    pub source_url: Option<String>,
+    /// Who to credit for the recipe, e.g. the name of a cookbook or a person
+    pub source_name: Option<String>,
+    pub cuisine: Option<String>,
+    pub difficulty: Option<RecipeDifficulty>,
+    /// What `servings` is counting, e.g. "loaf", "cookies" or "20 cm cake tin". Servings if `None`
+    pub yield_unit: Option<String>,
+}
+
+/// A weighted random pick of recipes. Passing the same `seed` back gives the same pick, as long as
+/// the weights are the same. They change as time passes since the recipes were updated or viewed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RecipeSuggestions {
+    pub seed: u64,
+    pub recipes: Vec<Recipe>,
+}
+
+/// A recipe that is likely the same as one that is about to be created
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DuplicateRecipe {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    /// How alike the names are, from 0 to 1
+    pub name_similarity: f32,
+    /// How many of the ingredients the recipes have in common, from 0 to 1
+    pub ingredient_overlap: f32,
+}
+
+/// The response when creating a recipe that probably already exists. Creating it anyway is done
+/// with `?allow_duplicate=true`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DuplicateRecipes {
+    pub error: String,
+    pub candidates: Vec<DuplicateRecipe>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MergeRecipe {
+    /// The recipe that should be kept. The favorites, collections and planned meals of the merged
+    /// recipe are moved to this one
+    pub into_id: i32,
+}
+
+/// Formats what a recipe makes, e.g. "4 servings", "24 cookies" or "1 × 20 cm cake tin"
+pub fn format_yield(amount: impl fmt::Display, unit: Option<&str>) -> String {
+    match unit.map(str::trim).filter(|it| !it.is_empty()) {
+        None => format!("{amount} servings"),
+        Some(unit) if unit.starts_with(|c: char| c.is_ascii_digit()) => {
+            format!("{amount} × {unit}")
+        }
+        Some(unit) => format!("{amount} {unit}"),
+    }
+}
+
+/// A duration with minute precision. Serialized as the number of minutes
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(transparent)]
+pub struct Duration {
+    minutes: u32,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Copy, Eq, PartialEq, EnumIter, Display)]
+impl Duration {
+    pub const fn from_minutes(minutes: u32) -> Self {
+        Self { minutes }
+    }
+
+    pub const fn from_hours(hours: u32) -> Self {
+        Self {
+            minutes: hours.saturating_mul(60),
+        }
+    }
+
+    pub const fn as_minutes(&self) -> u32 {
+        self.minutes
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.minutes == 0
+    }
+}
+
+impl Add for Duration {
+    type Output = Duration;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::from_minutes(self.minutes.saturating_add(rhs.minutes))
+    }
+}
+
+impl Sum for Duration {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Duration::default(), Add::add)
+    }
+}
+
+// Formats as e.g. "1 d 2 h 30 min", leaving out the parts that are zero
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let days = self.minutes / (24 * 60);
+        let hours = self.minutes / 60 % 24;
+        let minutes = self.minutes % 60;
+
+        let parts = [(days, "d"), (hours, "h"), (minutes, "min")]
+            .into_iter()
+            .filter(|(value, _)| *value > 0)
+            .map(|(value, unit)| format!("{value} {unit}"))
+            .collect::<Vec<_>>();
+
+        match parts.is_empty() {
+            true => write!(f, "0 min"),
+            false => write!(f, "{}", parts.join(" ")),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, Eq, PartialEq, Hash, EnumIter, Display)]
 pub enum Unit {
     Milligram,
     Gram,
@@ -96,6 +262,14 @@ pub struct CreateRecipeIngredient {
     pub name: String,
     pub unit: Option<Unit>,
     pub amount: Option<Decimal>,
+    /// Free text shown next to the ingredient, e.g. "finely chopped"
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub optional: bool,
+    /// The group the ingredient belongs to, e.g. "For the sauce"
+    #[serde(default)]
+    pub group: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -104,6 +278,10 @@ pub struct RecipeIngredient {
     pub ingredient_name: String,
     pub unit: Option<Unit>,
     pub amount: Option<Decimal>,
+    pub position: i32,
+    pub note: Option<String>,
+    pub optional: bool,
+    pub group: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -128,8 +306,15 @@ impl From<Recipe> for CreateRecipe {
             img,
             servings: recipe.servings,
             prep_time: recipe.prep_time,
+            cooking_time: recipe.cooking_time,
             baking_time: recipe.baking_time,
+            resting_time: recipe.resting_time,
             visibility: recipe.visibility,
+            source_url: recipe.source_url,
+            source_name: recipe.source_name,
+            cuisine: recipe.cuisine,
+            difficulty: recipe.difficulty,
+            yield_unit: recipe.yield_unit,
             ingredients: recipe
                 .ingredients
                 .into_iter()
@@ -145,6 +330,27 @@ impl From<RecipeIngredient> for CreateRecipeIngredient {
             name: recipe_ingredient.ingredient_name,
             unit: recipe_ingredient.unit,
             amount: recipe_ingredient.amount,
+            note: recipe_ingredient.note,
+            optional: recipe_ingredient.optional,
+            group: recipe_ingredient.group,
+        }
+    }
+}
+
+/// Groups the ingredients by their group, in the order the groups first appear. Ingredients without
+/// a group are in a group of their own with `None` as the name
+pub fn group_ingredients(
+    ingredients: &[RecipeIngredient],
+) -> Vec<(Option<String>, Vec<RecipeIngredient>)> {
+    let mut groups: Vec<(Option<String>, Vec<RecipeIngredient>)> = vec![];
+    for ingredient in ingredients {
+        match groups
+            .iter_mut()
+            .find(|(name, _)| *name == ingredient.group)
+        {
+            Some((_, items)) => items.push(ingredient.clone()),
+            None => groups.push((ingredient.group.clone(), vec![ingredient.clone()])),
         }
     }
+    groups
 }
@@ -0,0 +1,29 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::{ingredient::IngredientCategory, recipe::Unit};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateShoppingList {
+    pub recipe_ids: Vec<i32>,
+}
+
+/// The ingredients needed for a set of recipes, grouped by category in the aisle order of the user
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShoppingList {
+    pub sections: Vec<ShoppingListSection>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShoppingListSection {
+    pub category: IngredientCategory,
+    pub items: Vec<ShoppingListItem>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ShoppingListItem {
+    pub ingredient_id: i32,
+    pub name: String,
+    pub unit: Option<Unit>,
+    pub amount: Option<Decimal>,
+}
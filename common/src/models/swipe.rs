@@ -0,0 +1,40 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CreateSwipeSession {
+    pub name: String,
+    /// Friends to swipe with. The one creating the session always takes part
+    pub participant_ids: Vec<i32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SwipeParticipant {
+    pub user_id: i32,
+    pub name: String,
+}
+
+/// Everyone in the session swipes yes or no on recipes, and a recipe everyone says yes to is a
+/// match
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SwipeSession {
+    pub id: i32,
+    pub owner_id: i32,
+    pub name: String,
+    pub created_at: DateTime<FixedOffset>,
+    pub participants: Vec<SwipeParticipant>,
+    /// The recipes everyone said yes to, the newest match first
+    pub matches: Vec<SwipeMatch>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Swipe {
+    pub liked: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SwipeMatch {
+    pub session_id: i32,
+    pub recipe_id: i32,
+    pub name: String,
+}
@@ -0,0 +1,92 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    friendship::FriendshipStatus,
+    ingredient::{Allergen, IngredientCategory},
+    recipe::{Duration, RecipeDifficulty, RecipeVisibility, Unit},
+};
+
+/// The version of the archive format. Archives with a newer version can't be imported
+pub const TAKEOUT_VERSION: u32 = 1;
+
+/// Describes the archive, and is stored as `manifest.json` in it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TakeoutManifest {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub name: String,
+    pub email: String,
+}
+
+/// Stored in `ingredients.json`. Contains the private ingredients of the user, and the ones from
+/// the catalog that their recipes uses
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TakeoutIngredient {
+    /// The id in the instance it was exported from, which the recipes refers to
+    pub id: i32,
+    pub name: String,
+    /// Whether it was a private ingredient of the user, and not a part of the catalog
+    pub private: bool,
+    pub category: IngredientCategory,
+    pub allergens: Vec<Allergen>,
+    pub vegetarian: bool,
+    pub vegan: bool,
+}
+
+/// Stored in `recipes.json`. The image is stored as `images/<img>` in the archive
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TakeoutRecipe {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub instructions: Option<Vec<String>>,
+    pub img: Option<Uuid>,
+    pub servings: i32,
+    pub prep_time: Option<Duration>,
+    pub cooking_time: Option<Duration>,
+    pub baking_time: Option<Duration>,
+    pub resting_time: Option<Duration>,
+    pub visibility: RecipeVisibility,
+    pub source_url: Option<String>,
+    pub source_name: Option<String>,
+    pub cuisine: Option<String>,
+    pub difficulty: Option<RecipeDifficulty>,
+    pub yield_unit: Option<String>,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+    pub ingredients: Vec<TakeoutRecipeIngredient>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TakeoutRecipeIngredient {
+    /// Refers to the `id` of an ingredient in `ingredients.json`
+    pub ingredient_id: i32,
+    pub unit: Option<Unit>,
+    pub amount: Option<Decimal>,
+    pub note: Option<String>,
+    pub optional: bool,
+    pub group: Option<String>,
+}
+
+/// Stored in `friendships.json`. The friends are users of the instance it was exported from, so
+/// these are not imported
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TakeoutFriendship {
+    pub name: String,
+    pub email: String,
+    pub status: FriendshipStatus,
+    /// Whether the user sent the friend request, or received it
+    pub requested: bool,
+    pub requested_at: DateTime<FixedOffset>,
+}
+
+/// What was created by importing an archive
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TakeoutImport {
+    pub recipes: usize,
+    pub ingredients: usize,
+    pub images: usize,
+}
@@ -1,13 +1,14 @@
 use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+use uuid::Uuid;
 
-use crate::friendship::FriendshipStatus;
+use crate::{friendship::FriendshipStatus, recipe::Recipe};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CreateUser {
     pub name: String,
     pub email: String,
     pub password: String,
-    // picture: String,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -15,7 +16,6 @@ pub struct User {
     pub id: i32,
     pub name: String,
     pub email: String,
-    // picture: String,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -28,6 +28,46 @@ pub struct UserWithRelation {
     pub status: Option<FriendshipStatus>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default, Display, EnumIter)]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+/// What a user shows about themselves to their friends
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Profile {
+    pub id: i32,
+    pub name: String,
+    /// Shown instead of the name if it is set
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    /// Url to the uploaded avatar, or the picture from Google if nothing is uploaded
+    pub avatar: Option<String>,
+    pub preferred_units: UnitSystem,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
+pub struct UpdateProfile {
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub preferred_units: UnitSystem,
+}
+
+/// An image that is uploaded with the presigned url from `/api/uploads/avatars`
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UpdateAvatar {
+    pub id: Uuid,
+}
+
+/// The profile of a friend, with the recipes they share with the user
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FriendProfile {
+    pub profile: Profile,
+    pub recipes: Vec<Recipe>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
 pub struct UserLogin {
     pub email: String,
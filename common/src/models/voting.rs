@@ -0,0 +1,43 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CreateVotingSession {
+    pub name: String,
+    /// The recipes to choose between, in the order they are shown
+    pub recipe_ids: Vec<i32>,
+    /// Friends to invite. The one creating the session always takes part
+    pub participant_ids: Vec<i32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VotingCandidate {
+    pub recipe_id: i32,
+    pub name: String,
+    pub votes: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VotingParticipant {
+    pub user_id: i32,
+    pub name: String,
+    pub has_voted: bool,
+}
+
+/// A household deciding what to make together. Everyone votes for one of the candidates, and
+/// the one with the most votes wins when the owner closes the vote
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VotingSession {
+    pub id: i32,
+    pub owner_id: i32,
+    pub name: String,
+    pub created_at: DateTime<FixedOffset>,
+    /// `None` while the vote is open
+    pub closed_at: Option<DateTime<FixedOffset>>,
+    /// Ties go to the candidate listed first. `None` if nobody voted
+    pub winner_recipe_id: Option<i32>,
+    pub candidates: Vec<VotingCandidate>,
+    pub participants: Vec<VotingParticipant>,
+    /// The recipe the requesting user voted for
+    pub vote: Option<i32>,
+}
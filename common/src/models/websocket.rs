@@ -2,11 +2,39 @@ use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
+use crate::{notification::Notification, swipe::SwipeMatch, user::User, voting::VotingSession};
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum FoodieMessageType {
-    FriendRequest,
+    /// Sent to the recipient of a friend request, with the user who sent it
+    FriendRequest(User),
+    /// Sent to the requester when the recipient accepts, with the recipient
+    FriendRequestAccepted(User),
+    /// Sent to the friend that was removed, with the user who removed them
+    FriendRemoved(User),
+    /// Sent to the user when a notification is stored for them
+    Notification(Notification),
     RecipeDelete,
     RecipeCreate,
+    /// Sent to the friends invited to a voting session
+    VotingSessionInvite(VotingSession),
+    /// Sent by a participant to vote, or change their vote, in an open session
+    Vote {
+        session_id: i32,
+        recipe_id: i32,
+    },
+    /// Sent to every participant when someone votes
+    VotingSessionUpdate(VotingSession),
+    /// Sent back to the participant when their vote was not counted, for example because the vote
+    /// was closed
+    VoteFailed {
+        session_id: i32,
+        error: String,
+    },
+    /// Sent to every participant with the result when the owner closes the vote
+    VotingSessionClosed(VotingSession),
+    /// Sent to everyone in a swipe session when they all said yes to a recipe
+    SwipeMatch(SwipeMatch),
 }
 
 impl FromStr for FoodieMessageType {
@@ -0,0 +1,19 @@
+use leptos::prelude::*;
+use thaw::*;
+
+#[component]
+pub fn FormFieldAutoComplete(
+    #[prop(optional, into)] class: MaybeProp<String>,
+    #[prop(optional, into)] name: MaybeProp<String>,
+    #[prop(optional, into)] value: thaw_utils::Model<String>,
+    #[prop(optional, into)] placeholder: MaybeProp<String>,
+    #[prop(optional)] children: Option<Children>,
+) -> impl IntoView {
+    view! {
+        <Field class=class name=name label=placeholder>
+            <AutoComplete value=value placeholder=placeholder class="w-full">
+                {children.map(|c| c())}
+            </AutoComplete>
+        </Field>
+    }
+}
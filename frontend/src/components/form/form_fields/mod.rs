@@ -1,3 +1,4 @@
+pub mod form_field_autocomplete;
 pub mod form_field_combobox;
 pub mod form_field_input;
 pub mod form_field_number_input;
@@ -1,8 +1,183 @@
+use std::time::Duration;
+
+use codee::string::FromToStringCodec;
+use common::{
+    notification::{Notification, NotificationKind, ReadNotifications, UnreadNotifications},
+    websocket::FoodieMessageType,
+};
 use leptos::{prelude::*, task::spawn_local};
 use leptos_router::hooks::use_navigate;
+use leptos_use::{use_websocket, UseWebSocketReturn};
 use thaw::*;
 
-use crate::{context::auth::AuthContext, request::post};
+use crate::{
+    context::{
+        auth::AuthContext,
+        friends::FriendEvents,
+        toast::{use_toast, Toast, ToastType, ToasterTrait},
+    },
+    request::{get, post},
+};
+
+fn notification_text(notification: &Notification) -> String {
+    let actor = notification.actor_name.as_deref().unwrap_or("Someone");
+    match notification.kind {
+        NotificationKind::FriendRequest => format!("{actor} sent you a friend request"),
+        NotificationKind::FriendRequestAccepted => {
+            format!("{actor} accepted your friend request")
+        }
+        NotificationKind::FriendRecipe => format!("{actor} added a new recipe"),
+        NotificationKind::VotingSessionInvite => format!("{actor} invited you to vote on dinner"),
+    }
+}
+
+// Where to go to see what the notification is about
+fn notification_href(notification: &Notification) -> String {
+    match (
+        notification.kind,
+        notification.recipe_id,
+        notification.voting_session_id,
+    ) {
+        (NotificationKind::FriendRecipe, Some(id), _) => format!("/recipes/{id}"),
+        (NotificationKind::VotingSessionInvite, _, Some(id)) => format!("/voting/{id}"),
+        _ => "/friends".to_string(),
+    }
+}
+
+// The toast to show when a friend request is received or answered, or a friend removes the user
+fn friend_event_toast(message: &FoodieMessageType) -> Option<Toast> {
+    let (ty, body) = match message {
+        FoodieMessageType::FriendRequest(user) => (
+            ToastType::Success,
+            format!("{} sent you a friend request", user.name),
+        ),
+        FoodieMessageType::FriendRequestAccepted(user) => (
+            ToastType::Success,
+            format!("{} accepted your friend request", user.name),
+        ),
+        FoodieMessageType::FriendRemoved(user) => (
+            ToastType::Warning,
+            format!("{} removed you as a friend", user.name),
+        ),
+        _ => return None,
+    };
+
+    Some(Toast {
+        ty,
+        body,
+        timeout: Some(Duration::from_secs(5)),
+    })
+}
+
+#[component]
+fn NotificationBell() -> impl IntoView {
+    let navigate = use_navigate();
+    let toast = use_toast().unwrap();
+    let friend_events = expect_context::<FriendEvents>().0;
+
+    let notifications = LocalResource::new(move || async move {
+        match get("/api/notifications").send().await {
+            Ok(res) => res.json::<Vec<Notification>>().await.unwrap_or_default(),
+            Err(_) => vec![],
+        }
+    });
+
+    let unread = LocalResource::new(move || async move {
+        match get("/api/notifications/unread").send().await {
+            Ok(res) => res
+                .json::<UnreadNotifications>()
+                .await
+                .map(|it| it.count)
+                .unwrap_or_default(),
+            Err(_) => 0,
+        }
+    });
+
+    let UseWebSocketReturn { message, .. } =
+        use_websocket::<String, FoodieMessageType, FromToStringCodec>("/api/ws");
+
+    // This is the one websocket that is open on every page, so friend events are handled here
+    Effect::new(move || {
+        let Some(message) = message() else {
+            return;
+        };
+        if let FoodieMessageType::Notification(_) = message {
+            notifications.refetch();
+            unread.refetch();
+        }
+        if let Some(friend_toast) = friend_event_toast(&message) {
+            toast.add(friend_toast);
+            friend_events.update(|it| *it += 1);
+        }
+    });
+
+    let on_select = move |key: String| {
+        let nav = navigate.clone();
+        let selected = key.parse::<i32>().ok().and_then(|id| {
+            notifications
+                .get_untracked()
+                .and_then(|it| it.iter().find(|n| n.id == id).cloned())
+        });
+        let body = ReadNotifications {
+            ids: selected.iter().map(|it| it.id).collect(),
+        };
+
+        spawn_local(async move {
+            let body = serde_json::to_string(&body).unwrap();
+            let _ = post("/api/notifications/read").body(body).send().await;
+            notifications.refetch();
+            unread.refetch();
+        });
+
+        if let Some(notification) = selected {
+            nav(&notification_href(&notification), Default::default());
+        }
+    };
+
+    view! {
+        <Menu on_select position=MenuPosition::BottomEnd>
+            <MenuTrigger slot>
+                <div class="relative">
+                    <Button appearance=ButtonAppearance::Transparent icon=icondata::AiBellOutlined />
+                    {move || {
+                        let count = unread.get().map(|it| *it).unwrap_or_default();
+                        (count > 0)
+                            .then(|| {
+                                view! {
+                                    <Badge
+                                        class="absolute top-0 right-0"
+                                        size=BadgeSize::Small
+                                        color=BadgeColor::Danger
+                                    >
+                                        {count.to_string()}
+                                    </Badge>
+                                }
+                            })
+                    }}
+                </div>
+            </MenuTrigger>
+            <MenuItem value="read_all">"Mark all as read"</MenuItem>
+            {move || {
+                notifications
+                    .get()
+                    .as_deref()
+                    .cloned()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|n| {
+                        let text = notification_text(&n);
+                        let class = if n.read { "opacity-60" } else { "font-bold" };
+                        view! {
+                            <MenuItem value=n.id.to_string()>
+                                <span class=class>{text}</span>
+                            </MenuItem>
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            }}
+        </Menu>
+    }
+}
 
 #[component]
 fn Profile() -> impl IntoView {
@@ -70,6 +245,7 @@ fn Profile() -> impl IntoView {
 
 #[component]
 pub fn Navbar() -> impl IntoView {
+    let auth = use_context::<AuthContext>().unwrap().0;
     let navigate = use_navigate();
     let on_select = move |key: String| {
         let nav = navigate.clone();
@@ -77,13 +253,19 @@ pub fn Navbar() -> impl IntoView {
             "home" => nav("/", Default::default()),
             "create_recipe" => nav("/recipes/create", Default::default()),
             "friends" => nav("/friends", Default::default()),
+            "feed" => nav("/feed", Default::default()),
+            "collections" => nav("/collections", Default::default()),
+            "dinner" => nav("/dinner", Default::default()),
+            "voting" => nav("/voting", Default::default()),
+            "swipe" => nav("/swipe", Default::default()),
+            "meal_plan" => nav("/meal-plan", Default::default()),
             _ => unreachable!("Should not happen"),
         }
     };
 
     view! {
         <LayoutHeader
-            class="flex align-center justify-between top-0 fixed w-full z-1000 p-4"
+            class="flex align-center justify-between top-0 fixed w-full z-1000 p-4 print:hidden"
             attr:style="background-color: var(--colorNeutralStroke1);"
         >
             <Flex>
@@ -96,6 +278,12 @@ pub fn Navbar() -> impl IntoView {
                     </MenuTrigger>
                     <MenuItem value="home">"Home"</MenuItem>
                     <MenuItem value="create_recipe">"Create recipe"</MenuItem>
+                    <MenuItem value="dinner">"What's for dinner?"</MenuItem>
+                    <MenuItem value="voting">"Vote on dinner"</MenuItem>
+                    <MenuItem value="swipe">"Swipe on dinner"</MenuItem>
+                    <MenuItem value="meal_plan">"Meal plan"</MenuItem>
+                    <MenuItem value="collections">"Collections"</MenuItem>
+                    <MenuItem value="feed">"Feed"</MenuItem>
                     <MenuItem value="friends">"Friends"</MenuItem>
                 </Menu>
 
@@ -106,6 +294,9 @@ pub fn Navbar() -> impl IntoView {
             </a>
 
             <Flex>
+                <Show when=move || matches!(auth.get(), Some(Some(_)))>
+                    <NotificationBell />
+                </Show>
                 <Profile />
             </Flex>
         </LayoutHeader>
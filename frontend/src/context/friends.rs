@@ -0,0 +1,12 @@
+use leptos::prelude::RwSignal;
+
+// Bumped by the navbar when a friend request is sent, answered or a friend is removed, so the
+// friend lists can fetch again without a websocket of their own
+#[derive(Clone, Copy)]
+pub struct FriendEvents(pub RwSignal<u32>);
+
+impl FriendEvents {
+    pub fn setup() -> Self {
+        Self(RwSignal::new(0))
+    }
+}
@@ -10,15 +10,27 @@ use thaw::{ConfigProvider, Layout, Theme};
 use crate::components::custom_route::{private_route, public_route};
 use crate::components::navbar::Navbar;
 use crate::context::auth::AuthContext;
+use crate::context::friends::FriendEvents;
 use crate::context::toast::Toaster;
 use crate::views::auth::login_page::Login;
+use crate::views::auth::register_page::Register;
+use crate::views::collections::collection::{CollectionPage, Favorites};
+use crate::views::collections::list::Collections;
+use crate::views::feed::list::Feed;
 use crate::views::friends::friends::Friends;
+use crate::views::friends::profile::FriendProfilePage;
 use crate::views::home::Home;
+use crate::views::meal_plan::list::MealPlan;
 use crate::views::profile::Profile;
 use crate::views::recipe::create_recipe::CreateRecipe;
+use crate::views::recipe::dinner::Dinner;
 use crate::views::recipe::edit_recipe::EditRecipe;
 use crate::views::recipe::recipe::RecipePage;
 use crate::views::recipe::recipes::Recipes;
+use crate::views::swipe::deck::SwipeDeck;
+use crate::views::swipe::list::SwipeSessions;
+use crate::views::voting::list::VotingSessions;
+use crate::views::voting::room::VotingRoom;
 
 mod components;
 mod context;
@@ -37,6 +49,7 @@ pub fn App() -> impl IntoView {
     let color = move || theme.get().color.color_neutral_background_1;
 
     provide_context(AuthContext::setup());
+    provide_context(FriendEvents::setup());
     provide_context(theme);
 
     view! {
@@ -46,10 +59,11 @@ pub fn App() -> impl IntoView {
                     {move || view! { <Html {..} style=format!("background-color: {}", color()) /> }}
                     <Router>
                         <Navbar />
-                        <main class="px-4 pt-20 w-full">
+                        <main class="px-4 pt-20 print:pt-0 w-full">
                             <Routes fallback=|| NotFound>
                                 <Route path=path!("/") view=private_route!(Recipes) />
                                 <Route path=path!("/login") view=public_route!(Login) />
+                                <Route path=path!("/register") view=public_route!(Register) />
                                 <Route path=path!("/profile") view=private_route!(Profile) />
                                 <Route path=path!("/settings") view=public_route!(Home) />
                                 <Route
@@ -67,6 +81,26 @@ pub fn App() -> impl IntoView {
                                 />
 
                                 <Route path=path!("/friends") view=private_route!(Friends) />
+                                <Route
+                                    path=path!("/users/:id")
+                                    view=private_route!(FriendProfilePage)
+                                />
+                                <Route
+                                    path=path!("/collections")
+                                    view=private_route!(Collections)
+                                />
+                                <Route
+                                    path=path!("/collections/:id")
+                                    view=private_route!(CollectionPage)
+                                />
+                                <Route path=path!("/favorites") view=private_route!(Favorites) />
+                                <Route path=path!("/dinner") view=private_route!(Dinner) />
+                                <Route path=path!("/voting") view=private_route!(VotingSessions) />
+                                <Route path=path!("/voting/:id") view=private_route!(VotingRoom) />
+                                <Route path=path!("/swipe") view=private_route!(SwipeSessions) />
+                                <Route path=path!("/meal-plan") view=private_route!(MealPlan) />
+                                <Route path=path!("/feed") view=private_route!(Feed) />
+                                <Route path=path!("/swipe/:id") view=private_route!(SwipeDeck) />
                             </Routes>
                         </main>
                     </Router>
@@ -1,2 +1,3 @@
 pub mod google_oauth;
 pub mod login_page;
+pub mod register_page;
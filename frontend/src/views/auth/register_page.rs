@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use common::user::{CreateUser, UserLogin};
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_query_map;
+use thaw::*;
+use web_sys::window;
+
+use crate::{
+    components::form::{form_fields::form_field_input::FormFieldInput, Form, FormGroup},
+    context::toast::{use_toast, Toast, ToastType, ToasterTrait},
+    request::post,
+};
+
+// Registers with the link from an invite, and logs in right after
+#[component]
+pub fn Register() -> impl IntoView {
+    let toast = use_toast().unwrap();
+    let query = use_query_map();
+    let invite = move || query.with(|query| query.get("invite").unwrap_or_default());
+
+    let name = RwSignal::new(String::new());
+    let email = RwSignal::new(String::new());
+    let password = RwSignal::new(String::new());
+
+    let on_submit = move |_| {
+        let user = CreateUser {
+            name: name.get_untracked(),
+            email: email.get_untracked(),
+            password: password.get_untracked(),
+        };
+        let login = UserLogin {
+            email: user.email.clone(),
+            password: user.password.clone(),
+        };
+        let url = format!("/api/register?invite={}", invite());
+
+        spawn_local(async move {
+            let body = match post(&url)
+                .body(serde_json::to_string(&user).unwrap())
+                .send()
+                .await
+            {
+                Ok(res) if res.ok() => {
+                    let _ = post("/api/login")
+                        .body(serde_json::to_string(&login).unwrap())
+                        .send()
+                        .await;
+                    window().unwrap().location().set_href("/").unwrap();
+                    return;
+                }
+                Ok(res) if res.status() == 403 => "The invite is for another email",
+                Ok(res) if res.status() == 409 => "There is already a user with this email",
+                Ok(res) if res.status() == 404 || res.status() == 410 => {
+                    "The invite is already used or has expired"
+                }
+                _ => "Failed to register",
+            };
+
+            toast.add(Toast {
+                ty: ToastType::Error,
+                body: body.to_string(),
+                timeout: Some(Duration::from_secs(5)),
+            });
+        });
+    };
+
+    view! {
+        <div class="flex justify-center h-navbar-screen">
+            <Form on_submit=on_submit>
+                <FormGroup>
+                    <FormFieldInput
+                        class="col-span-12"
+                        name="name"
+                        rules=vec![InputRule::required(true.into())]
+                        value=name
+                        placeholder="Name"
+                    />
+
+                    <FormFieldInput
+                        class="col-span-12"
+                        name="email"
+                        rules=vec![InputRule::required(true.into())]
+                        value=email
+                        placeholder="The email you were invited with"
+                    />
+
+                    <FormFieldInput
+                        class="col-span-12"
+                        name="password"
+                        input_type=InputType::Password
+                        rules=vec![InputRule::required(true.into())]
+                        value=password
+                        placeholder="Password"
+                    />
+
+                    <Button class="col-span-12" button_type=ButtonType::Submit>
+                        "Register"
+                    </Button>
+                </FormGroup>
+            </Form>
+        </div>
+    }
+}
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use common::collection::{Collection, RecipeBookmarks};
+use leptos::{prelude::*, task::spawn_local};
+use thaw::*;
+
+use crate::{
+    context::{
+        auth::AuthStore,
+        toast::{use_toast, Toast, ToastType, ToasterTrait},
+    },
+    request::{delete, get, put},
+};
+
+// Heart to toggle the recipe as a favorite, and a menu to add it to or remove it from the
+// user's own collections
+#[component]
+pub fn RecipeBookmarksControl(recipe_id: i32) -> impl IntoView {
+    let state = expect_context::<AuthStore>();
+    let toast = use_toast().unwrap();
+    let bookmarks = RwSignal::new(RecipeBookmarks::default());
+
+    spawn_local(async move {
+        if let Ok(res) = get(&format!("/api/recipes/{recipe_id}/bookmarks"))
+            .send()
+            .await
+        {
+            if let Ok(b) = res.json::<RecipeBookmarks>().await {
+                bookmarks.set(b);
+            }
+        }
+    });
+
+    let collections = LocalResource::new(move || async move {
+        match get("/api/collections").send().await {
+            Ok(res) => res
+                .json::<Vec<Collection>>()
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|it| it.user_id == state.id)
+                .collect::<Vec<_>>(),
+            Err(_) => vec![],
+        }
+    });
+
+    let send = move |url: String, add: bool| {
+        spawn_local(async move {
+            let res = match add {
+                true => put(&url).send().await,
+                false => delete(&url).send().await,
+            };
+            match res {
+                Ok(r) if r.ok() => {
+                    if let Ok(b) = r.json::<RecipeBookmarks>().await {
+                        bookmarks.set(b);
+                    }
+                }
+                _ => toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: "Failed to update the bookmarks".to_string(),
+                    timeout: Some(Duration::from_secs(5)),
+                }),
+            }
+        });
+    };
+
+    let on_favorite = move |_| {
+        let favorite = bookmarks.with_untracked(|b| b.favorite);
+        send(format!("/api/recipes/{recipe_id}/favorite"), !favorite);
+    };
+
+    let on_select = move |key: String| {
+        let Ok(collection_id) = key.parse::<i32>() else {
+            return;
+        };
+        let included = bookmarks.with_untracked(|b| b.collection_ids.contains(&collection_id));
+        send(
+            format!("/api/collections/{collection_id}/recipes/{recipe_id}"),
+            !included,
+        );
+    };
+
+    view! {
+        <Button
+            appearance=ButtonAppearance::Transparent
+            icon=Signal::derive(move || {
+                match bookmarks.with(|b| b.favorite) {
+                    true => icondata::AiHeartFilled,
+                    false => icondata::AiHeartOutlined,
+                }
+            })
+            on_click=on_favorite
+        />
+        <Menu position=MenuPosition::BottomEnd on_select=on_select>
+            <MenuTrigger slot>
+                <Button appearance=ButtonAppearance::Transparent icon=icondata::AiFolderAddOutlined />
+            </MenuTrigger>
+            {move || {
+                let collections = collections.get().as_deref().cloned().unwrap_or_default();
+                if collections.is_empty() {
+                    return view! {
+                        <MenuItem value="none" disabled=true>
+                            "No collections yet"
+                        </MenuItem>
+                    }
+                        .into_any();
+                }
+                collections
+                    .into_iter()
+                    .map(|c| {
+                        let id = c.id;
+                        view! {
+                            <MenuItem
+                                value=id.to_string()
+                                icon=MaybeProp::derive(move || {
+                                    bookmarks
+                                        .with(|b| b.collection_ids.contains(&id))
+                                        .then_some(icondata::AiCheckOutlined)
+                                })
+                            >
+                                {c.name}
+                            </MenuItem>
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .into_any()
+            }}
+        </Menu>
+    }
+}
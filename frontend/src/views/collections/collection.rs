@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use common::{collection::Collection, recipe::Recipe};
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::{use_navigate, use_params_map};
+use thaw::*;
+
+use crate::{
+    components::{loading::Loading, not_found::NotFound},
+    context::{
+        auth::AuthStore,
+        toast::{use_toast, Toast, ToastType, ToasterTrait},
+    },
+    request::{delete, get},
+    views::recipe::recipes::RecipeCard,
+};
+
+#[component]
+pub fn CollectionPage() -> impl IntoView {
+    let state = expect_context::<AuthStore>();
+    let toast = use_toast().unwrap();
+    let params = use_params_map();
+    let id = move || params.with(|params| params.get("id").unwrap_or_default());
+
+    let collection = LocalResource::new(move || async move {
+        get(&format!("/api/collections/{}", id()))
+            .send()
+            .await
+            .ok()?
+            .json::<Collection>()
+            .await
+            .ok()
+    });
+
+    let navigate = use_navigate();
+    let on_delete = move |_| {
+        let nav = navigate.clone();
+        spawn_local(async move {
+            match delete(&format!("/api/collections/{}", id())).send().await {
+                Ok(r) if r.ok() => nav("/collections", Default::default()),
+                _ => toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: "Failed to delete collection".to_string(),
+                    timeout: Some(Duration::from_secs(5)),
+                }),
+            }
+        });
+    };
+
+    view! {
+        <Transition fallback=Loading>
+            {move || {
+                let on_delete = on_delete.clone();
+                collection
+                    .get()
+                    .as_deref()
+                    .cloned()
+                    .map(|data| match data {
+                        None => NotFound.into_any(),
+                        Some(c) => {
+                            let is_owner = c.user_id == state.id;
+                            view! {
+                                <div class="mx-auto w-[80%] max-w-screen-xl flex flex-col gap-4">
+                                    <Flex justify=FlexJustify::SpaceBetween align=FlexAlign::Center>
+                                        <div>
+                                            <h1 class="text-2xl">{c.name}</h1>
+                                            <p>{c.description}</p>
+                                        </div>
+                                        {is_owner
+                                            .then(|| {
+                                                view! {
+                                                    <Button on:click=on_delete>
+                                                        "Delete collection"
+                                                    </Button>
+                                                }
+                                            })}
+                                    </Flex>
+                                    <RecipeGrid url=format!("/api/collections/{}/recipes", c.id) />
+                                </div>
+                            }
+                                .into_any()
+                        }
+                    })
+            }}
+        </Transition>
+    }
+}
+
+#[component]
+pub fn Favorites() -> impl IntoView {
+    view! {
+        <div class="mx-auto w-[80%] max-w-screen-xl flex flex-col gap-4">
+            <h1 class="text-2xl">"Favorites"</h1>
+            <RecipeGrid url="/api/favorites".to_string() />
+        </div>
+    }
+}
+
+#[component]
+fn RecipeGrid(url: String) -> impl IntoView {
+    let recipes = LocalResource::new(move || {
+        let url = url.clone();
+        async move {
+            match get(&url).send().await {
+                Ok(res) => res.json::<Vec<Recipe>>().await.unwrap_or_default(),
+                Err(_) => vec![],
+            }
+        }
+    });
+
+    view! {
+        <div class="grid grid-cols-12 gap-8">
+            <Transition fallback=Loading>
+                {move || {
+                    let recipes = recipes.get().as_deref().cloned().unwrap_or_default();
+                    if recipes.is_empty() {
+                        return view! { <p class="col-span-12">"No recipes here yet"</p> }.into_any();
+                    }
+                    recipes
+                        .into_iter()
+                        .map(|recipe| {
+                            view! {
+                                <div class="col-span-12 sm:col-span-6 lg:col-span-4">
+                                    <RecipeCard recipe />
+                                </div>
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .into_any()
+                }}
+            </Transition>
+        </div>
+    }
+}
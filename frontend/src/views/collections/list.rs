@@ -0,0 +1,189 @@
+use std::time::Duration;
+
+use common::{
+    collection::{Collection, CreateCollection},
+    recipe::RecipeVisibility,
+    strum::IntoEnumIterator,
+};
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_navigate;
+use thaw::*;
+
+use crate::{
+    components::{
+        form::{
+            form_fields::{form_field_combobox::FormFieldSelect, form_field_input::FormFieldInput},
+            FormGroup,
+        },
+        loading::Loading,
+    },
+    context::{
+        auth::AuthStore,
+        toast::{use_toast, Toast, ToastType, ToasterTrait},
+    },
+    request::{get, post},
+};
+
+#[component]
+pub fn Collections() -> impl IntoView {
+    let state = expect_context::<AuthStore>();
+    let toast = use_toast().unwrap();
+
+    let collections = LocalResource::new(move || async move {
+        match get("/api/collections").send().await {
+            Ok(res) => res.json::<Vec<Collection>>().await.unwrap_or_default(),
+            Err(_) => vec![],
+        }
+    });
+
+    let new_collection = RwSignal::new(CreateCollection::default());
+    let name = slice!(new_collection.name);
+    let description = create_slice(
+        new_collection,
+        |c| c.description.clone().unwrap_or_default(),
+        |c, s: String| c.description = (!s.is_empty()).then_some(s),
+    );
+    let visibility = create_slice(
+        new_collection,
+        |c| c.visibility.to_string(),
+        |c, n: String| {
+            c.visibility = n
+                .parse::<RecipeVisibility>()
+                .unwrap_or(RecipeVisibility::Friends)
+        },
+    );
+
+    let on_create = move |_| {
+        let body = serde_json::to_string(&new_collection.get_untracked()).unwrap();
+        spawn_local(async move {
+            match post("/api/collections").body(body).send().await {
+                Ok(r) if r.ok() => {
+                    new_collection.set(CreateCollection::default());
+                    collections.refetch();
+                }
+                Ok(r) if r.status() == 409 => toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: "You already have a collection with that name".to_string(),
+                    timeout: Some(Duration::from_secs(5)),
+                }),
+                _ => toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: "Failed to create collection".to_string(),
+                    timeout: Some(Duration::from_secs(5)),
+                }),
+            }
+        });
+    };
+
+    view! {
+        <div class="mx-auto w-[80%] max-w-screen-xl flex flex-col gap-8">
+            <Card>
+                <h1 class="text-2xl">"New collection"</h1>
+                <FormGroup>
+                    <FormFieldInput
+                        class="col-span-12 md:col-span-4"
+                        placeholder="Name"
+                        value=name
+                    />
+                    <FormFieldInput
+                        class="col-span-12 md:col-span-5"
+                        placeholder="Description"
+                        value=description
+                    />
+                    <FormFieldSelect
+                        class="col-span-12 md:col-span-3"
+                        value=visibility
+                        placeholder="Visibility"
+                    >
+                        {move || {
+                            RecipeVisibility::iter()
+                                .map(|u| {
+                                    view! {
+                                        <ComboboxOption text=u.to_string() value=u.to_string()>
+                                            {u.to_string()}
+                                        </ComboboxOption>
+                                    }
+                                })
+                                .collect::<Vec<_>>()
+                        }}
+                    </FormFieldSelect>
+                    <Button
+                        class="col-span-12"
+                        appearance=ButtonAppearance::Primary
+                        disabled=Signal::derive(move || name.0.get().trim().is_empty())
+                        on_click=on_create
+                    >
+                        "Create collection"
+                    </Button>
+                </FormGroup>
+            </Card>
+
+            <div class="grid grid-cols-12 gap-8">
+                <div class="col-span-12 sm:col-span-6 lg:col-span-4">
+                    <CollectionCard
+                        href="/favorites".to_string()
+                        name="Favorites".to_string()
+                        description=None
+                        subtitle="Recipes you have marked with a heart".to_string()
+                    />
+                </div>
+                <Transition fallback=Loading>
+                    {move || {
+                        collections
+                            .get()
+                            .as_deref()
+                            .cloned()
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|c| {
+                                let subtitle = match c.user_id == state.id {
+                                    true => format!("{} · {}", recipe_count(&c), c.visibility),
+                                    false => format!("{} · Shared by a friend", recipe_count(&c)),
+                                };
+                                view! {
+                                    <div class="col-span-12 sm:col-span-6 lg:col-span-4">
+                                        <CollectionCard
+                                            href=format!("/collections/{}", c.id)
+                                            name=c.name
+                                            description=c.description
+                                            subtitle
+                                        />
+                                    </div>
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    }}
+                </Transition>
+            </div>
+        </div>
+    }
+}
+
+fn recipe_count(collection: &Collection) -> String {
+    match collection.recipe_ids.len() {
+        1 => "1 recipe".to_string(),
+        n => format!("{n} recipes"),
+    }
+}
+
+#[component]
+fn CollectionCard(
+    href: String,
+    name: String,
+    description: Option<String>,
+    subtitle: String,
+) -> impl IntoView {
+    let navigate = use_navigate();
+
+    view! {
+        <Card class="cursor-pointer" on:click=move |_| navigate(&href, Default::default())>
+            <CardHeader>
+                <Body1>{name}</Body1>
+                <CardHeaderDescription slot>
+                    <Caption1>{subtitle}</Caption1>
+                </CardHeaderDescription>
+            </CardHeader>
+            {description.map(|d| view! { <p>{d}</p> })}
+        </Card>
+    }
+}
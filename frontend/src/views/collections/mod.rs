@@ -0,0 +1,3 @@
+pub mod bookmarks;
+pub mod collection;
+pub mod list;
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use common::feed::CookRecipe;
+use leptos::{prelude::*, task::spawn_local};
+use thaw::*;
+
+use crate::{
+    context::toast::{use_toast, Toast, ToastType, ToasterTrait},
+    request::post,
+};
+
+// Button with a dialog to log that the recipe was cooked, with an optional rating
+#[component]
+pub fn CookedButton(recipe_id: i32) -> impl IntoView {
+    let toast = use_toast().unwrap();
+    let open = RwSignal::new(false);
+    let rating = RwSignal::new(None::<f32>);
+
+    let on_cooked = move |_| {
+        let body = serde_json::to_string(&CookRecipe {
+            rating: rating.get_untracked().map(|it| it as i32),
+        })
+        .unwrap();
+
+        spawn_local(async move {
+            match post(&format!("/api/recipes/{recipe_id}/cooked"))
+                .body(body)
+                .send()
+                .await
+            {
+                Ok(r) if r.ok() => {
+                    open.set(false);
+                    rating.set(None);
+                    toast.add(Toast {
+                        ty: ToastType::Success,
+                        body: "Marked as cooked".to_string(),
+                        timeout: Some(Duration::from_secs(5)),
+                    });
+                }
+                _ => toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: "Failed to mark as cooked".to_string(),
+                    timeout: Some(Duration::from_secs(5)),
+                }),
+            }
+        });
+    };
+
+    view! {
+        <Button
+            appearance=ButtonAppearance::Transparent
+            icon=icondata::LuChefHat
+            on_click=move |_| open.set(true)
+        />
+        <Dialog open>
+            <DialogSurface>
+                <DialogBody>
+                    <DialogTitle>"Cooked it"</DialogTitle>
+                    <DialogContent>
+                        <Flex vertical=true>
+                            <Body1>"How was it? The rating is optional"</Body1>
+                            <Rating value=rating />
+                        </Flex>
+                    </DialogContent>
+                    <DialogActions>
+                        <Button appearance=ButtonAppearance::Primary on_click=on_cooked>
+                            "Save"
+                        </Button>
+                        <Button on_click=move |_| open.set(false)>"Cancel"</Button>
+                    </DialogActions>
+                </DialogBody>
+            </DialogSurface>
+        </Dialog>
+    }
+}
@@ -0,0 +1,113 @@
+use chrono::{Local, SecondsFormat, Utc};
+use common::feed::{FeedItem, FeedKind};
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_navigate;
+use thaw::*;
+
+use crate::{components::loading::Loading, request::get};
+
+const PAGE_SIZE: usize = 20;
+
+async fn fetch_feed(before: Option<&FeedItem>) -> Vec<FeedItem> {
+    // Formatted in UTC so the timestamp does not contain a `+` that needs to be url encoded
+    let url = match before {
+        Some(item) => format!(
+            "/api/feed?limit={PAGE_SIZE}&before={}&before_kind={:?}&before_id={}",
+            item.at
+                .with_timezone(&Utc)
+                .to_rfc3339_opts(SecondsFormat::Micros, true),
+            item.kind,
+            item.id,
+        ),
+        None => format!("/api/feed?limit={PAGE_SIZE}"),
+    };
+
+    match get(&url).send().await {
+        Ok(res) => res.json::<Vec<FeedItem>>().await.unwrap_or_default(),
+        Err(_) => vec![],
+    }
+}
+
+#[component]
+pub fn Feed() -> impl IntoView {
+    let first = LocalResource::new(move || async move { fetch_feed(None).await });
+    let more = RwSignal::new(Vec::<FeedItem>::new());
+    let has_more = RwSignal::new(true);
+
+    let items = move || {
+        let mut items = first.get().as_deref().cloned().unwrap_or_default();
+        items.extend(more.get());
+        items
+    };
+
+    let on_load_more = move |_| {
+        let items = items();
+        spawn_local(async move {
+            let page = fetch_feed(items.last()).await;
+            has_more.set(page.len() == PAGE_SIZE);
+            more.update(|more| more.extend(page));
+        });
+    };
+
+    view! {
+        <div class="mx-auto w-[80%] max-w-screen-xl flex flex-col gap-4">
+            <h1 class="text-2xl">"Feed"</h1>
+            <Transition fallback=Loading>
+                {move || {
+                    let items = items();
+                    if items.is_empty() {
+                        return view! {
+                            <p>"Nothing here yet. Add some friends to see what they are cooking"</p>
+                        }
+                            .into_any();
+                    }
+                    let show_more = has_more.get() && items.len() >= PAGE_SIZE;
+                    view! {
+                        {items
+                            .into_iter()
+                            .map(|item| view! { <FeedItemCard item /> })
+                            .collect::<Vec<_>>()}
+                        <Show when=move || show_more>
+                            <Button on_click=on_load_more>"Load more"</Button>
+                        </Show>
+                    }
+                        .into_any()
+                }}
+            </Transition>
+        </div>
+    }
+}
+
+#[component]
+fn FeedItemCard(item: FeedItem) -> impl IntoView {
+    let navigate = use_navigate();
+    let href = format!("/recipes/{}", item.recipe_id);
+
+    let action = match item.kind {
+        FeedKind::RecipeCreated => "created",
+        FeedKind::RecipeUpdated => "updated",
+        FeedKind::RecipeCooked => "cooked",
+    };
+    let at = item.at.with_timezone(&Local);
+
+    view! {
+        <Card>
+            <CardHeader>
+                <Body1
+                    class="cursor-pointer"
+                    on:click=move |_| navigate(&href, Default::default())
+                >
+                    {format!("{} {} {}", item.user_name, action, item.recipe_name)}
+                </Body1>
+                <CardHeaderDescription slot>
+                    <Caption1>{at.format("%A %e %B %H:%M").to_string()}</Caption1>
+                </CardHeaderDescription>
+                <CardHeaderAction slot>
+                    {item
+                        .rating
+                        .map(|rating| view! { <RatingDisplay value=rating as f32 /> })}
+                </CardHeaderAction>
+            </CardHeader>
+        </Card>
+    }
+}
@@ -0,0 +1,2 @@
+pub mod cooked;
+pub mod list;
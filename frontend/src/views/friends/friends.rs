@@ -1,16 +1,236 @@
 use std::time::Duration;
 
-use common::{friendship::FriendshipStatus, user::UserWithRelation};
+use common::{
+    friendship::{FriendSuggestion, FriendshipStatus},
+    invite::{CreateInvite, Invite},
+    user::UserWithRelation,
+};
 use leptos::{prelude::*, task::spawn_local};
 use thaw::*;
 
 use crate::{
-    context::toast::{use_toast, Toast, ToastType, ToasterTrait},
+    components::loading::Loading,
+    context::{
+        friends::FriendEvents,
+        toast::{use_toast, Toast, ToastType, ToasterTrait},
+    },
     request::{get, post},
+    views::profile::PendingRequests,
 };
 
 #[component]
 pub fn Friends() -> impl IntoView {
+    let selected = RwSignal::new("friends".to_string());
+
+    view! {
+        <div>
+            <TabList selected_value=selected>
+                <Tab value="friends">"Friends"</Tab>
+                <Tab value="requests">"Requests"</Tab>
+                <Tab value="sent">"Sent"</Tab>
+                <Tab value="suggestions">"Suggestions"</Tab>
+                <Tab value="find">"Find friends"</Tab>
+                <Tab value="invite">"Invite"</Tab>
+                <Tab value="blocked">"Blocked"</Tab>
+            </TabList>
+            {move || match selected.get().as_str() {
+                "requests" => view! { <PendingRequests /> }.into_any(),
+                "sent" => {
+                    view! {
+                        <FriendList
+                            url="/api/friends/outgoing"
+                            action="Cancel"
+                            action_url="/api/friends/cancel"
+                        />
+                    }
+                        .into_any()
+                }
+                "suggestions" => view! { <FriendSuggestions /> }.into_any(),
+                "find" => view! { <FindFriends /> }.into_any(),
+                "invite" => view! { <InviteFriends /> }.into_any(),
+                "blocked" => {
+                    view! {
+                        <FriendList
+                            url="/api/friends/blocked"
+                            action="Unblock"
+                            action_url="/api/friends/unblock"
+                        />
+                    }
+                        .into_any()
+                }
+                _ => {
+                    view! {
+                        <FriendList
+                            url="/api/friends"
+                            action="Remove"
+                            action_url="/api/friends/remove"
+                            link_profile=true
+                        />
+                    }
+                        .into_any()
+                }
+            }}
+        </div>
+    }
+}
+
+// Lists the users from `url`, with a button that posts to `action_url` for the user. The names link
+// to the profile of the user with `link_profile`, which is only viewable for friends
+#[component]
+fn FriendList(
+    url: &'static str,
+    action: &'static str,
+    action_url: &'static str,
+    #[prop(optional)] link_profile: bool,
+) -> impl IntoView {
+    let toast = use_toast().unwrap();
+
+    let friend_events = expect_context::<FriendEvents>().0;
+
+    let users = LocalResource::new(move || async move {
+        friend_events.track();
+        match get(url).send().await {
+            Ok(res) => res
+                .json::<Vec<UserWithRelation>>()
+                .await
+                .unwrap_or_default(),
+            Err(_) => vec![],
+        }
+    });
+
+    let on_click = move |id: i32| {
+        spawn_local(async move {
+            match post(&format!("{action_url}/{id}")).send().await {
+                Ok(res) if res.ok() => users.refetch(),
+                _ => toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: format!("Failed to {}", action.to_lowercase()),
+                    timeout: Some(Duration::from_secs(5)),
+                }),
+            }
+        })
+    };
+
+    view! {
+        <ul>
+            <Transition fallback=Loading>
+                {move || {
+                    users
+                        .get()
+                        .as_deref()
+                        .cloned()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|u| {
+                            view! {
+                                <li>
+                                    <Card>
+                                        <CardHeader>
+                                            {if link_profile {
+                                                view! {
+                                                    <a href=format!("/users/{}", u.id) class="underline">
+                                                        {u.name}
+                                                    </a>
+                                                }
+                                                    .into_any()
+                                            } else {
+                                                view! { <p>{u.name}</p> }.into_any()
+                                            }}
+                                            <CardHeaderAction slot>
+                                                <Button
+                                                    button_type=ButtonType::Button
+                                                    on:click=move |_| on_click(u.id)
+                                                >
+                                                    {action}
+                                                </Button>
+                                            </CardHeaderAction>
+                                        </CardHeader>
+                                    </Card>
+                                </li>
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                }}
+            </Transition>
+        </ul>
+    }
+}
+
+// What the user has in common with the suggested user, e.g. "2 mutual friends · 1 similar recipe"
+fn suggestion_reason(suggestion: &FriendSuggestion) -> String {
+    let plural = |count: i64, one: &str, many: &str| match count {
+        1 => format!("1 {one}"),
+        _ => format!("{count} {many}"),
+    };
+
+    [
+        (suggestion.mutual_friends, "mutual friend", "mutual friends"),
+        (
+            suggestion.shared_sessions,
+            "shared session",
+            "shared sessions",
+        ),
+        (
+            suggestion.similar_recipes,
+            "similar recipe",
+            "similar recipes",
+        ),
+    ]
+    .into_iter()
+    .filter(|(count, _, _)| *count > 0)
+    .map(|(count, one, many)| plural(count, one, many))
+    .collect::<Vec<_>>()
+    .join(" · ")
+}
+
+#[component]
+fn FriendSuggestions() -> impl IntoView {
+    let suggestions = LocalResource::new(move || async move {
+        match get("/api/friends/suggestions").send().await {
+            Ok(res) => res
+                .json::<Vec<FriendSuggestion>>()
+                .await
+                .unwrap_or_default(),
+            Err(_) => vec![],
+        }
+    });
+
+    view! {
+        <ul>
+            <Transition fallback=Loading>
+                {move || {
+                    let suggestions = suggestions.get().as_deref().cloned().unwrap_or_default();
+                    if suggestions.is_empty() {
+                        return view! { <p>"No suggestions right now"</p> }.into_any();
+                    }
+                    suggestions
+                        .into_iter()
+                        .map(|suggestion| {
+                            let description = suggestion_reason(&suggestion);
+                            let user = UserWithRelation {
+                                id: suggestion.id,
+                                name: suggestion.name,
+                                email: suggestion.email,
+                                requester_id: None,
+                                recipient_id: None,
+                                status: None,
+                            };
+                            view! {
+                                <li>
+                                    <UserList user description />
+                                </li>
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .into_any()
+                }}
+            </Transition>
+        </ul>
+    }
+}
+
+#[component]
+fn FindFriends() -> impl IntoView {
     let search = RwSignal::new(String::new());
     let toast = use_toast().unwrap();
     let users: RwSignal<Vec<UserWithRelation>> = RwSignal::new(vec![]);
@@ -57,14 +277,105 @@ pub fn Friends() -> impl IntoView {
     }
 }
 
+// Invites someone who is not on Foodie yet by email, and lists the invites the user has sent
+#[component]
+fn InviteFriends() -> impl IntoView {
+    let toast = use_toast().unwrap();
+    let email = RwSignal::new(String::new());
+
+    let invites = LocalResource::new(move || async move {
+        match get("/api/invites").send().await {
+            Ok(res) => res.json::<Vec<Invite>>().await.unwrap_or_default(),
+            Err(_) => vec![],
+        }
+    });
+
+    let on_click = move |_| {
+        let body = serde_json::to_string(&CreateInvite {
+            email: email.get_untracked(),
+        })
+        .unwrap();
+
+        spawn_local(async move {
+            let (ty, body) = match post("/api/invites").body(body).send().await {
+                Ok(res) if res.ok() => {
+                    email.set(String::new());
+                    invites.refetch();
+                    (ToastType::Success, "Invite sent")
+                }
+                Ok(res) if res.status() == 400 => (ToastType::Error, "Not a valid email"),
+                Ok(res) if res.status() == 409 => (
+                    ToastType::Warning,
+                    "They are already on Foodie. Find them instead",
+                ),
+                _ => (ToastType::Error, "Failed to send invite"),
+            };
+
+            toast.add(Toast {
+                ty,
+                body: body.to_string(),
+                timeout: Some(Duration::from_secs(5)),
+            });
+        })
+    };
+
+    view! {
+        <div>
+            <Input value=email input_type=InputType::Email placeholder="Email" />
+            <Button on_click=on_click>"Send invite"</Button>
+            <ul>
+                <Transition fallback=Loading>
+                    {move || {
+                        invites
+                            .get()
+                            .as_deref()
+                            .cloned()
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|invite| {
+                                let status = if invite.accepted {
+                                    "Joined".to_string()
+                                } else if invite.expires_at < chrono::Utc::now() {
+                                    "Expired".to_string()
+                                } else {
+                                    format!("Expires {}", invite.expires_at.format("%e %B"))
+                                };
+                                view! {
+                                    <li>
+                                        <Card>
+                                            <CardHeader>
+                                                <p>{invite.email}</p>
+                                                <CardHeaderDescription slot>
+                                                    <Caption1>{status}</Caption1>
+                                                </CardHeaderDescription>
+                                            </CardHeader>
+                                        </Card>
+                                    </li>
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    }}
+                </Transition>
+            </ul>
+        </div>
+    }
+}
+
 #[component]
-pub fn UserList(user: UserWithRelation) -> impl IntoView {
+pub fn UserList(
+    user: UserWithRelation,
+    #[prop(optional, into)] description: Option<String>,
+) -> impl IntoView {
     let (status, set_status) = signal(user.status);
+    let (blocked, set_blocked) = signal(false);
+    let name = StoredValue::new(user.name);
+    let description = StoredValue::new(description);
     let button_text = move || match status() {
         Some(s) => match s {
             FriendshipStatus::Pending => "Pending",
             FriendshipStatus::Accepted => "Accepted",
-            FriendshipStatus::Rejected => "Rejected",
+            // A rejected request can be sent again
+            FriendshipStatus::Rejected => "Add friend",
             FriendshipStatus::Blocked => "Blocked",
         },
         None => "Add friend",
@@ -72,47 +383,62 @@ pub fn UserList(user: UserWithRelation) -> impl IntoView {
 
     let toast = use_toast().unwrap();
     let on_click = move |id: i32| {
-        if status().is_some() {
+        if !matches!(status(), None | Some(FriendshipStatus::Rejected)) {
             return;
         }
 
         spawn_local(async move {
-            // Only allow to send a request if they have no status
+            // Only allow to send a request if they have no status, or it was rejected
             let res = post(&format!("/api/friends/new/{id}")).send().await;
 
-            let Ok(res) = res else {
-                toast.add(Toast {
-                    ty: ToastType::Error,
-                    body: "Failed to send friend request".to_string(),
-                    timeout: Some(Duration::from_secs(5)),
-                });
-                return;
+            let body = match res {
+                Ok(res) if res.ok() => {
+                    set_status(Some(FriendshipStatus::Pending));
+                    return;
+                }
+                Ok(res) if res.status() == 429 => "You can send a new friend request later",
+                _ => "Failed to send friend request",
             };
 
-            if !res.ok() {
-                toast.add(Toast {
+            toast.add(Toast {
+                ty: ToastType::Error,
+                body: body.to_string(),
+                timeout: Some(Duration::from_secs(5)),
+            });
+        })
+    };
+
+    let on_block = move |id: i32| {
+        spawn_local(async move {
+            match post(&format!("/api/friends/block/{id}")).send().await {
+                Ok(res) if res.ok() => set_blocked(true),
+                _ => toast.add(Toast {
                     ty: ToastType::Error,
-                    body: "Failed to send friend request".to_string(),
+                    body: "Failed to block user".to_string(),
                     timeout: Some(Duration::from_secs(5)),
-                });
-                return;
+                }),
             }
-
-            set_status(Some(FriendshipStatus::Pending));
         })
     };
 
     view! {
-        <Card>
-            <CardHeader>
-                <p>{user.name}</p>
-                <CardHeaderAction slot>
-                    <Button button_type=ButtonType::Button on:click=move |_| on_click(user.id)>
-                        {move || button_text()}
-                    </Button>
-                </CardHeaderAction>
-            </CardHeader>
-
-        </Card>
+        <Show when=move || !blocked()>
+            <Card>
+                <CardHeader>
+                    <p>{name.get_value()}</p>
+                    <CardHeaderDescription slot>
+                        <Caption1>{description.get_value()}</Caption1>
+                    </CardHeaderDescription>
+                    <CardHeaderAction slot>
+                        <Button button_type=ButtonType::Button on:click=move |_| on_click(user.id)>
+                            {move || button_text()}
+                        </Button>
+                        <Button button_type=ButtonType::Button on:click=move |_| on_block(user.id)>
+                            "Block"
+                        </Button>
+                    </CardHeaderAction>
+                </CardHeader>
+            </Card>
+        </Show>
     }
 }
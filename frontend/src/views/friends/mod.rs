@@ -1 +1,2 @@
 pub mod friends;
+pub mod profile;
@@ -0,0 +1,73 @@
+use common::user::FriendProfile;
+use leptos::prelude::*;
+use leptos_router::hooks::use_params_map;
+use thaw::*;
+
+use crate::components::loading::Loading;
+use crate::components::not_found::NotFound;
+use crate::request::get;
+use crate::views::recipe::recipes::RecipeCard;
+
+#[component]
+pub fn FriendProfilePage() -> impl IntoView {
+    let params = use_params_map();
+    let id = move || params.with(|params| params.get("id").unwrap_or_default());
+
+    let profile = LocalResource::new(move || async move {
+        get(&format!("/api/users/{}/profile", id()))
+            .send()
+            .await
+            .ok()?
+            .json::<FriendProfile>()
+            .await
+            .ok()
+    });
+
+    let _profile = move || profile.get().as_deref().map(|it| it.to_owned());
+
+    view! {
+        <Transition fallback=Loading>
+            {move || {
+                _profile()
+                    .map(|data| match data {
+                        None => NotFound.into_any(),
+                        Some(p) => view! { <Profile profile=p /> }.into_any(),
+                    })
+            }}
+
+        </Transition>
+    }
+}
+
+// The profile of a friend, with the recipes they share with the user
+#[component]
+fn Profile(profile: FriendProfile) -> impl IntoView {
+    let FriendProfile { profile, recipes } = profile;
+    let name = profile.display_name.unwrap_or(profile.name);
+
+    view! {
+        <div class="mx-auto w-[80%] max-w-screen-xl">
+            <Flex vertical=true class="mb-8">
+                <Flex align=FlexAlign::Center>
+                    <Avatar src=profile.avatar name=name.clone() size=72 />
+                    <Body1 class="text-xl font-semibold">{name}</Body1>
+                </Flex>
+                {profile.bio.map(|bio| view! { <Body1 class="whitespace-pre-line">{bio}</Body1> })}
+            </Flex>
+
+            <div class="grid grid-cols-12 gap-8">
+                <For
+                    each=move || recipes.clone()
+                    key=|recipe| recipe.id
+                    children=move |recipe| {
+                        view! {
+                            <div class="col-span-12 sm:col-span-6 lg:col-span-4">
+                                <RecipeCard recipe=recipe.clone() />
+                            </div>
+                        }
+                    }
+                />
+            </div>
+        </div>
+    }
+}
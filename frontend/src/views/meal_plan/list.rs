@@ -0,0 +1,179 @@
+use std::time::Duration;
+
+use chrono::{Local, TimeDelta};
+use common::meal_plan::{CalendarFeed, PlannedMeal};
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_navigate;
+use thaw::*;
+
+use crate::{
+    components::loading::Loading,
+    context::toast::{use_toast, Toast, ToastType, ToasterTrait},
+    request::{delete, get, post},
+};
+
+#[component]
+pub fn MealPlan() -> impl IntoView {
+    let meals = LocalResource::new(move || async move {
+        match get("/api/meal-plan").send().await {
+            Ok(res) => res.json::<Vec<PlannedMeal>>().await.unwrap_or_default(),
+            Err(_) => vec![],
+        }
+    });
+
+    view! {
+        <div class="mx-auto w-[80%] max-w-screen-xl flex flex-col gap-8">
+            <CalendarFeedCard />
+            <div class="flex flex-col gap-4">
+                <h1 class="text-2xl">"Meal plan"</h1>
+                <Transition fallback=Loading>
+                    {move || {
+                        let planned = meals.get().as_deref().cloned().unwrap_or_default();
+                        if planned.is_empty() {
+                            return view! {
+                                <p>"Nothing planned yet. Plan a meal from the page of a recipe"</p>
+                            }
+                                .into_any();
+                        }
+                        planned
+                            .into_iter()
+                            .map(|meal| {
+                                view! {
+                                    <PlannedMealCard
+                                        meal
+                                        on_delete=Callback::new(move |_| meals.refetch())
+                                    />
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                            .into_any()
+                    }}
+                </Transition>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn PlannedMealCard(meal: PlannedMeal, on_delete: Callback<()>) -> impl IntoView {
+    let toast = use_toast().unwrap();
+    let navigate = use_navigate();
+    let href = format!("/recipes/{}", meal.recipe_id);
+
+    let eat_at = meal.eat_at.with_timezone(&Local);
+    let subtitle = match meal.total_time {
+        Some(total_time) => {
+            let start = eat_at - TimeDelta::minutes(total_time.as_minutes() as i64);
+            format!(
+                "Start cooking at {} · Ready at {}",
+                start.format("%H:%M"),
+                eat_at.format("%H:%M")
+            )
+        }
+        None => format!("Ready at {}", eat_at.format("%H:%M")),
+    };
+
+    let on_click = move |_| {
+        spawn_local(async move {
+            match delete(&format!("/api/meal-plan/{}", meal.id)).send().await {
+                Ok(r) if r.ok() => on_delete.run(()),
+                _ => toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: "Failed to remove the meal".to_string(),
+                    timeout: Some(Duration::from_secs(5)),
+                }),
+            }
+        });
+    };
+
+    view! {
+        <Card>
+            <CardHeader>
+                <Body1
+                    class="cursor-pointer"
+                    on:click=move |_| navigate(&href, Default::default())
+                >
+                    {format!("{} · {}", eat_at.format("%A %e %B"), meal.name)}
+                </Body1>
+                <CardHeaderDescription slot>
+                    <Caption1>{subtitle}</Caption1>
+                </CardHeaderDescription>
+                <CardHeaderAction slot>
+                    <Button
+                        appearance=ButtonAppearance::Transparent
+                        icon=icondata::AiDeleteOutlined
+                        on_click=on_click
+                    />
+                </CardHeaderAction>
+            </CardHeader>
+        </Card>
+    }
+}
+
+// The link to subscribe to the meal plan from a calendar app
+#[component]
+fn CalendarFeedCard() -> impl IntoView {
+    let toast = use_toast().unwrap();
+    let feed = RwSignal::new(None::<CalendarFeed>);
+    let reminders = RwSignal::new(true);
+
+    spawn_local(async move {
+        if let Ok(res) = get("/api/meal-plan/calendar").send().await {
+            feed.set(res.json::<CalendarFeed>().await.ok());
+        }
+    });
+
+    let url = move || {
+        feed.with(|it| {
+            it.as_ref().map(|feed| match reminders.get() {
+                true => feed.url.clone(),
+                false => format!("{}?reminders=false", feed.url),
+            })
+        })
+    };
+
+    let on_reset = move |_| {
+        spawn_local(async move {
+            match post("/api/meal-plan/calendar/reset").send().await {
+                Ok(r) if r.ok() => {
+                    feed.set(r.json::<CalendarFeed>().await.ok());
+                    toast.add(Toast {
+                        ty: ToastType::Success,
+                        body: "The old link no longer works".to_string(),
+                        timeout: Some(Duration::from_secs(5)),
+                    });
+                }
+                _ => toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: "Failed to reset the link".to_string(),
+                    timeout: Some(Duration::from_secs(5)),
+                }),
+            }
+        });
+    };
+
+    view! {
+        <Card>
+            <h1 class="text-2xl">"Calendar"</h1>
+            <Body1>
+                "Subscribe to the meal plan in your calendar app. Anyone with the link can see the plan, so reset it if it has been shared by mistake"
+            </Body1>
+            <Checkbox checked=reminders label="Prep reminders for recipes that have to rest" />
+            {move || {
+                url()
+                    .map(|url| {
+                        let webcal = url.replacen("https://", "webcal://", 1).replacen("http://", "webcal://", 1);
+                        view! {
+                            <Caption1 class="break-all">{url}</Caption1>
+                            <Flex>
+                                <a href=webcal>
+                                    <Button appearance=ButtonAppearance::Primary>"Subscribe"</Button>
+                                </a>
+                                <Button on_click=on_reset>"Reset link"</Button>
+                            </Flex>
+                        }
+                    })
+            }}
+        </Card>
+    }
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use chrono::{Local, NaiveTime, TimeZone};
+use common::meal_plan::CreatePlannedMeal;
+use leptos::{prelude::*, task::spawn_local};
+use thaw::*;
+
+use crate::{
+    context::toast::{use_toast, Toast, ToastType, ToasterTrait},
+    request::post,
+};
+
+// Button with a dialog to pick when the recipe should be ready to eat, and add it to the meal plan
+#[component]
+pub fn PlanMealButton(recipe_id: i32) -> impl IntoView {
+    let toast = use_toast().unwrap();
+    let open = RwSignal::new(false);
+    let date = RwSignal::new(Some(Local::now().date_naive()));
+    let time = RwSignal::new(NaiveTime::from_hms_opt(18, 0, 0));
+
+    let on_plan = move |_| {
+        let eat_at = date
+            .get_untracked()
+            .zip(time.get_untracked())
+            .and_then(|(date, time)| Local.from_local_datetime(&date.and_time(time)).earliest());
+        let Some(eat_at) = eat_at else {
+            return;
+        };
+
+        let body = serde_json::to_string(&CreatePlannedMeal {
+            recipe_id,
+            eat_at: eat_at.fixed_offset(),
+        })
+        .unwrap();
+
+        spawn_local(async move {
+            match post("/api/meal-plan").body(body).send().await {
+                Ok(r) if r.ok() => {
+                    open.set(false);
+                    toast.add(Toast {
+                        ty: ToastType::Success,
+                        body: "Added to the meal plan".to_string(),
+                        timeout: Some(Duration::from_secs(5)),
+                    });
+                }
+                _ => toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: "Failed to add to the meal plan".to_string(),
+                    timeout: Some(Duration::from_secs(5)),
+                }),
+            }
+        });
+    };
+
+    view! {
+        <Button
+            appearance=ButtonAppearance::Transparent
+            icon=icondata::AiCalendarOutlined
+            on_click=move |_| open.set(true)
+        />
+        <Dialog open>
+            <DialogSurface>
+                <DialogBody>
+                    <DialogTitle>"Plan meal"</DialogTitle>
+                    <DialogContent>
+                        <Flex vertical=true>
+                            <Body1>"When should it be ready to eat?"</Body1>
+                            <DatePicker value=date />
+                            <TimePicker value=time />
+                        </Flex>
+                    </DialogContent>
+                    <DialogActions>
+                        <Button
+                            appearance=ButtonAppearance::Primary
+                            disabled=Signal::derive(move || {
+                                date.with(Option::is_none) || time.with(Option::is_none)
+                            })
+                            on_click=on_plan
+                        >
+                            "Plan"
+                        </Button>
+                        <Button on_click=move |_| open.set(false)>"Cancel"</Button>
+                    </DialogActions>
+                </DialogBody>
+            </DialogSurface>
+        </Dialog>
+    }
+}
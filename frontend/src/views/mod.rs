@@ -1,5 +1,10 @@
 pub mod auth;
+pub mod collections;
+pub mod feed;
 pub mod friends;
 pub mod home;
+pub mod meal_plan;
 pub mod profile;
 pub mod recipe;
+pub mod swipe;
+pub mod voting;
@@ -2,12 +2,20 @@ use leptos::task::spawn_local;
 use std::time::Duration;
 use thaw::*;
 
-use common::user::UserWithRelation;
+use common::{recipe::RecipeImage, strum::IntoEnumIterator};
+use common::{
+    takeout::TakeoutImport,
+    user::{
+        Profile as UserProfile, UnitSystem, UpdateAvatar, UpdateProfile, User, UserWithRelation,
+    },
+};
 use leptos::prelude::*;
 use leptos::prelude::{Get, Transition};
+use leptos_router::hooks::use_navigate;
 
-use crate::context::auth::AuthStore;
-use crate::request::post;
+use crate::context::auth::{AuthContext, AuthStore};
+use crate::context::friends::FriendEvents;
+use crate::request::{delete, post, put};
 use crate::{
     components::loading::Loading,
     context::toast::{use_toast, Toast, ToastType, ToasterTrait},
@@ -16,14 +24,264 @@ use crate::{
 
 #[component]
 pub fn Profile() -> impl IntoView {
+    view! {
+        <EditProfile />
+
+        <Takeout />
+
+        <PendingRequests />
+
+        <DeleteAccount />
+    }
+}
+
+// Avatar, display name, bio and preferred units, as the friends of the user sees them
+#[component]
+pub fn EditProfile() -> impl IntoView {
     let state = expect_context::<AuthStore>();
+    let auth = use_context::<AuthContext>().unwrap().0;
+    let toast = use_toast().unwrap();
+
+    let avatar = RwSignal::new(None::<String>);
+    let display_name = RwSignal::new(String::new());
+    let bio = RwSignal::new(String::new());
+    let units = RwSignal::new(Some(UnitSystem::default().to_string()));
+
+    let set_profile = move |profile: UserProfile| {
+        avatar.set(profile.avatar);
+        display_name.set(profile.display_name.unwrap_or_default());
+        bio.set(profile.bio.unwrap_or_default());
+        units.set(Some(profile.preferred_units.to_string()));
+    };
+
+    let error = move |body: &str| {
+        toast.add(Toast {
+            ty: ToastType::Error,
+            body: body.to_string(),
+            timeout: Some(Duration::from_secs(5)),
+        })
+    };
+
+    spawn_local(async move {
+        if let Ok(res) = get("/api/me/profile").send().await {
+            if let Ok(profile) = res.json::<UserProfile>().await {
+                set_profile(profile);
+            }
+        }
+    });
+
+    let (id, email) = (state.id, state.email.clone());
+    let on_save = move |_| {
+        let preferred_units = UnitSystem::iter()
+            .find(|it| Some(it.to_string()) == units.get_untracked())
+            .unwrap_or_default();
+        let body = serde_json::to_string(&UpdateProfile {
+            display_name: Some(display_name.get_untracked()),
+            bio: Some(bio.get_untracked()),
+            preferred_units,
+        })
+        .unwrap();
+        let email = email.clone();
+
+        spawn_local(async move {
+            let profile = match put("/api/me/profile").body(body).send().await {
+                Ok(res) if res.ok() => res.json::<UserProfile>().await.ok(),
+                _ => None,
+            };
+            let Some(profile) = profile else {
+                return error("Failed to save the profile");
+            };
+
+            // The display name is shown everywhere instead of the name
+            auth.set(Some(Some(User {
+                id,
+                name: profile.display_name.clone().unwrap_or(profile.name.clone()),
+                email,
+            })));
+            set_profile(profile);
+            toast.add(Toast {
+                ty: ToastType::Success,
+                body: "Saved the profile".to_string(),
+                timeout: Some(Duration::from_secs(5)),
+            });
+        });
+    };
+
+    let custom_request = move |files: FileList| {
+        let Some(file) = files.get(0) else {
+            return;
+        };
+
+        spawn_local(async move {
+            let image = match get("/api/uploads/avatars").send().await {
+                Ok(res) if res.ok() => res.json::<RecipeImage>().await.ok(),
+                _ => None,
+            };
+            let Some(image) = image else {
+                return error("Failed to upload the avatar");
+            };
+
+            let uploaded = reqwasm::http::Request::put(&image.url)
+                .body(file.value_of())
+                .send()
+                .await;
+            if !uploaded.is_ok_and(|res| res.ok()) {
+                return error("Failed to upload the avatar");
+            }
+
+            let body = serde_json::to_string(&UpdateAvatar { id: image.id }).unwrap();
+            match put("/api/me/avatar").body(body).send().await {
+                Ok(res) if res.ok() => match res.json::<UserProfile>().await {
+                    Ok(profile) => avatar.set(profile.avatar),
+                    Err(_) => error("Failed to upload the avatar"),
+                },
+                _ => error("Failed to upload the avatar"),
+            }
+        });
+    };
+
+    let on_remove_avatar = move |_| {
+        spawn_local(async move {
+            match delete("/api/me/avatar").send().await {
+                Ok(res) if res.ok() => match res.json::<UserProfile>().await {
+                    Ok(profile) => avatar.set(profile.avatar),
+                    Err(_) => error("Failed to remove the avatar"),
+                },
+                _ => error("Failed to remove the avatar"),
+            }
+        });
+    };
 
     view! {
-        <p>{state.id}</p>
-        <p>{state.name}</p>
-        <p>{state.email}</p>
+        <Flex vertical=true class="my-4 max-w-md">
+            <Flex align=FlexAlign::Center>
+                <Avatar src=avatar name=state.name.clone() size=72 />
+                <Flex vertical=true>
+                    <Body1>{state.name.clone()}</Body1>
+                    <Caption1>{state.email.clone()}</Caption1>
+                </Flex>
+            </Flex>
+            <Flex>
+                <Upload accept="image/*" custom_request>
+                    <Button icon=icondata::AiUploadOutlined>"Upload avatar"</Button>
+                </Upload>
+                <Button on_click=on_remove_avatar>"Remove avatar"</Button>
+            </Flex>
+            <Input value=display_name placeholder="Display name" />
+            <Textarea value=bio placeholder="Tell your friends about yourself" />
+            <RadioGroup value=units>
+                {UnitSystem::iter()
+                    .map(|it| view! { <Radio value=it.to_string() label=it.to_string() /> })
+                    .collect::<Vec<_>>()}
+            </RadioGroup>
+            <Button appearance=ButtonAppearance::Primary on_click=on_save>
+                "Save profile"
+            </Button>
+        </Flex>
+    }
+}
 
-        <PendingRequests />
+#[component]
+pub fn DeleteAccount() -> impl IntoView {
+    let toast = use_toast().unwrap();
+    let auth = use_context::<AuthContext>().unwrap().0;
+    let open = RwSignal::new(false);
+
+    let navigate = use_navigate();
+    let on_delete = move |_| {
+        let nav = navigate.clone();
+        spawn_local(async move {
+            match delete("/api/me").send().await {
+                Ok(r) if r.ok() => {
+                    nav("/", Default::default());
+                    auth.set(Some(None));
+                }
+                _ => {
+                    open.set(false);
+                    toast.add(Toast {
+                        ty: ToastType::Error,
+                        body: "Failed to delete account".to_string(),
+                        timeout: Some(Duration::from_secs(5)),
+                    });
+                }
+            };
+        });
+    };
+
+    view! {
+        <Button class="my-4" on:click=move |_| open.set(true)>
+            "Delete account"
+        </Button>
+        <Dialog open>
+            <DialogSurface>
+                <DialogBody>
+                    <DialogTitle>"Delete account"</DialogTitle>
+                    <DialogContent>
+                        "Are you sure you want to delete your account? All your recipes, images and friendships are deleted, and this can't be undone."
+                    </DialogContent>
+                    <DialogActions>
+                        <Button on:click=on_delete appearance=ButtonAppearance::Primary>
+                            "Yes"
+                        </Button>
+                        <Button on:click=move |_| open.set(false)>"No"</Button>
+                    </DialogActions>
+                </DialogBody>
+            </DialogSurface>
+        </Dialog>
+    }
+}
+
+#[component]
+pub fn Takeout() -> impl IntoView {
+    let toast = use_toast().unwrap();
+
+    let custom_request = move |files: FileList| {
+        let Some(file) = files.get(0) else {
+            return;
+        };
+
+        spawn_local(async move {
+            let res = reqwasm::http::Request::post("/api/takeout")
+                .credentials(web_sys::RequestCredentials::Include)
+                .header("content-type", "application/zip")
+                .body(file.value_of())
+                .send()
+                .await;
+
+            let imported = match res {
+                Ok(res) if res.ok() => res.json::<TakeoutImport>().await.ok(),
+                _ => None,
+            };
+
+            let Some(imported) = imported else {
+                toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: "Failed to import the archive".to_string(),
+                    timeout: Some(Duration::from_secs(5)),
+                });
+                return;
+            };
+
+            toast.add(Toast {
+                ty: ToastType::Success,
+                body: format!(
+                    "Imported {} recipes and {} images",
+                    imported.recipes, imported.images
+                ),
+                timeout: Some(Duration::from_secs(5)),
+            });
+        })
+    };
+
+    view! {
+        <Flex class="my-4">
+            <a href="/api/takeout" download>
+                <Button icon=icondata::AiDownloadOutlined>"Export my data"</Button>
+            </a>
+            <Upload accept=".zip" custom_request>
+                <Button icon=icondata::AiUploadOutlined>"Import data"</Button>
+            </Upload>
+        </Flex>
     }
 }
 
@@ -31,7 +289,10 @@ pub fn Profile() -> impl IntoView {
 pub fn PendingRequests() -> impl IntoView {
     let toast = use_toast().unwrap();
 
+    let friend_events = expect_context::<FriendEvents>().0;
+
     let users_resource = LocalResource::new(move || async move {
+        friend_events.track();
         let res = match get("/api/friends/pending").send().await {
             Ok(res) => res,
             Err(_) => {
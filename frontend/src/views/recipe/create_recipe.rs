@@ -16,7 +16,7 @@ use crate::{
     request::post,
 };
 
-use common::recipe::Recipe;
+use common::recipe::{DuplicateRecipe, DuplicateRecipes, Recipe};
 
 #[component]
 pub fn CreateRecipe() -> impl IntoView {
@@ -30,24 +30,22 @@ pub fn CreateRecipe() -> impl IntoView {
 
     let navigate = use_navigate();
 
-    let on_submit = move |_| {
-        let mut create_recipe = recipe.get();
+    // Set when the backend finds recipes that are probably the same, so the user can choose to
+    // create it anyway
+    let pending = RwSignal::new(None::<common::recipe::CreateRecipe>);
+    let duplicates = RwSignal::new(Vec::<DuplicateRecipe>::new());
+    let open = RwSignal::new(false);
+
+    let save = move |create_recipe: common::recipe::CreateRecipe, allow_duplicate: bool| {
         let nav = navigate.clone();
         spawn_local(async move {
-            match try_upload_image(file.0.get_untracked()).await {
-                Ok(Some(img)) => create_recipe.img = Some(img),
-                Err(_) => {
-                    toast.add(Toast {
-                        ty: ToastType::Error,
-                        body: "Failed to upload image".to_string(),
-                        timeout: Some(Duration::from_secs(5)),
-                    });
-                }
-                _ => (),
-            }
-
-            let body = serde_json::to_value(create_recipe).unwrap();
-            let res = post("/api/recipes").body(body.to_string()).send().await;
+            let url = if allow_duplicate {
+                "/api/recipes?allow_duplicate=true"
+            } else {
+                "/api/recipes"
+            };
+            let body = serde_json::to_value(&create_recipe).unwrap();
+            let res = post(url).body(body.to_string()).send().await;
 
             match res {
                 Ok(r) if r.ok() => {
@@ -66,6 +64,13 @@ pub fn CreateRecipe() -> impl IntoView {
                         nav("/", NavigateOptions::default());
                     };
                 }
+                Ok(r) if r.status() == 409 => {
+                    if let Ok(res) = r.json::<DuplicateRecipes>().await {
+                        pending.set(Some(create_recipe));
+                        duplicates.set(res.candidates);
+                        open.set(true);
+                    }
+                }
                 _ => {
                     toast.add(Toast {
                         ty: ToastType::Error,
@@ -77,6 +82,34 @@ pub fn CreateRecipe() -> impl IntoView {
         })
     };
 
+    let save_anyway = save.clone();
+    let on_create_anyway = move |_| {
+        open.set(false);
+        if let Some(create_recipe) = pending.get_untracked() {
+            save_anyway(create_recipe, true);
+        }
+    };
+
+    let on_submit = move |_| {
+        let mut create_recipe = recipe.get();
+        let save = save.clone();
+        spawn_local(async move {
+            match try_upload_image(file.0.get_untracked()).await {
+                Ok(Some(img)) => create_recipe.img = Some(img),
+                Err(_) => {
+                    toast.add(Toast {
+                        ty: ToastType::Error,
+                        body: "Failed to upload image".to_string(),
+                        timeout: Some(Duration::from_secs(5)),
+                    });
+                }
+                _ => (),
+            }
+
+            save(create_recipe, false);
+        })
+    };
+
     let (current_file, _) = signal::<Option<String>>(None);
 
     view! {
@@ -91,5 +124,42 @@ pub fn CreateRecipe() -> impl IntoView {
                 {"Save"}
             </Button>
         </Form>
+        <Dialog open>
+            <DialogSurface>
+                <DialogBody>
+                    <DialogTitle>"The recipe probably already exists"</DialogTitle>
+                    <DialogContent>
+                        <ul class="flex flex-col gap-2">
+                            <For
+                                each=move || duplicates.get()
+                                key=|duplicate| duplicate.id
+                                children=move |duplicate| {
+                                    view! {
+                                        <li>
+                                            <a
+                                                class="underline"
+                                                href=format!("/recipes/{}", duplicate.id)
+                                            >
+                                                {duplicate.name}
+                                            </a>
+                                            {format!(
+                                                " ({:.0}% of the ingredients in common)",
+                                                duplicate.ingredient_overlap * 100.0,
+                                            )}
+                                        </li>
+                                    }
+                                }
+                            />
+                        </ul>
+                    </DialogContent>
+                    <DialogActions>
+                        <Button on:click=on_create_anyway appearance=ButtonAppearance::Primary>
+                            "Create anyway"
+                        </Button>
+                        <Button on:click=move |_| open.set(false)>"Cancel"</Button>
+                    </DialogActions>
+                </DialogBody>
+            </DialogSurface>
+        </Dialog>
     }
 }
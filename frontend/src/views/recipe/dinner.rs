@@ -0,0 +1,78 @@
+use common::recipe::RecipeSuggestions;
+use leptos::prelude::*;
+use thaw::*;
+
+use crate::{
+    components::{
+        form::form_fields::form_field_number_input::FormFieldNumberInput, loading::Loading,
+    },
+    request::get,
+    views::recipe::recipes::RecipeCard,
+};
+
+// One tap to get something to make for dinner, or a shortlist for the household to pick from
+#[component]
+pub fn Dinner() -> impl IntoView {
+    let max_time = RwSignal::new(String::new());
+    let shortlist = RwSignal::new(false);
+    // Bumped to get a new pick with the same filters
+    let shuffle = RwSignal::new(0);
+
+    let suggestions = LocalResource::new(move || async move {
+        shuffle.track();
+        let mut params = vec![format!("count={}", if shortlist.get() { 3 } else { 1 })];
+        if let Ok(minutes) = max_time.get().parse::<u32>() {
+            params.push(format!("max_time={minutes}"));
+        }
+
+        let url = format!("/api/recipes/suggestions?{}", params.join("&"));
+        match get(&url).send().await {
+            Ok(res) => res
+                .json::<RecipeSuggestions>()
+                .await
+                .map(|it| it.recipes)
+                .unwrap_or_default(),
+            Err(_) => vec![],
+        }
+    });
+
+    view! {
+        <div class="mx-auto w-[80%] max-w-screen-xl flex flex-col gap-4">
+            <h1 class="text-2xl">"What's for dinner?"</h1>
+            <Flex align=FlexAlign::End>
+                <FormFieldNumberInput value=max_time placeholder="Max time (minutes)" />
+                <Checkbox checked=shortlist label="Give me a shortlist" />
+                <Button
+                    icon=icondata::BiShuffleRegular
+                    on:click=move |_| shuffle.update(|it| *it += 1)
+                >
+                    "Shuffle"
+                </Button>
+            </Flex>
+            <div class="grid grid-cols-12 gap-8">
+                <Transition fallback=Loading>
+                    {move || {
+                        let recipes = suggestions.get().as_deref().cloned().unwrap_or_default();
+                        if recipes.is_empty() {
+                            return view! {
+                                <p class="col-span-12">"No recipes match, try a longer time"</p>
+                            }
+                                .into_any();
+                        }
+                        recipes
+                            .into_iter()
+                            .map(|recipe| {
+                                view! {
+                                    <div class="col-span-12 sm:col-span-6 lg:col-span-4">
+                                        <RecipeCard recipe />
+                                    </div>
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                            .into_any()
+                    }}
+                </Transition>
+            </div>
+        </div>
+    }
+}
@@ -10,9 +10,12 @@ use crate::components::loading::Loading;
 use crate::components::not_found::NotFound;
 use crate::context::auth::AuthStore;
 use crate::context::toast::{use_toast, Toast, ToastType, ToasterTrait};
+use crate::views::collections::bookmarks::RecipeBookmarksControl;
+use crate::views::feed::cooked::CookedButton;
+use crate::views::meal_plan::plan::PlanMealButton;
 use crate::views::recipe::recipe_image::RecipeImage;
-use crate::views::recipe::{format_ingredients, format_time, total_time};
-use common::recipe::{Recipe, RecipeIngredient};
+use crate::views::recipe::{format_ingredients, recipe_times};
+use common::recipe::{format_yield, group_ingredients, Recipe, RecipeIngredient};
 use leptos_router::NavigateOptions;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
@@ -81,12 +84,33 @@ fn Recipe(recipe: Recipe) -> impl IntoView {
 fn RecipeCard(recipe: Recipe) -> impl IntoView {
     let state = expect_context::<AuthStore>();
 
-    let _recipe = recipe.clone();
     let toast = use_toast().unwrap();
-    let time = move || {
-        total_time(_recipe.prep_time, _recipe.baking_time)
-            .map(format_time)
-            .unwrap_or_default()
+    let time = recipe
+        .total_time
+        .map(|it| it.to_string())
+        .unwrap_or_default();
+    let times = recipe_times(&recipe)
+        .into_iter()
+        .map(|(label, time)| format!("{label} {time}"))
+        .collect::<Vec<_>>()
+        .join(" · ");
+    let source = match (recipe.source_name.clone(), recipe.source_url.clone()) {
+        (None, None) => None,
+        (name, Some(url)) => Some({
+            let text = name.unwrap_or(url.clone());
+            view! {
+                <Caption1>
+                    "Source: "
+                    <a href=url target="_blank" rel="noopener noreferrer" class="underline">
+                        {text}
+                    </a>
+                </Caption1>
+            }
+            .into_any()
+        }),
+        (Some(name), None) => {
+            Some(view! { <Caption1>{format!("Source: {name}")}</Caption1> }.into_any())
+        }
     };
     let open = RwSignal::new(false);
 
@@ -130,6 +154,9 @@ fn RecipeCard(recipe: Recipe) -> impl IntoView {
             <CardHeader>
                 <Body1>{recipe.name}</Body1>
                 <CardHeaderAction slot>
+                    <RecipeBookmarksControl recipe_id=recipe.id />
+                    <PlanMealButton recipe_id=recipe.id />
+                    <CookedButton recipe_id=recipe.id />
                     {if state.id == recipe.user_id {
                         view! {
                             <Menu position=MenuPosition::BottomEnd on_select=on_select>
@@ -171,11 +198,55 @@ fn RecipeCard(recipe: Recipe) -> impl IntoView {
                 <Flex align=FlexAlign::Center>
                     <Icon icon=icondata::AiClockCircleOutlined />
                     <p class="ml-1 flex-none">{time}</p>
+                    <Caption1 class="ml-2">{times}</Caption1>
                 </Flex>
                 <Flex align=FlexAlign::Center>
                     <Icon icon=icondata::AiShoppingCartOutlined />
                     <p class="ml-1">{format_ingredients(recipe.ingredients.len())}</p>
                 </Flex>
+                <Flex align=FlexAlign::Center class="flex-wrap">
+                    {recipe
+                        .cuisine
+                        .map(|cuisine| {
+                            view! {
+                                <Badge appearance=BadgeAppearance::Tint color=BadgeColor::Brand>
+                                    {cuisine}
+                                </Badge>
+                            }
+                        })}
+                    {recipe
+                        .difficulty
+                        .map(|difficulty| {
+                            view! {
+                                <Badge appearance=BadgeAppearance::Tint color=BadgeColor::Informative>
+                                    {difficulty.to_string()}
+                                </Badge>
+                            }
+                        })}
+                    {recipe
+                        .diets
+                        .into_iter()
+                        .map(|diet| {
+                            view! {
+                                <Badge appearance=BadgeAppearance::Tint color=BadgeColor::Success>
+                                    {diet.to_string()}
+                                </Badge>
+                            }
+                        })
+                        .collect::<Vec<_>>()}
+                    {recipe
+                        .allergens
+                        .into_iter()
+                        .map(|allergen| {
+                            view! {
+                                <Badge appearance=BadgeAppearance::Tint color=BadgeColor::Warning>
+                                    {allergen.to_string()}
+                                </Badge>
+                            }
+                        })
+                        .collect::<Vec<_>>()}
+                </Flex>
+                {source}
             </CardFooter>
         </Card>
     }
@@ -184,6 +255,7 @@ fn RecipeCard(recipe: Recipe) -> impl IntoView {
 #[component]
 fn RecipeIngredients(recipe: Recipe, ingredients: Vec<RecipeIngredient>) -> impl IntoView {
     let internal_ingredients = RwSignal::new(ingredients.clone());
+    let yield_unit = recipe.yield_unit.clone();
     let (servings, set_servings) = signal(Ratio::new(recipe.servings as i128, 1));
 
     let set_ingredients = move |old_serving: Ratio<i128>, new_serving: Ratio<i128>| {
@@ -202,12 +274,10 @@ fn RecipeIngredients(recipe: Recipe, ingredients: Vec<RecipeIngredient>) -> impl
         let new_ingredients = internal_ingredients()
             .iter()
             .map(|i| RecipeIngredient {
-                ingredient_id: i.ingredient_id,
-                ingredient_name: i.ingredient_name.clone(),
-                unit: i.unit,
                 amount: i
                     .amount
                     .map(|a| compute_amount(a, old_serving, new_serving)),
+                ..i.clone()
             })
             .collect();
 
@@ -218,42 +288,57 @@ fn RecipeIngredients(recipe: Recipe, ingredients: Vec<RecipeIngredient>) -> impl
     view! {
         <Card>
             <h1 class="flex text-2xl justify-center">"Ingredients"</h1>
-            <Flex justify=FlexJustify::Center>
+            <Flex justify=FlexJustify::Center align=FlexAlign::Center>
                 <Button
+                    class="print:hidden"
                     appearance=ButtonAppearance::Transparent
                     icon=icondata::AiMinusCircleOutlined
                     on:click=move |_| { set_ingredients(servings(), servings().sub(1)) }
                 />
-                <p>{move || format!("{} servings", servings())}</p>
+                <p>{move || format_yield(servings(), yield_unit.as_deref())}</p>
                 <Button
+                    class="print:hidden"
                     appearance=ButtonAppearance::Transparent
                     icon=icondata::AiPlusCircleOutlined
                     on:click=move |_| { set_ingredients(servings(), servings().add(1)) }
                 />
             </Flex>
+            <Flex justify=FlexJustify::Center class="print:hidden">
+                <Button
+                    appearance=ButtonAppearance::Subtle
+                    icon=icondata::AiPrinterOutlined
+                    on:click=move |_| {
+                        let _ = window().print();
+                    }
+                >
+                    "Print"
+                </Button>
+                // The PDF is only made for whole servings, so half a serving is rounded up
+                <a
+                    href=move || {
+                        format!(
+                            "/api/recipes/{}/pdf?servings={}",
+                            recipe.id,
+                            servings().ceil().to_integer().max(1),
+                        )
+                    }
+                    target="_blank"
+                >
+                    <Button appearance=ButtonAppearance::Subtle icon=icondata::AiFilePdfOutlined>
+                        "PDF"
+                    </Button>
+                </a>
+            </Flex>
             {move || {
-                internal_ingredients()
+                group_ingredients(&internal_ingredients())
                     .into_iter()
-                    .map(|ingredient| {
+                    .map(|(group, ingredients)| {
                         view! {
-                            <p class="mb-1 bg-neutral rounded-md">
-                                {format!(
-                                    "{} {} {}",
-                                    ingredient
-                                        .amount
-                                        .map(|a| {
-                                            if a.is_integer() {
-                                                a.to_i64().unwrap().to_string()
-                                            } else {
-                                                a.to_string()
-                                            }
-                                        })
-                                        .unwrap_or_default(),
-                                    ingredient.unit.map(|i| i.to_string()).unwrap_or_default(),
-                                    ingredient.ingredient_name,
-                                )}
-
-                            </p>
+                            {group.map(|group| view! { <h2 class="text-lg mt-2">{group}</h2> })}
+                            {ingredients
+                                .into_iter()
+                                .map(|ingredient| view! { <IngredientLine ingredient=ingredient /> })
+                                .collect::<Vec<_>>()}
                         }
                     })
                     .collect::<Vec<_>>()
@@ -263,6 +348,33 @@ fn RecipeIngredients(recipe: Recipe, ingredients: Vec<RecipeIngredient>) -> impl
     }
 }
 
+#[component]
+fn IngredientLine(ingredient: RecipeIngredient) -> impl IntoView {
+    let amount = ingredient
+        .amount
+        .map(|a| {
+            if a.is_integer() {
+                a.to_i64().unwrap().to_string()
+            } else {
+                a.to_string()
+            }
+        })
+        .unwrap_or_default();
+
+    view! {
+        <p class="mb-1 bg-neutral rounded-md">
+            {format!(
+                "{} {} {}",
+                amount,
+                ingredient.unit.map(|i| i.to_string()).unwrap_or_default(),
+                ingredient.ingredient_name,
+            )}
+            {ingredient.note.map(|note| view! { <span class="italic">{format!(", {note}")}</span> })}
+            {ingredient.optional.then(|| view! { <span class="opacity-60">" (optional)"</span> })}
+        </p>
+    }
+}
+
 #[component]
 fn RecipeSteps(steps: Vec<String>) -> impl IntoView {
     view! {
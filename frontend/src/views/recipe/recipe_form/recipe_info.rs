@@ -1,6 +1,6 @@
-use chrono::Timelike;
 use common::strum::IntoEnumIterator;
 use leptos::prelude::*;
+use leptos::reactive::wrappers::write::SignalSetter;
 use std::time::Duration;
 use thaw::*;
 use web_sys::{File, Url};
@@ -10,7 +10,7 @@ use crate::components::form::form_fields::form_field_input::FormFieldInput;
 use crate::components::form::form_fields::form_field_number_input::FormFieldNumberInput;
 use crate::components::form::form_fields::form_field_textarea::FormFieldTextarea;
 use crate::context::toast::{use_toast, Toast, ToastType, ToasterTrait};
-use common::recipe::{CreateRecipe, RecipeVisibility};
+use common::recipe::{CreateRecipe, RecipeDifficulty, RecipeVisibility};
 
 use crate::components::form::FormGroup;
 use crate::views::recipe::recipe_image::RecipeImage;
@@ -38,6 +38,32 @@ pub fn RecipeInfo(
         |r, s: String| r.description = Some(s),
     );
 
+    let yield_unit = create_slice(
+        recipe,
+        |r| r.yield_unit.clone().unwrap_or_default(),
+        |r, s: String| r.yield_unit = (!s.is_empty()).then_some(s),
+    );
+    let cuisine = create_slice(
+        recipe,
+        |r| r.cuisine.clone().unwrap_or_default(),
+        |r, s: String| r.cuisine = (!s.is_empty()).then_some(s),
+    );
+    let difficulty = create_slice(
+        recipe,
+        |r| r.difficulty.map(|d| d.to_string()).unwrap_or_default(),
+        |r, s: String| r.difficulty = s.parse::<RecipeDifficulty>().ok(),
+    );
+    let source_name = create_slice(
+        recipe,
+        |r| r.source_name.clone().unwrap_or_default(),
+        |r, s: String| r.source_name = (!s.is_empty()).then_some(s),
+    );
+    let source_url = create_slice(
+        recipe,
+        |r| r.source_url.clone().unwrap_or_default(),
+        |r, s: String| r.source_url = (!s.is_empty()).then_some(s),
+    );
+
     let visibility = create_slice(
         recipe,
         |r| r.visibility.to_string(),
@@ -60,7 +86,7 @@ pub fn RecipeInfo(
             />
 
             <FormFieldNumberInput
-                class="col-span-12"
+                class="col-span-12 md:col-span-6"
                 name="servings"
                 placeholder="Servings"
                 value=servings
@@ -86,6 +112,13 @@ pub fn RecipeInfo(
                 ]
             />
 
+            <FormFieldInput
+                class="col-span-12 md:col-span-6"
+                name="yield_unit"
+                placeholder="Makes (e.g. cookies or 20 cm cake tin). Servings if empty"
+                value=yield_unit
+            />
+
             <RecipeDuration recipe />
 
             <FormFieldTextarea
@@ -95,6 +128,58 @@ pub fn RecipeInfo(
                 placeholder="Description"
             />
 
+            <FormFieldInput
+                class="col-span-12 md:col-span-6"
+                name="cuisine"
+                placeholder="Cuisine"
+                value=cuisine
+            />
+
+            <FormFieldSelect
+                class="col-span-12 md:col-span-6"
+                value=difficulty
+                placeholder="Difficulty"
+            >
+                {move || {
+                    RecipeDifficulty::iter()
+                        .map(|d| {
+                            view! {
+                                <ComboboxOption text=d.to_string() value=d.to_string()>
+                                    {d.to_string()}
+                                </ComboboxOption>
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                }}
+            </FormFieldSelect>
+
+            <FormFieldInput
+                class="col-span-12 md:col-span-6"
+                name="source_name"
+                placeholder="Source (e.g. a cookbook or a person)"
+                value=source_name
+            />
+
+            <FormFieldInput
+                class="col-span-12 md:col-span-6"
+                name="source_url"
+                placeholder="Link to the source"
+                value=source_url
+                rules=vec![
+                    InputRule::validator(move |v: &String, _| {
+                        if v.is_empty() || v.starts_with("http://") || v.starts_with("https://") {
+                            Ok(())
+                        } else {
+                            Err(
+                                FieldValidationState::Error(
+                                    "Must start with http:// or https://".to_string(),
+                                ),
+                            )
+                        }
+                    }),
+                ]
+            />
+
             <FormFieldSelect class="col-span-12" value=visibility placeholder="Visibility">
                 {move || {
                     common::recipe::RecipeVisibility::iter()
@@ -114,45 +199,54 @@ pub fn RecipeInfo(
 
 #[component]
 fn RecipeDuration(recipe: RwSignal<CreateRecipe>) -> impl IntoView {
-    let baking_time_minutes = RwSignal::new(
-        recipe
-            .get_untracked()
-            .baking_time
-            .map(|it| (it.hour() * 60 + it.minute()).to_string())
-            .unwrap_or_default(),
-    );
+    let prep_time = create_slice(recipe, |r| r.prep_time, |r, t| r.prep_time = t);
+    let cooking_time = create_slice(recipe, |r| r.cooking_time, |r, t| r.cooking_time = t);
+    let baking_time = create_slice(recipe, |r| r.baking_time, |r, t| r.baking_time = t);
+    let resting_time = create_slice(recipe, |r| r.resting_time, |r, t| r.resting_time = t);
 
-    Effect::new(move || {
-        let Ok(total_minutes) = baking_time_minutes().parse::<u32>() else {
-            return;
-        };
-        let hours = total_minutes / 60;
-        let minutes = total_minutes % 60;
-        if let Some(baking_time) = chrono::NaiveTime::from_hms_opt(hours, minutes, 0) {
-            recipe.update(|r| {
-                r.baking_time = Some(baking_time);
-            });
-        }
-    });
+    view! {
+        <DurationInput name="prep_time" placeholder="Prep time (minutes)" time=prep_time />
+        <DurationInput
+            name="cooking_time"
+            placeholder="Active cooking time (minutes)"
+            time=cooking_time
+        />
+        <DurationInput name="baking_time" placeholder="Baking time (minutes)" time=baking_time />
+        <DurationInput
+            name="resting_time"
+            placeholder="Resting or marinating time (minutes)"
+            time=resting_time
+        />
+    }
+}
+
+// A week should be enough for anything that isn't fermented for months
+const MAX_MINUTES: u32 = 7 * 24 * 60;
 
-    let prep_time_minutes = RwSignal::new(
-        recipe
+#[component]
+fn DurationInput(
+    name: &'static str,
+    placeholder: &'static str,
+    time: (
+        Signal<Option<common::recipe::Duration>>,
+        SignalSetter<Option<common::recipe::Duration>>,
+    ),
+) -> impl IntoView {
+    let minutes = RwSignal::new(
+        time.0
             .get_untracked()
-            .prep_time
-            .map(|it| (it.hour() * 60 + it.minute()).to_string())
+            .map(|it| it.as_minutes().to_string())
             .unwrap_or_default(),
     );
 
     Effect::new(move || {
-        let Ok(total_minutes) = prep_time_minutes().parse::<u32>() else {
+        let value = minutes();
+        if value.is_empty() {
+            time.1.set(None);
             return;
-        };
-        let hours = total_minutes / 60;
-        let minutes = total_minutes % 60;
-        if let Some(prep_time) = chrono::NaiveTime::from_hms_opt(hours, minutes, 0) {
-            recipe.update(|r| {
-                r.prep_time = Some(prep_time);
-            });
+        }
+        if let Ok(m) = value.parse::<u32>() {
+            time.1.set(Some(common::recipe::Duration::from_minutes(m)));
         }
     });
 
@@ -161,36 +255,22 @@ fn RecipeDuration(recipe: RwSignal<CreateRecipe>) -> impl IntoView {
             return Ok(());
         }
 
-        let Ok(val) = v.parse::<u32>() else {
-            return Err(FieldValidationState::Error(
-                "Must be a number between 0 and 500".to_string(),
-            ));
-        };
-        match (0..500).contains(&val) {
-            true => Ok(()),
-            false => Err(FieldValidationState::Error(
-                "Must be a number between 0 and 500".to_string(),
-            )),
+        match v.parse::<u32>() {
+            Ok(val) if val <= MAX_MINUTES => Ok(()),
+            _ => Err(FieldValidationState::Error(format!(
+                "Must be a number of minutes between 0 and {MAX_MINUTES}"
+            ))),
         }
     };
 
     view! {
         <FormFieldNumberInput
-            class="col-span-12"
-            name="baking_time"
-            placeholder="Baking time"
-            value=baking_time_minutes
-            rules=vec![InputRule::validator(move |v: &String, _| valid_time(v))]
-        />
-
-        <FormFieldNumberInput
-            class="col-span-12"
-            name="prep_time"
-            placeholder="Prep time"
-            value=prep_time_minutes
+            class="col-span-12 md:col-span-6"
+            name=name
+            placeholder=placeholder
+            value=minutes
             rules=vec![InputRule::validator(move |v: &String, _| valid_time(v))]
         />
-
     }
 }
 
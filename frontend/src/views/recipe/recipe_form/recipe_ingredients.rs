@@ -1,7 +1,12 @@
+use crate::components::form::form_fields::form_field_autocomplete::FormFieldAutoComplete;
 use crate::components::form::form_fields::form_field_combobox::FormFieldSelect;
 use crate::components::form::form_fields::form_field_input::FormFieldInput;
 use crate::components::form::form_fields::form_field_number_input::FormFieldNumberInput;
+use crate::components::form::form_fields::form_field_textarea::FormFieldTextarea;
 use crate::components::form::FormGroup;
+use crate::request::get;
+use common::ingredient::Ingredient;
+use common::ingredient_parser::parse_ingredient_lines;
 use common::recipe::{CreateRecipe, CreateRecipeIngredient, Unit};
 use common::strum::IntoEnumIterator;
 use leptos::prelude::*;
@@ -15,8 +20,38 @@ pub fn RecipeIngredients() -> impl IntoView {
     let recipe_ingredient = RwSignal::new(CreateRecipeIngredient::default());
 
     let name = slice!(recipe_ingredient.name);
+    let optional = slice!(recipe_ingredient.optional);
+    let group = create_slice(
+        recipe_ingredient,
+        |ri| ri.group.clone().unwrap_or_default(),
+        |ri, g: String| ri.group = (!g.is_empty()).then_some(g),
+    );
+    let note = create_slice(
+        recipe_ingredient,
+        |ri| ri.note.clone().unwrap_or_default(),
+        |ri, n: String| ri.note = (!n.is_empty()).then_some(n),
+    );
     let amount = RwSignal::new(String::new());
 
+    let paste_mode = RwSignal::new(false);
+    let pasted = RwSignal::new(String::new());
+
+    let suggestions = LocalResource::new(move || async move {
+        let q = name.0.get();
+        if q.trim().is_empty() {
+            return vec![];
+        }
+
+        let url = format!(
+            "/api/ingredients/search?q={}",
+            web_sys::js_sys::encode_uri_component(&q)
+        );
+        match get(&url).send().await {
+            Ok(res) => res.json::<Vec<Ingredient>>().await.unwrap_or_default(),
+            Err(_) => vec![],
+        }
+    });
+
     Effect::new(move || {
         let Ok(a) = amount().parse::<Decimal>() else {
             return;
@@ -34,7 +69,42 @@ pub fn RecipeIngredients() -> impl IntoView {
 
     view! {
         <FormGroup>
-            <FormFieldInput class="col-span-12 md:col-span-6" value=name placeholder="Name" />
+            <Switch class="col-span-12" checked=paste_mode label="Paste a list of ingredients" />
+            <Show when=move || paste_mode.get()>
+                <FormFieldTextarea
+                    class="col-span-12"
+                    value=pasted
+                    placeholder="One ingredient per line, e.g. \"2 dl milk\". Start a group with a line like \"For the sauce:\""
+                />
+                <Button
+                    class="col-span-12"
+                    button_type=ButtonType::Button
+                    on:click=move |_| {
+                        let ingredients = parse_ingredient_lines(&pasted.get_untracked());
+                        recipe.update(|r| r.ingredients.extend(ingredients));
+                        pasted.set(String::new());
+                    }
+                >
+                    "Add pasted ingredients"
+                </Button>
+            </Show>
+
+            <FormFieldAutoComplete class="col-span-12 md:col-span-6" value=name placeholder="Name">
+                {move || {
+                    suggestions
+                        .get()
+                        .as_deref()
+                        .cloned()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|i| {
+                            view! {
+                                <AutoCompleteOption value=i.name.clone()>{i.name}</AutoCompleteOption>
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                }}
+            </FormFieldAutoComplete>
 
             <FormFieldNumberInput
                 name="amount"
@@ -57,14 +127,32 @@ pub fn RecipeIngredients() -> impl IntoView {
                 }}
             </FormFieldSelect>
 
+            <FormFieldInput
+                class="col-span-12 md:col-span-5"
+                value=group
+                placeholder="Group (e.g. For the sauce)"
+            />
+            <FormFieldInput
+                class="col-span-12 md:col-span-5"
+                value=note
+                placeholder="Note (e.g. finely chopped)"
+            />
+            <Checkbox class="col-span-12 md:col-span-2 self-end" checked=optional label="Optional" />
+
             <Button
                 class="col-span-12"
                 button_type=ButtonType::Button
                 on:click=move |_| {
                     recipe
                         .update(|r| {
-                            r.ingredients.push(recipe_ingredient.get_untracked());
-                            recipe_ingredient.set(CreateRecipeIngredient::default());
+                            let ingredient = recipe_ingredient.get_untracked();
+                            // Keep the group, since ingredients are usually added group by group
+                            recipe_ingredient
+                                .set(CreateRecipeIngredient {
+                                    group: ingredient.group.clone(),
+                                    ..Default::default()
+                                });
+                            r.ingredients.push(ingredient);
                             amount.set(String::new());
                         })
                 }
@@ -120,13 +208,16 @@ fn Ingredients(
             <CardHeader>
                 <h2 class="card-title">
                     {format!(
-                        "{} {} {}",
+                        "{} {} {}{}{}",
                         ingredient.amount.map(|a| a.to_string()).unwrap_or_default(),
                         ingredient.unit.map(|i| i.to_string()).unwrap_or_default(),
                         ingredient.name,
+                        ingredient.note.map(|n| format!(", {n}")).unwrap_or_default(),
+                        if ingredient.optional { " (optional)" } else { "" },
                     )}
 
                 </h2>
+                <CardHeaderDescription slot>{ingredient.group}</CardHeaderDescription>
                 <CardHeaderAction slot>
                     <Show when=move || { index > 0 }>
                         <Button
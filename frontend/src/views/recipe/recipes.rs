@@ -1,13 +1,15 @@
+use crate::components::form::form_fields::form_field_combobox::FormFieldSelect;
 use crate::components::not_found::NotFound;
+use crate::views::recipe::format_ingredients;
 use crate::views::recipe::recipe_image::RecipeImage;
-use crate::views::recipe::{format_ingredients, format_time, total_time};
 use codee::string::FromToStringCodec;
 use common::websocket::FoodieMessageType;
 use leptos_use::{use_websocket, UseWebSocketReturn};
 use std::time::Duration;
 use thaw::*;
 
-use common::recipe::Recipe;
+use common::recipe::{Recipe, RecipeDifficulty};
+use common::strum::IntoEnumIterator;
 use leptos::prelude::*;
 use leptos::prelude::{Get, Transition};
 use leptos_router::{hooks::use_navigate, NavigateOptions};
@@ -21,8 +23,25 @@ use crate::{
 #[component]
 pub fn Recipes() -> impl IntoView {
     let toast = use_toast().unwrap();
+    let cuisine = RwSignal::new(String::new());
+    let difficulty = RwSignal::new(String::new());
+
+    let cuisines = LocalResource::new(move || async move {
+        match get("/api/recipes/cuisines").send().await {
+            Ok(res) => res.json::<Vec<String>>().await.unwrap_or_default(),
+            Err(_) => vec![],
+        }
+    });
+
     let recipes = LocalResource::new(move || async move {
-        match get("/api/recipes").send().await {
+        let params = [("cuisine", cuisine.get()), ("difficulty", difficulty.get())]
+            .into_iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(key, value)| format!("{key}={}", web_sys::js_sys::encode_uri_component(&value)))
+            .collect::<Vec<_>>()
+            .join("&");
+        let url = format!("/api/recipes?{params}");
+        match get(&url).send().await {
             Ok(res) => res.json::<Vec<Recipe>>().await.ok(),
             Err(_) => {
                 toast.add(Toast {
@@ -41,8 +60,11 @@ pub fn Recipes() -> impl IntoView {
     Effect::new(move || match message() {
         Some(m) => match m {
             FoodieMessageType::RecipeDelete => recipes.refetch(),
-            FoodieMessageType::RecipeCreate => recipes.refetch(),
-            FoodieMessageType::FriendRequest => (),
+            FoodieMessageType::RecipeCreate => {
+                recipes.refetch();
+                cuisines.refetch();
+            }
+            _ => (),
         },
         None => {}
     });
@@ -51,6 +73,39 @@ pub fn Recipes() -> impl IntoView {
 
     view! {
         <div class="p-4 w-full justify-center flex flex-col items-center">
+            <div class="grid grid-cols-12 gap-4 w-full max-w-screen-xl mb-4">
+                <FormFieldSelect class="col-span-6" value=cuisine placeholder="Cuisine">
+                    {move || {
+                        cuisines
+                            .get()
+                            .as_deref()
+                            .cloned()
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|c| {
+                                view! {
+                                    <ComboboxOption text=c.clone() value=c.clone()>
+                                        {c}
+                                    </ComboboxOption>
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    }}
+                </FormFieldSelect>
+                <FormFieldSelect class="col-span-6" value=difficulty placeholder="Difficulty">
+                    {move || {
+                        RecipeDifficulty::iter()
+                            .map(|d| {
+                                view! {
+                                    <ComboboxOption text=d.to_string() value=d.to_string()>
+                                        {d.to_string()}
+                                    </ComboboxOption>
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    }}
+                </FormFieldSelect>
+            </div>
             <div class="grid grid-cols-12 gap-8">
                 <Transition fallback=Loading>
                     {move || {
@@ -98,15 +153,11 @@ pub fn Recipes() -> impl IntoView {
 }
 
 #[component]
-fn RecipeCard(recipe: Recipe) -> impl IntoView {
-    // TODO: Do I want to include both prep time and baking time when displaying how long time it
-    // takes to make the recipe
-    let _recipe = recipe.clone();
-    let time = move || {
-        total_time(_recipe.prep_time, _recipe.baking_time)
-            .map(format_time)
-            .unwrap_or_default()
-    };
+pub fn RecipeCard(recipe: Recipe) -> impl IntoView {
+    let time = recipe
+        .total_time
+        .map(|it| it.to_string())
+        .unwrap_or_default();
 
     // TODO(seb): Should use <a> instead of use_navigate
     let navigate = use_navigate();
@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use codee::string::FromToStringCodec;
+use common::{
+    recipe::Recipe,
+    swipe::{Swipe, SwipeSession},
+    websocket::FoodieMessageType,
+};
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_params_map;
+use leptos_use::{use_websocket, UseWebSocketReturn};
+use thaw::*;
+
+use crate::{
+    components::{loading::Loading, not_found::NotFound},
+    context::toast::{use_toast, Toast, ToastType, ToasterTrait},
+    request::{get, put},
+    views::recipe::recipes::RecipeCard,
+};
+
+// How far a card has to be dragged before it counts as a swipe
+const SWIPE_THRESHOLD: f64 = 120.0;
+
+#[component]
+pub fn SwipeDeck() -> impl IntoView {
+    let toast = use_toast().unwrap();
+    let params = use_params_map();
+    let id = move || params.with(|params| params.get("id").unwrap_or_default());
+
+    let session = LocalResource::new(move || async move {
+        let res = get(&format!("/api/swipe/{}", id())).send().await.ok()?;
+        if !res.ok() {
+            return None;
+        }
+        res.json::<SwipeSession>().await.ok()
+    });
+
+    let deck = RwSignal::new(Vec::<Recipe>::new());
+    let cards = LocalResource::new(move || async move {
+        let recipes = match get(&format!("/api/swipe/{}/deck", id())).send().await {
+            Ok(res) => res.json::<Vec<Recipe>>().await.unwrap_or_default(),
+            Err(_) => vec![],
+        };
+        deck.set(recipes);
+    });
+
+    let UseWebSocketReturn { message, .. } =
+        use_websocket::<String, FoodieMessageType, FromToStringCodec>("/api/ws");
+
+    Effect::new(move || {
+        if let Some(FoodieMessageType::SwipeMatch(swipe_match)) = message() {
+            if swipe_match.session_id.to_string() == id() {
+                toast.add(Toast {
+                    ty: ToastType::Success,
+                    body: format!("It's a match: {}", swipe_match.name),
+                    timeout: Some(Duration::from_secs(5)),
+                });
+                session.refetch();
+            }
+        }
+    });
+
+    let swipe = move |liked: bool| {
+        let Some(recipe) = deck.with_untracked(|it| it.first().cloned()) else {
+            return;
+        };
+        deck.update(|it| {
+            it.remove(0);
+        });
+        if deck.with_untracked(|it| it.is_empty()) {
+            cards.refetch();
+        }
+
+        let body = serde_json::to_string(&Swipe { liked }).unwrap();
+        spawn_local(async move {
+            let url = format!("/api/swipe/{}/recipes/{}", id(), recipe.id);
+            match put(&url).body(body).send().await {
+                Ok(r) if r.ok() => {}
+                _ => toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: "Failed to save the swipe".to_string(),
+                    timeout: Some(Duration::from_secs(5)),
+                }),
+            }
+        });
+    };
+
+    // Where the pointer was pressed down, and how far the card has been dragged since
+    let drag_start = RwSignal::new(None::<f64>);
+    let offset = RwSignal::new(0.0);
+    let on_release = move || {
+        if drag_start.get_untracked().is_none() {
+            return;
+        }
+        let dragged = offset.get_untracked();
+        drag_start.set(None);
+        offset.set(0.0);
+        if dragged > SWIPE_THRESHOLD {
+            swipe(true);
+        } else if dragged < -SWIPE_THRESHOLD {
+            swipe(false);
+        }
+    };
+
+    view! {
+        <Transition fallback=Loading>
+            {move || {
+                session
+                    .get()
+                    .as_deref()
+                    .cloned()
+                    .map(|data| match data {
+                        None => NotFound.into_any(),
+                        Some(s) => {
+                            view! {
+                                <div class="mx-auto w-[90%] max-w-md flex flex-col gap-4">
+                                    <h1 class="text-2xl">{s.name}</h1>
+                                    {move || match deck.with(|it| it.first().cloned()) {
+                                        None => {
+                                            view! {
+                                                <p>"You have swiped on every recipe. Check back when someone adds more"</p>
+                                            }
+                                                .into_any()
+                                        }
+                                        Some(recipe) => {
+                                            view! {
+                                                <div
+                                                    class="relative select-none touch-pan-y"
+                                                    style=move || {
+                                                        format!(
+                                                            "transform: translateX({0}px) rotate({1}deg)",
+                                                            offset.get(),
+                                                            offset.get() / 20.0,
+                                                        )
+                                                    }
+                                                    on:pointerdown=move |ev| {
+                                                        drag_start.set(Some(ev.client_x() as f64))
+                                                    }
+                                                    on:pointermove=move |ev| {
+                                                        if let Some(start) = drag_start.get_untracked() {
+                                                            offset.set(ev.client_x() as f64 - start);
+                                                        }
+                                                    }
+                                                    on:pointerup=move |_| on_release()
+                                                    on:pointerleave=move |_| on_release()
+                                                >
+                                                    <RecipeCard recipe />
+                                                    // Covers the card so dragging it doesn't open the recipe
+                                                    <div class="absolute inset-0" />
+                                                </div>
+                                            }
+                                                .into_any()
+                                        }
+                                    }}
+                                    <Flex justify=FlexJustify::SpaceAround>
+                                        <Button
+                                            icon=icondata::AiCloseOutlined
+                                            disabled=Signal::derive(move || deck.with(|it| it.is_empty()))
+                                            on_click=move |_| swipe(false)
+                                        >
+                                            "No"
+                                        </Button>
+                                        <Button
+                                            appearance=ButtonAppearance::Primary
+                                            icon=icondata::AiHeartOutlined
+                                            disabled=Signal::derive(move || deck.with(|it| it.is_empty()))
+                                            on_click=move |_| swipe(true)
+                                        >
+                                            "Yes"
+                                        </Button>
+                                    </Flex>
+                                    <h2 class="text-xl">"Matches"</h2>
+                                    {s
+                                        .matches
+                                        .into_iter()
+                                        .map(|m| {
+                                            view! {
+                                                <a href=format!("/recipes/{}", m.recipe_id)>
+                                                    <Body1>{m.name}</Body1>
+                                                </a>
+                                            }
+                                        })
+                                        .collect::<Vec<_>>()}
+                                </div>
+                            }
+                                .into_any()
+                        }
+                    })
+            }}
+        </Transition>
+    }
+}
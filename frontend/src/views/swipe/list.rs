@@ -0,0 +1,163 @@
+use std::{collections::HashSet, time::Duration};
+
+use common::{
+    friendship::FriendshipStatus,
+    swipe::{CreateSwipeSession, SwipeSession},
+    user::UserWithRelation,
+};
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_navigate;
+use thaw::*;
+
+use crate::{
+    components::{
+        form::{form_fields::form_field_input::FormFieldInput, FormGroup},
+        loading::Loading,
+    },
+    context::toast::{use_toast, Toast, ToastType, ToasterTrait},
+    request::{get, post},
+};
+
+#[component]
+pub fn SwipeSessions() -> impl IntoView {
+    let toast = use_toast().unwrap();
+    let navigate = use_navigate();
+
+    let sessions = LocalResource::new(move || async move {
+        match get("/api/swipe").send().await {
+            Ok(res) => res.json::<Vec<SwipeSession>>().await.unwrap_or_default(),
+            Err(_) => vec![],
+        }
+    });
+
+    let friends = LocalResource::new(move || async move {
+        match get("/api/users").send().await {
+            Ok(res) => res
+                .json::<Vec<UserWithRelation>>()
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|it| it.status == Some(FriendshipStatus::Accepted))
+                .collect(),
+            Err(_) => vec![],
+        }
+    });
+
+    let name = RwSignal::new(String::new());
+    let participant_ids = RwSignal::new(HashSet::<String>::new());
+
+    let on_create = move |_| {
+        let body = serde_json::to_string(&CreateSwipeSession {
+            name: name.get_untracked(),
+            participant_ids: participant_ids
+                .get_untracked()
+                .iter()
+                .filter_map(|it| it.parse::<i32>().ok())
+                .collect(),
+        })
+        .unwrap();
+
+        let nav = navigate.clone();
+        spawn_local(async move {
+            let session = match post("/api/swipe").body(body).send().await {
+                Ok(r) if r.ok() => r.json::<SwipeSession>().await.ok(),
+                _ => None,
+            };
+            match session {
+                Some(session) => nav(&format!("/swipe/{}", session.id), Default::default()),
+                None => toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: "Failed to start swiping".to_string(),
+                    timeout: Some(Duration::from_secs(5)),
+                }),
+            }
+        });
+    };
+
+    view! {
+        <div class="mx-auto w-[80%] max-w-screen-xl flex flex-col gap-8">
+            <Card>
+                <h1 class="text-2xl">"Swipe on dinner"</h1>
+                <FormGroup>
+                    <FormFieldInput
+                        class="col-span-12"
+                        placeholder="Name, e.g. Weekday dinner"
+                        value=name
+                    />
+                    <div class="col-span-12">
+                        <Body1>"Swipe with"</Body1>
+                        <CheckboxGroup value=participant_ids>
+                            {move || {
+                                friends
+                                    .get()
+                                    .as_deref()
+                                    .cloned()
+                                    .unwrap_or_default()
+                                    .into_iter()
+                                    .map(|u| {
+                                        view! { <Checkbox value=u.id.to_string() label=u.name /> }
+                                    })
+                                    .collect::<Vec<_>>()
+                            }}
+                        </CheckboxGroup>
+                    </div>
+                    <Button
+                        class="col-span-12"
+                        appearance=ButtonAppearance::Primary
+                        disabled=Signal::derive(move || participant_ids.with(|it| it.is_empty()))
+                        on_click=on_create
+                    >
+                        "Start swiping"
+                    </Button>
+                </FormGroup>
+            </Card>
+
+            <div class="flex flex-col gap-4">
+                <Transition fallback=Loading>
+                    {move || {
+                        sessions
+                            .get()
+                            .as_deref()
+                            .cloned()
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|s| view! { <SwipeSessionCard session=s /> })
+                            .collect::<Vec<_>>()
+                    }}
+                </Transition>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn SwipeSessionCard(session: SwipeSession) -> impl IntoView {
+    let navigate = use_navigate();
+    let href = format!("/swipe/{}", session.id);
+
+    let subtitle = format!(
+        "{} · {}",
+        session
+            .participants
+            .iter()
+            .map(|it| it.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        match session.matches.len() {
+            0 => "No matches yet".to_string(),
+            1 => "1 match".to_string(),
+            n => format!("{n} matches"),
+        }
+    );
+
+    view! {
+        <Card class="cursor-pointer" on:click=move |_| navigate(&href, Default::default())>
+            <CardHeader>
+                <Body1>{session.name}</Body1>
+                <CardHeaderDescription slot>
+                    <Caption1>{subtitle}</Caption1>
+                </CardHeaderDescription>
+            </CardHeader>
+        </Card>
+    }
+}
@@ -0,0 +1,214 @@
+use std::{collections::HashSet, time::Duration};
+
+use codee::string::FromToStringCodec;
+use common::{
+    friendship::FriendshipStatus,
+    recipe::Recipe,
+    user::UserWithRelation,
+    voting::{CreateVotingSession, VotingSession},
+    websocket::FoodieMessageType,
+};
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_navigate;
+use leptos_use::{use_websocket, UseWebSocketReturn};
+use thaw::*;
+
+use crate::{
+    components::{
+        form::{form_fields::form_field_input::FormFieldInput, FormGroup},
+        loading::Loading,
+    },
+    context::toast::{use_toast, Toast, ToastType, ToasterTrait},
+    request::{get, post},
+};
+
+#[component]
+pub fn VotingSessions() -> impl IntoView {
+    let toast = use_toast().unwrap();
+    let navigate = use_navigate();
+
+    let sessions = LocalResource::new(move || async move {
+        match get("/api/voting").send().await {
+            Ok(res) => res.json::<Vec<VotingSession>>().await.unwrap_or_default(),
+            Err(_) => vec![],
+        }
+    });
+
+    let UseWebSocketReturn { message, .. } =
+        use_websocket::<String, FoodieMessageType, FromToStringCodec>("/api/ws");
+
+    Effect::new(move || {
+        if let Some(
+            FoodieMessageType::VotingSessionInvite(_) | FoodieMessageType::VotingSessionClosed(_),
+        ) = message()
+        {
+            sessions.refetch();
+        }
+    });
+
+    let recipes = LocalResource::new(move || async move {
+        match get("/api/recipes").send().await {
+            Ok(res) => res.json::<Vec<Recipe>>().await.unwrap_or_default(),
+            Err(_) => vec![],
+        }
+    });
+
+    let friends = LocalResource::new(move || async move {
+        match get("/api/users").send().await {
+            Ok(res) => res
+                .json::<Vec<UserWithRelation>>()
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|it| it.status == Some(FriendshipStatus::Accepted))
+                .collect(),
+            Err(_) => vec![],
+        }
+    });
+
+    let name = RwSignal::new(String::new());
+    let recipe_ids = RwSignal::new(HashSet::<String>::new());
+    let participant_ids = RwSignal::new(HashSet::<String>::new());
+
+    let on_create = move |_| {
+        let ids = |set: RwSignal<HashSet<String>>| {
+            set.get_untracked()
+                .iter()
+                .filter_map(|it| it.parse::<i32>().ok())
+                .collect::<Vec<_>>()
+        };
+        let body = serde_json::to_string(&CreateVotingSession {
+            name: name.get_untracked(),
+            recipe_ids: ids(recipe_ids),
+            participant_ids: ids(participant_ids),
+        })
+        .unwrap();
+
+        let nav = navigate.clone();
+        spawn_local(async move {
+            let session = match post("/api/voting").body(body).send().await {
+                Ok(r) if r.ok() => r.json::<VotingSession>().await.ok(),
+                _ => None,
+            };
+            match session {
+                Some(session) => nav(&format!("/voting/{}", session.id), Default::default()),
+                None => toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: "Failed to start the vote".to_string(),
+                    timeout: Some(Duration::from_secs(5)),
+                }),
+            }
+        });
+    };
+
+    view! {
+        <div class="mx-auto w-[80%] max-w-screen-xl flex flex-col gap-8">
+            <Card>
+                <h1 class="text-2xl">"Vote on dinner"</h1>
+                <FormGroup>
+                    <FormFieldInput
+                        class="col-span-12"
+                        placeholder="Name, e.g. Friday dinner"
+                        value=name
+                    />
+                    <div class="col-span-12 md:col-span-8">
+                        <Body1>"Recipes to choose between"</Body1>
+                        <CheckboxGroup value=recipe_ids>
+                            {move || {
+                                recipes
+                                    .get()
+                                    .as_deref()
+                                    .cloned()
+                                    .unwrap_or_default()
+                                    .into_iter()
+                                    .map(|r| {
+                                        view! { <Checkbox value=r.id.to_string() label=r.name /> }
+                                    })
+                                    .collect::<Vec<_>>()
+                            }}
+                        </CheckboxGroup>
+                    </div>
+                    <div class="col-span-12 md:col-span-4">
+                        <Body1>"Friends to invite"</Body1>
+                        <CheckboxGroup value=participant_ids>
+                            {move || {
+                                friends
+                                    .get()
+                                    .as_deref()
+                                    .cloned()
+                                    .unwrap_or_default()
+                                    .into_iter()
+                                    .map(|u| {
+                                        view! { <Checkbox value=u.id.to_string() label=u.name /> }
+                                    })
+                                    .collect::<Vec<_>>()
+                            }}
+                        </CheckboxGroup>
+                    </div>
+                    <Button
+                        class="col-span-12"
+                        appearance=ButtonAppearance::Primary
+                        disabled=Signal::derive(move || recipe_ids.with(|it| it.len() < 2))
+                        on_click=on_create
+                    >
+                        "Start the vote"
+                    </Button>
+                </FormGroup>
+            </Card>
+
+            <div class="flex flex-col gap-4">
+                <Transition fallback=Loading>
+                    {move || {
+                        sessions
+                            .get()
+                            .as_deref()
+                            .cloned()
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|s| view! { <VotingSessionCard session=s /> })
+                            .collect::<Vec<_>>()
+                    }}
+                </Transition>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn VotingSessionCard(session: VotingSession) -> impl IntoView {
+    let navigate = use_navigate();
+    let href = format!("/voting/{}", session.id);
+
+    let subtitle = match session.closed_at {
+        None => format!(
+            "Open · {} of {} have voted",
+            session
+                .participants
+                .iter()
+                .filter(|it| it.has_voted)
+                .count(),
+            session.participants.len()
+        ),
+        Some(_) => {
+            let winner = session
+                .candidates
+                .iter()
+                .find(|it| Some(it.recipe_id) == session.winner_recipe_id);
+            match winner {
+                Some(winner) => format!("Closed · {} won", winner.name),
+                None => "Closed · Nobody voted".to_string(),
+            }
+        }
+    };
+
+    view! {
+        <Card class="cursor-pointer" on:click=move |_| navigate(&href, Default::default())>
+            <CardHeader>
+                <Body1>{session.name}</Body1>
+                <CardHeaderDescription slot>
+                    <Caption1>{subtitle}</Caption1>
+                </CardHeaderDescription>
+            </CardHeader>
+        </Card>
+    }
+}
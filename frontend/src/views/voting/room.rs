@@ -0,0 +1,212 @@
+use std::time::Duration;
+
+use codee::string::FromToStringCodec;
+use common::{
+    voting::{VotingCandidate, VotingSession},
+    websocket::FoodieMessageType,
+};
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_params_map;
+use leptos_use::{use_websocket, UseWebSocketReturn};
+use thaw::*;
+
+use crate::{
+    components::{loading::Loading, not_found::NotFound},
+    context::{
+        auth::AuthStore,
+        toast::{use_toast, Toast, ToastType, ToasterTrait},
+    },
+    request::{get, post},
+};
+
+// Everyone taking part sees the votes come in live, and gets the result when the owner closes
+// the vote. Votes are sent over the websocket
+#[component]
+pub fn VotingRoom() -> impl IntoView {
+    let state = expect_context::<AuthStore>();
+    let toast = use_toast().unwrap();
+    let params = use_params_map();
+    let id = move || {
+        params.with(|params| {
+            params
+                .get("id")
+                .and_then(|it| it.parse::<i32>().ok())
+                .unwrap_or_default()
+        })
+    };
+
+    let session = RwSignal::new(None::<VotingSession>);
+    let loaded = LocalResource::new(move || async move {
+        let res = get(&format!("/api/voting/{}", id())).send().await.ok()?;
+        if !res.ok() {
+            return None;
+        }
+        let data = res.json::<VotingSession>().await.ok()?;
+        session.set(Some(data.clone()));
+        Some(data)
+    });
+
+    let UseWebSocketReturn { message, send, .. } =
+        use_websocket::<String, FoodieMessageType, FromToStringCodec>("/api/ws");
+
+    Effect::new(move || match message() {
+        Some(FoodieMessageType::VotingSessionUpdate(s)) if s.id == id() => session.set(Some(s)),
+        Some(FoodieMessageType::VotingSessionClosed(s)) if s.id == id() => session.set(Some(s)),
+        // The vote may have been closed in the meantime, so the session is fetched again
+        Some(FoodieMessageType::VoteFailed { session_id, error }) if session_id == id() => {
+            toast.add(Toast {
+                ty: ToastType::Error,
+                body: error,
+                timeout: Some(Duration::from_secs(5)),
+            });
+            loaded.refetch();
+        }
+        _ => {}
+    });
+
+    let vote = Callback::new(move |recipe_id: i32| {
+        let message = FoodieMessageType::Vote {
+            session_id: id(),
+            recipe_id,
+        };
+        send(&serde_json::to_string(&message).unwrap());
+    });
+
+    let on_close = move |_| {
+        spawn_local(async move {
+            match post(&format!("/api/voting/{}/close", id())).send().await {
+                Ok(r) if r.ok() => {
+                    if let Ok(s) = r.json::<VotingSession>().await {
+                        session.set(Some(s));
+                    }
+                }
+                _ => toast.add(Toast {
+                    ty: ToastType::Error,
+                    body: "Failed to close the vote".to_string(),
+                    timeout: Some(Duration::from_secs(5)),
+                }),
+            }
+        });
+    };
+
+    view! {
+        <Transition fallback=Loading>
+            {move || {
+                loaded
+                    .get()
+                    .as_deref()
+                    .cloned()
+                    .map(|data| match data {
+                        None => NotFound.into_any(),
+                        Some(_) => {
+                            view! {
+                                <div class="mx-auto w-[80%] max-w-screen-xl flex flex-col gap-4">
+                                    {move || {
+                                        session
+                                            .get()
+                                            .map(|s| {
+                                                let is_open = s.closed_at.is_none();
+                                                let can_close = is_open && s.owner_id == state.id;
+                                                let voted = s
+                                                    .participants
+                                                    .iter()
+                                                    .filter(|it| it.has_voted)
+                                                    .count();
+                                                let status = match is_open {
+                                                    true => {
+                                                        format!(
+                                                            "{voted} of {} have voted",
+                                                            s.participants.len(),
+                                                        )
+                                                    }
+                                                    false => "The vote is closed".to_string(),
+                                                };
+                                                view! {
+                                                    <Flex
+                                                        justify=FlexJustify::SpaceBetween
+                                                        align=FlexAlign::Center
+                                                    >
+                                                        <div>
+                                                            <h1 class="text-2xl">{s.name}</h1>
+                                                            <Caption1>{status}</Caption1>
+                                                        </div>
+                                                        {can_close
+                                                            .then(|| {
+                                                                view! {
+                                                                    <Button on_click=on_close>
+                                                                        "Close the vote"
+                                                                    </Button>
+                                                                }
+                                                            })}
+                                                    </Flex>
+                                                    {s
+                                                        .candidates
+                                                        .into_iter()
+                                                        .map(|candidate| {
+                                                            view! {
+                                                                <CandidateCard
+                                                                    is_winner=s.winner_recipe_id
+                                                                        == Some(candidate.recipe_id)
+                                                                    is_own_vote=s.vote
+                                                                        == Some(candidate.recipe_id)
+                                                                    on_vote=is_open.then_some(vote)
+                                                                    candidate
+                                                                />
+                                                            }
+                                                        })
+                                                        .collect::<Vec<_>>()}
+                                                }
+                                            })
+                                    }}
+                                </div>
+                            }
+                                .into_any()
+                        }
+                    })
+            }}
+        </Transition>
+    }
+}
+
+#[component]
+fn CandidateCard(
+    candidate: VotingCandidate,
+    is_winner: bool,
+    is_own_vote: bool,
+    /// `None` when the vote is closed
+    on_vote: Option<Callback<i32>>,
+) -> impl IntoView {
+    let recipe_id = candidate.recipe_id;
+    let votes = match candidate.votes {
+        1 => "1 vote".to_string(),
+        n => format!("{n} votes"),
+    };
+    let appearance = match is_own_vote {
+        true => ButtonAppearance::Primary,
+        false => ButtonAppearance::Secondary,
+    };
+
+    view! {
+        <Card>
+            <CardHeader>
+                <a href=format!("/recipes/{recipe_id}")>
+                    <Body1>{candidate.name}</Body1>
+                </a>
+                <CardHeaderDescription slot>
+                    <Caption1>{votes}</Caption1>
+                </CardHeaderDescription>
+                <CardHeaderAction slot>
+                    {is_winner.then(|| view! { <Badge color=BadgeColor::Success>"Winner"</Badge> })}
+                    {on_vote
+                        .map(|on_vote| {
+                            view! {
+                                <Button appearance on_click=move |_| on_vote.run(recipe_id)>
+                                    "Vote"
+                                </Button>
+                            }
+                        })}
+                </CardHeaderAction>
+            </CardHeader>
+        </Card>
+    }
+}